@@ -1,5 +1,6 @@
 use linux_drm::{
     event::{DrmEvent, GenericDrmEvent},
+    format::Fourcc,
     modeset::{
         AtomicCommitFlags, CardResources, ConnectionState, ConnectorId, ConnectorState, CrtcId,
         DumbBuffer, DumbBufferRequest, ModeInfo, ObjectId, PlaneId, PropertyId,
@@ -224,12 +225,11 @@ fn prepare_output(
     let crtc_id = enc.current_crtc_id;
     let crtc = card.crtc_state(crtc_id)?;
     let mode = crtc.mode;
-    let db = card.create_dumb_buffer(DumbBufferRequest {
-        width: mode.hdisplay as u32,
-        height: mode.vdisplay as u32,
-        depth: 24,
-        bpp: 32,
-    })?;
+    let db = card.create_dumb_buffer(DumbBufferRequest::with_format(
+        mode.hdisplay as u32,
+        mode.vdisplay as u32,
+        Fourcc::XRGB8888,
+    )?)?;
 
     // We need to find the primary plane that's currently assigned to this CRTC.
     // The following is not really a correct way to do it, but it'll work for
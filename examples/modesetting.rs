@@ -154,6 +154,7 @@ fn prepare_output(
         height: mode.vdisplay as u32,
         depth: 24,
         bpp: 32,
+        format: None,
     })?;
     Ok(Output {
         conn_id: conn.id,
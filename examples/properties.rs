@@ -6,7 +6,7 @@ use std::{
 };
 
 use linux_drm::{
-    modeset::{ModeProp, ObjectId, PropertyId, PropertyType},
+    modeset::{BlobId, ModeProp, ObjectId, PropertyId, PropertyType},
     result::Error,
     Card, ClientCap, DeviceCap,
 };
@@ -153,7 +153,12 @@ fn show_property_list(
                 println!("");
             }
             PropertyType::Blob => {
-                println!("blob #{}", prop.value)
+                if prop.value == 0 {
+                    println!("no blob");
+                } else {
+                    let content = card.property_blob(BlobId(prop.value as u32))?;
+                    println!("blob #{} ({} bytes)", prop.value, content.len())
+                }
             }
             _ => println!("{}", prop.value),
         }
@@ -0,0 +1,801 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::ioctl;
+use crate::modeset::ModeInfo;
+use crate::result::Error;
+
+mod cta;
+mod displayid;
+
+pub use cta::*;
+pub use displayid::*;
+
+/// The size of each block of an EDID, including the base block and each
+/// extension block.
+pub const BLOCK_SIZE: usize = 128;
+
+const HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+
+/// A parsed Extended Display Identification Data structure, as provided by
+/// a connector's `EDID` property.
+///
+/// Use [`crate::Card::connector_edid`] to obtain this for a connector, or
+/// [`Edid::parse`] if you already have the raw bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edid {
+    raw: Vec<u8>,
+    pub version: u8,
+    pub revision: u8,
+    /// The manufacturer's three-letter PNP id, such as `*b"DEL"`.
+    pub manufacturer: [u8; 3],
+    pub product_code: u16,
+    /// The numeric serial number, which is zero if the manufacturer didn't
+    /// provide one. Many monitors provide [`Self::serial_string`] instead.
+    pub serial_number: u32,
+    /// The week of manufacture, from 1 to 54, if specified.
+    pub manufacture_week: Option<u8>,
+    /// The year of manufacture, or the model year if
+    /// [`Self::is_model_year`] is set.
+    pub manufacture_year: u16,
+    pub is_model_year: bool,
+    /// True if the display has a digital input, rather than analog.
+    pub is_digital: bool,
+    /// The physical size of the screen in centimeters, as
+    /// `(width, height)`, if specified.
+    pub screen_size_cm: Option<(u8, u8)>,
+    /// The display transfer characteristic (gamma), if specified.
+    pub gamma: Option<f32>,
+    pub color: ColorCharacteristics,
+    pub established_timings: Vec<SimpleTiming>,
+    pub standard_timings: Vec<SimpleTiming>,
+    pub detailed_timings: Vec<DetailedTiming>,
+    /// The monitor name from the display product name descriptor, if any.
+    pub monitor_name: Option<String>,
+    /// The serial number from the display product serial number descriptor,
+    /// if any.
+    pub serial_string: Option<String>,
+    /// Any unspecified text from alphanumeric data string descriptors.
+    pub text: Vec<String>,
+    pub range_limits: Option<RangeLimits>,
+    /// The number of extension blocks the base block says should follow it.
+    pub extension_count: u8,
+    /// True if the first detailed timing is the display's preferred
+    /// timing, which is always the case for EDID 1.4 and later.
+    pub first_timing_is_preferred: bool,
+}
+
+/// The chromaticity coordinates of the display's primaries and white point,
+/// in the CIE 1931 color space.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ColorCharacteristics {
+    pub red: (f64, f64),
+    pub green: (f64, f64),
+    pub blue: (f64, f64),
+    pub white: (f64, f64),
+}
+
+impl ColorCharacteristics {
+    /// The primaries and D65 white point from ITU-R BT.709, which are also
+    /// used by sRGB.
+    pub const BT709: Self = Self {
+        red: (0.64, 0.33),
+        green: (0.30, 0.60),
+        blue: (0.15, 0.06),
+        white: (0.3127, 0.3290),
+    };
+
+    /// The primaries and D65 white point from ITU-R BT.2020, which are used
+    /// for most HDR content.
+    pub const BT2020: Self = Self {
+        red: (0.708, 0.292),
+        green: (0.170, 0.797),
+        blue: (0.131, 0.046),
+        white: (0.3127, 0.3290),
+    };
+}
+
+/// A timing described only by its resolution and refresh rate, as used in
+/// the established and standard timings sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SimpleTiming {
+    pub width: u16,
+    pub height: u16,
+    pub refresh: u16,
+    pub interlaced: bool,
+    /// True if the timing uses CVT reduced blanking.
+    pub reduced_blanking: bool,
+}
+
+impl SimpleTiming {
+    const fn new(width: u16, height: u16, refresh: u16) -> Self {
+        Self {
+            width,
+            height,
+            refresh,
+            interlaced: false,
+            reduced_blanking: false,
+        }
+    }
+
+    const fn interlaced(self) -> Self {
+        Self {
+            interlaced: true,
+            ..self
+        }
+    }
+
+    const fn reduced_blanking(self) -> Self {
+        Self {
+            reduced_blanking: true,
+            ..self
+        }
+    }
+}
+
+/// A full description of a video timing, as used in 18-byte detailed timing
+/// descriptors.
+///
+/// The vertical values are for each field, and so for interlaced timings
+/// they are half of the values for a full frame. Use
+/// [`DetailedTiming::to_mode_info`] to convert to a [`ModeInfo`] using the
+/// full-frame values that the kernel expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DetailedTiming {
+    pub pixel_clock_khz: u32,
+    pub hactive: u16,
+    pub hblank: u16,
+    pub hsync_offset: u16,
+    pub hsync_width: u16,
+    pub vactive: u16,
+    pub vblank: u16,
+    pub vsync_offset: u16,
+    pub vsync_width: u16,
+    /// The physical size of the image in millimeters.
+    pub width_mm: u16,
+    pub height_mm: u16,
+    pub hborder: u8,
+    pub vborder: u8,
+    pub interlaced: bool,
+    /// The raw stereo viewing support bits (bits 6, 5, and 0 of the
+    /// flags byte), which are zero for normal display.
+    pub stereo: u8,
+    /// The raw sync signal definition bits (bits 4 through 1 of the flags
+    /// byte).
+    pub sync: u8,
+}
+
+impl DetailedTiming {
+    /// Parse an 18-byte detailed timing descriptor, returning `None` if the
+    /// descriptor is a display descriptor rather than a timing.
+    pub fn parse(raw: &[u8; 18]) -> Option<Self> {
+        let clock = u16::from_le_bytes([raw[0], raw[1]]);
+        if clock == 0 {
+            return None;
+        }
+        let hi = |b: u8, shift: u32, mask: u8| (((b >> shift) & mask) as u16) << 8;
+        Some(Self {
+            pixel_clock_khz: clock as u32 * 10,
+            hactive: raw[2] as u16 | hi(raw[4], 4, 0xf),
+            hblank: raw[3] as u16 | hi(raw[4], 0, 0xf),
+            vactive: raw[5] as u16 | hi(raw[7], 4, 0xf),
+            vblank: raw[6] as u16 | hi(raw[7], 0, 0xf),
+            hsync_offset: raw[8] as u16 | hi(raw[11], 6, 0x3),
+            hsync_width: raw[9] as u16 | hi(raw[11], 4, 0x3),
+            vsync_offset: (raw[10] >> 4) as u16 | (((raw[11] >> 2) & 0x3) as u16) << 4,
+            vsync_width: (raw[10] & 0xf) as u16 | ((raw[11] & 0x3) as u16) << 4,
+            width_mm: raw[12] as u16 | hi(raw[14], 4, 0xf),
+            height_mm: raw[13] as u16 | hi(raw[14], 0, 0xf),
+            hborder: raw[15],
+            vborder: raw[16],
+            interlaced: (raw[17] & 0x80) != 0,
+            stereo: raw[17] & 0x61,
+            sync: (raw[17] >> 1) & 0xf,
+        })
+    }
+
+    /// Returns true if the sync definition specifies a positive
+    /// horizontal sync polarity.
+    #[inline]
+    pub const fn hsync_positive(&self) -> bool {
+        (self.sync & 0x1) != 0
+    }
+
+    /// Returns true if the sync definition specifies a positive vertical
+    /// sync polarity.
+    #[inline]
+    pub const fn vsync_positive(&self) -> bool {
+        (self.sync & 0x2) != 0
+    }
+
+    /// Convert the timing into a mode that could be used with a CRTC.
+    ///
+    /// This follows the same conventions as the kernel's own EDID parser,
+    /// including setting the mode type to `DRM_MODE_TYPE_DRIVER`.
+    pub fn to_mode_info(&self) -> ModeInfo {
+        let hsync_start = self.hactive + self.hsync_offset;
+        let vsync_start = self.vactive + self.vsync_offset;
+        let mut mode = ModeInfo {
+            name: Vec::new(),
+            clock: self.pixel_clock_khz,
+            hdisplay: self.hactive,
+            hsync_start,
+            hsync_end: hsync_start + self.hsync_width,
+            htotal: self.hactive + self.hblank,
+            hskew: 0,
+            vdisplay: self.vactive,
+            vsync_start,
+            vsync_end: vsync_start + self.vsync_width,
+            vtotal: self.vactive + self.vblank,
+            vscan: 0,
+            vrefresh: 0,
+            flags: 0,
+            typ: ioctl::DRM_MODE_TYPE_DRIVER,
+        };
+        mode.flags |= if self.hsync_positive() {
+            ioctl::DRM_MODE_FLAG_PHSYNC
+        } else {
+            ioctl::DRM_MODE_FLAG_NHSYNC
+        };
+        mode.flags |= if self.vsync_positive() {
+            ioctl::DRM_MODE_FLAG_PVSYNC
+        } else {
+            ioctl::DRM_MODE_FLAG_NVSYNC
+        };
+        if self.interlaced {
+            mode.vdisplay *= 2;
+            mode.vsync_start *= 2;
+            mode.vsync_end *= 2;
+            mode.vtotal = mode.vtotal * 2 + 1;
+            mode.flags |= ioctl::DRM_MODE_FLAG_INTERLACE;
+        }
+        mode.fill_vrefresh_and_name();
+        mode
+    }
+}
+
+/// The display range limits, from the display range limits descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeLimits {
+    pub min_vfreq_hz: u16,
+    pub max_vfreq_hz: u16,
+    pub min_hfreq_khz: u16,
+    pub max_hfreq_khz: u16,
+    /// The maximum supported pixel clock, if specified.
+    pub max_pixel_clock_khz: Option<u32>,
+    pub timing_formula: RangeLimitsFormula,
+}
+
+/// Describes which timing formula, if any, the display supports for modes
+/// within its range limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeLimitsFormula {
+    DefaultGtf,
+    RangeLimitsOnly,
+    SecondaryGtf,
+    Cvt,
+    Other(u8),
+}
+
+/// Returns true if the given block has a valid checksum, meaning that all
+/// of its bytes sum to zero modulo 256.
+pub fn block_checksum_valid(block: &[u8]) -> bool {
+    block.iter().fold(0_u8, |acc, b| acc.wrapping_add(*b)) == 0
+}
+
+static ESTABLISHED_TIMINGS: [SimpleTiming; 17] = [
+    SimpleTiming::new(800, 600, 60),
+    SimpleTiming::new(800, 600, 56),
+    SimpleTiming::new(640, 480, 75),
+    SimpleTiming::new(640, 480, 72),
+    SimpleTiming::new(640, 480, 67),
+    SimpleTiming::new(640, 480, 60),
+    SimpleTiming::new(720, 400, 88),
+    SimpleTiming::new(720, 400, 70),
+    SimpleTiming::new(1280, 1024, 75),
+    SimpleTiming::new(1024, 768, 75),
+    SimpleTiming::new(1024, 768, 70),
+    SimpleTiming::new(1024, 768, 60),
+    SimpleTiming::new(1024, 768, 87).interlaced(),
+    SimpleTiming::new(832, 624, 75),
+    SimpleTiming::new(800, 600, 75),
+    SimpleTiming::new(800, 600, 72),
+    SimpleTiming::new(1152, 870, 75),
+];
+
+static ESTABLISHED_TIMINGS_III: [SimpleTiming; 48] = [
+    SimpleTiming::new(1152, 864, 75),
+    SimpleTiming::new(1024, 768, 85),
+    SimpleTiming::new(800, 600, 85),
+    SimpleTiming::new(848, 480, 60),
+    SimpleTiming::new(640, 480, 85),
+    SimpleTiming::new(720, 400, 85),
+    SimpleTiming::new(640, 400, 85),
+    SimpleTiming::new(640, 350, 85),
+    SimpleTiming::new(1280, 1024, 85),
+    SimpleTiming::new(1280, 1024, 60),
+    SimpleTiming::new(1280, 960, 85),
+    SimpleTiming::new(1280, 960, 60),
+    SimpleTiming::new(1280, 768, 85),
+    SimpleTiming::new(1280, 768, 75),
+    SimpleTiming::new(1280, 768, 60),
+    SimpleTiming::new(1280, 768, 60).reduced_blanking(),
+    SimpleTiming::new(1400, 1050, 75),
+    SimpleTiming::new(1400, 1050, 60),
+    SimpleTiming::new(1400, 1050, 60).reduced_blanking(),
+    SimpleTiming::new(1440, 900, 85),
+    SimpleTiming::new(1440, 900, 75),
+    SimpleTiming::new(1440, 900, 60),
+    SimpleTiming::new(1440, 900, 60).reduced_blanking(),
+    SimpleTiming::new(1360, 768, 60),
+    SimpleTiming::new(1600, 1200, 70),
+    SimpleTiming::new(1600, 1200, 65),
+    SimpleTiming::new(1600, 1200, 60),
+    SimpleTiming::new(1680, 1050, 85),
+    SimpleTiming::new(1680, 1050, 75),
+    SimpleTiming::new(1680, 1050, 60),
+    SimpleTiming::new(1680, 1050, 60).reduced_blanking(),
+    SimpleTiming::new(1400, 1050, 85),
+    SimpleTiming::new(1920, 1200, 60),
+    SimpleTiming::new(1920, 1200, 60).reduced_blanking(),
+    SimpleTiming::new(1856, 1392, 75),
+    SimpleTiming::new(1856, 1392, 60),
+    SimpleTiming::new(1792, 1344, 75),
+    SimpleTiming::new(1792, 1344, 60),
+    SimpleTiming::new(1600, 1200, 85),
+    SimpleTiming::new(1600, 1200, 75),
+    SimpleTiming::new(0, 0, 0),
+    SimpleTiming::new(0, 0, 0),
+    SimpleTiming::new(0, 0, 0),
+    SimpleTiming::new(0, 0, 0),
+    SimpleTiming::new(1920, 1440, 75),
+    SimpleTiming::new(1920, 1440, 60),
+    SimpleTiming::new(1920, 1200, 85),
+    SimpleTiming::new(1920, 1200, 75),
+];
+
+impl Edid {
+    /// Parse an EDID from its raw bytes, which must include at least the
+    /// base block.
+    ///
+    /// Returns [`Error::Invalid`] if the base block is truncated, doesn't
+    /// start with the EDID header, or has an incorrect checksum. Extension
+    /// blocks are not validated until they are used, because they are often
+    /// truncated or corrupt in practice even when the base block is usable.
+    pub fn parse(raw: &[u8]) -> Result<Self, Error> {
+        if raw.len() < BLOCK_SIZE || raw[0..8] != HEADER {
+            return Err(Error::Invalid);
+        }
+        let base = &raw[0..BLOCK_SIZE];
+        if !block_checksum_valid(base) {
+            return Err(Error::Invalid);
+        }
+
+        let mut owned = Vec::new();
+        owned.try_reserve_exact(raw.len())?;
+        owned.extend_from_slice(raw);
+
+        let mfg = u16::from_be_bytes([base[8], base[9]]);
+        let letter = |shift: u32| b'A' - 1 + ((mfg >> shift) & 0x1f) as u8;
+        let version = base[18];
+        let revision = base[19];
+        let is_model_year = base[16] == 0xff;
+
+        let mut ret = Self {
+            raw: owned,
+            version,
+            revision,
+            manufacturer: [letter(10), letter(5), letter(0)],
+            product_code: u16::from_le_bytes([base[10], base[11]]),
+            serial_number: u32::from_le_bytes([base[12], base[13], base[14], base[15]]),
+            manufacture_week: match base[16] {
+                0 | 0xff => None,
+                week => Some(week),
+            },
+            manufacture_year: base[17] as u16 + 1990,
+            is_model_year,
+            is_digital: (base[20] & 0x80) != 0,
+            screen_size_cm: match (base[21], base[22]) {
+                (0, _) | (_, 0) => None,
+                size => Some(size),
+            },
+            gamma: match base[23] {
+                0xff => None,
+                v => Some((v as f32 + 100.0) / 100.0),
+            },
+            color: parse_color(&base[25..35]),
+            established_timings: Vec::new(),
+            standard_timings: Vec::new(),
+            detailed_timings: Vec::new(),
+            monitor_name: None,
+            serial_string: None,
+            text: Vec::new(),
+            range_limits: None,
+            extension_count: base[126],
+            first_timing_is_preferred: (base[24] & 0x02) != 0 || (version, revision) >= (1, 4),
+        };
+
+        let established = u32::from_be_bytes([base[35], base[36], base[37], 0]) >> 8;
+        push_bits(&mut ret.established_timings, &ESTABLISHED_TIMINGS, |i| {
+            // The table above is in ascending bit order for each of bytes
+            // 35 and 36 in turn, followed by the one bit that's defined in
+            // byte 37.
+            let bit = match i {
+                0..=7 => 16 + i,
+                8..=15 => i,
+                _ => 7,
+            };
+            (established & (1 << bit)) != 0
+        });
+
+        for pair in base[38..54].chunks_exact(2) {
+            if let Some(timing) = ret.parse_standard_timing(pair[0], pair[1]) {
+                ret.standard_timings.push(timing);
+            }
+        }
+
+        for offset in [54, 72, 90, 108] {
+            let desc: &[u8; 18] = base[offset..offset + 18].try_into().unwrap();
+            ret.parse_descriptor(desc);
+        }
+
+        Ok(ret)
+    }
+
+    /// Returns the raw bytes that this EDID was parsed from, including any
+    /// extension blocks.
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+
+    /// Returns the manufacturer's PNP id as a string.
+    pub fn manufacturer_id(&self) -> &str {
+        // The id is always built from letters in the range '@'..='_', so
+        // it's always valid UTF-8.
+        core::str::from_utf8(&self.manufacturer).unwrap_or("???")
+    }
+
+    /// Returns the extension blocks that follow the base block, skipping any
+    /// that are truncated or have an incorrect checksum.
+    pub fn extension_blocks(&self) -> impl Iterator<Item = &[u8; BLOCK_SIZE]> + '_ {
+        self.raw[BLOCK_SIZE..]
+            .chunks_exact(BLOCK_SIZE)
+            .take(self.extension_count as usize)
+            .filter(|block| block_checksum_valid(block))
+            .map(|block| block.try_into().unwrap())
+    }
+
+    /// Returns the parsed CTA-861 extension blocks, skipping any that are
+    /// malformed.
+    pub fn cta_extensions(&self) -> impl Iterator<Item = CtaExtension> + '_ {
+        self.extension_blocks()
+            .filter(|block| block[0] == CTA_EXTENSION_TAG)
+            .filter_map(|block| CtaExtension::parse(block).ok())
+    }
+
+    /// Returns the DisplayID data from the EDID's DisplayID extension
+    /// blocks, if there are any.
+    ///
+    /// The sections from all of the blocks are combined, because displays
+    /// with a large number of timings often spread them across several
+    /// blocks. Malformed blocks are skipped.
+    pub fn displayid(&self) -> Option<DisplayId> {
+        let mut ret = DisplayId::default();
+        for block in self.extension_blocks() {
+            if block[0] == DISPLAYID_EXTENSION_TAG {
+                // Skip malformed blocks, keeping whatever the others had.
+                let _ = ret.merge_edid_extension(block);
+            }
+        }
+        (ret.version != 0).then_some(ret)
+    }
+
+    /// Returns the HDR capabilities from the first CTA-861 extension block
+    /// that has an HDR static metadata data block, if any.
+    pub fn hdr_static_metadata(&self) -> Option<HdrStaticMetadata> {
+        self.cta_extensions()
+            .find_map(|cta| cta.hdr_static_metadata)
+    }
+
+    /// Returns the variable refresh rate range from the first CTA-861
+    /// extension block that has an HDMI Forum data block declaring VRR
+    /// support, if any.
+    pub fn hdmi_vrr_range(&self) -> Option<VrrRange> {
+        self.cta_extensions()
+            .find_map(|cta| cta.hdmi_forum.and_then(|hf| hf.vrr))
+    }
+
+    /// Returns the physical size of the screen in millimeters, as
+    /// `(width, height)`.
+    ///
+    /// This prefers the more precise size from the first detailed timing,
+    /// falling back on the screen size in centimeters from the base block.
+    pub fn physical_size_mm(&self) -> Option<(u32, u32)> {
+        if let Some(t) = self.detailed_timings.first() {
+            if t.width_mm != 0 && t.height_mm != 0 {
+                return Some((t.width_mm as u32, t.height_mm as u32));
+            }
+        }
+        self.screen_size_cm
+            .map(|(w, h)| (w as u32 * 10, h as u32 * 10))
+    }
+
+    /// Returns the display's preferred timing, if it has one.
+    pub fn preferred_timing(&self) -> Option<&DetailedTiming> {
+        if !self.first_timing_is_preferred {
+            return None;
+        }
+        self.detailed_timings.first()
+    }
+
+    /// Converts all of the detailed timings from the base block into modes,
+    /// marking the preferred timing with `DRM_MODE_TYPE_PREFERRED`.
+    pub fn detailed_modes(&self) -> Vec<ModeInfo> {
+        let mut ret: Vec<ModeInfo> = self
+            .detailed_timings
+            .iter()
+            .map(DetailedTiming::to_mode_info)
+            .collect();
+        if self.first_timing_is_preferred {
+            if let Some(mode) = ret.first_mut() {
+                mode.typ |= ioctl::DRM_MODE_TYPE_PREFERRED;
+            }
+        }
+        ret
+    }
+
+    fn parse_standard_timing(&self, b0: u8, b1: u8) -> Option<SimpleTiming> {
+        if (b0 == 0x01 && b1 == 0x01) || b0 == 0x00 {
+            return None;
+        }
+        let width = (b0 as u16 + 31) * 8;
+        let height = match b1 >> 6 {
+            0 if (self.version, self.revision) < (1, 3) => width,
+            0 => width * 10 / 16,
+            1 => width * 3 / 4,
+            2 => width * 4 / 5,
+            _ => width * 9 / 16,
+        };
+        Some(SimpleTiming::new(width, height, (b1 & 0x3f) as u16 + 60))
+    }
+
+    fn parse_descriptor(&mut self, desc: &[u8; 18]) {
+        if let Some(timing) = DetailedTiming::parse(desc) {
+            self.detailed_timings.push(timing);
+            return;
+        }
+        let data = &desc[5..18];
+        match desc[3] {
+            0xff => self.serial_string = Some(descriptor_text(data)),
+            0xfe => self.text.push(descriptor_text(data)),
+            0xfc => self.monitor_name = Some(descriptor_text(data)),
+            0xfd => self.range_limits = Some(parse_range_limits(desc)),
+            0xfa => {
+                for pair in desc[5..17].chunks_exact(2) {
+                    if let Some(timing) = self.parse_standard_timing(pair[0], pair[1]) {
+                        self.standard_timings.push(timing);
+                    }
+                }
+            }
+            0xf7 => {
+                let bits = &desc[6..12];
+                push_bits(
+                    &mut self.established_timings,
+                    &ESTABLISHED_TIMINGS_III,
+                    |i| (bits[i / 8] & (1 << (i % 8))) != 0,
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+fn push_bits(into: &mut Vec<SimpleTiming>, table: &[SimpleTiming], is_set: impl Fn(usize) -> bool) {
+    for (i, timing) in table.iter().enumerate() {
+        if timing.width != 0 && is_set(i) {
+            into.push(*timing);
+        }
+    }
+}
+
+fn descriptor_text(data: &[u8]) -> String {
+    let text = data.split(|b| *b == 0x0a).next().unwrap_or(data);
+    let text: String = text.iter().map(|b| *b as char).collect();
+    String::from(text.trim_end())
+}
+
+fn parse_color(raw: &[u8]) -> ColorCharacteristics {
+    // Each coordinate is a 10-bit fraction whose two least significant bits
+    // are packed into the first two bytes.
+    let coord = |hi: u8, lo_byte: u8, lo_shift: u32| {
+        let v = ((hi as u16) << 2) | ((lo_byte >> lo_shift) & 0x3) as u16;
+        v as f64 / 1024.0
+    };
+    ColorCharacteristics {
+        red: (coord(raw[2], raw[0], 6), coord(raw[3], raw[0], 4)),
+        green: (coord(raw[4], raw[0], 2), coord(raw[5], raw[0], 0)),
+        blue: (coord(raw[6], raw[1], 6), coord(raw[7], raw[1], 4)),
+        white: (coord(raw[8], raw[1], 2), coord(raw[9], raw[1], 0)),
+    }
+}
+
+fn parse_range_limits(desc: &[u8; 18]) -> RangeLimits {
+    let offsets = desc[4];
+    let add = |v: u8, has_offset: bool| v as u16 + if has_offset { 255 } else { 0 };
+    let max_v_offset = (offsets & 0x02) != 0;
+    let max_h_offset = (offsets & 0x08) != 0;
+    let timing_formula = match desc[10] {
+        0x00 => RangeLimitsFormula::DefaultGtf,
+        0x01 => RangeLimitsFormula::RangeLimitsOnly,
+        0x02 => RangeLimitsFormula::SecondaryGtf,
+        0x04 => RangeLimitsFormula::Cvt,
+        v => RangeLimitsFormula::Other(v),
+    };
+    let mut max_pixel_clock_khz = match desc[9] {
+        0 => None,
+        v => Some(v as u32 * 10_000),
+    };
+    if let (RangeLimitsFormula::Cvt, Some(clock)) = (timing_formula, max_pixel_clock_khz) {
+        // CVT support descriptors can refine the maximum pixel clock in
+        // units of 0.25MHz below the coarse value.
+        max_pixel_clock_khz = Some(clock.saturating_sub((desc[12] >> 2) as u32 * 250));
+    }
+    RangeLimits {
+        min_vfreq_hz: add(desc[5], max_v_offset && (offsets & 0x01) != 0),
+        max_vfreq_hz: add(desc[6], max_v_offset),
+        min_hfreq_khz: add(desc[7], max_h_offset && (offsets & 0x04) != 0),
+        max_hfreq_khz: add(desc[8], max_h_offset),
+        max_pixel_clock_khz,
+        timing_formula,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A two-block EDID for a 4K monitor with a CTA-861 extension, built
+    /// from descriptors and data blocks as they appear in real displays.
+    const MONITOR_4K: &[u8] = include_bytes!("../testdata/edid/4k-monitor.bin");
+
+    #[test]
+    fn parse_base_block() {
+        let edid = Edid::parse(MONITOR_4K).unwrap();
+        assert_eq!(edid.manufacturer_id(), "DEL");
+        assert_eq!(edid.product_code, 0x40f0);
+        assert_eq!(edid.serial_number, 0x3032344c);
+        assert_eq!(edid.manufacture_week, Some(12));
+        assert_eq!((edid.manufacture_year, edid.is_model_year), (2020, false));
+        assert_eq!((edid.version, edid.revision), (1, 3));
+        assert!(edid.is_digital);
+        assert_eq!(edid.screen_size_cm, Some((60, 34)));
+        assert_eq!(edid.gamma, Some(2.2));
+        assert_eq!(edid.color.red, (655.0 / 1024.0, 338.0 / 1024.0));
+        assert_eq!(edid.color.green, (307.0 / 1024.0, 614.0 / 1024.0));
+        assert_eq!(edid.color.blue, (154.0 / 1024.0, 61.0 / 1024.0));
+        assert_eq!(edid.color.white, (320.0 / 1024.0, 337.0 / 1024.0));
+        assert_eq!(edid.monitor_name.as_deref(), Some("LDRM 4K UHD"));
+        assert_eq!(edid.serial_string.as_deref(), Some("F8MBP13"));
+        assert_eq!(edid.extension_count, 1);
+        assert_eq!(edid.extension_blocks().count(), 1);
+        assert_eq!(edid.physical_size_mm(), Some((600, 340)));
+        assert_eq!(
+            edid.range_limits,
+            Some(RangeLimits {
+                min_vfreq_hz: 24,
+                max_vfreq_hz: 75,
+                min_hfreq_khz: 30,
+                max_hfreq_khz: 140,
+                max_pixel_clock_khz: Some(600_000),
+                timing_formula: RangeLimitsFormula::RangeLimitsOnly,
+            })
+        );
+    }
+
+    #[test]
+    fn established_timings() {
+        let edid = Edid::parse(MONITOR_4K).unwrap();
+        let timings: Vec<_> = edid
+            .established_timings
+            .iter()
+            .map(|t| (t.width, t.height, t.refresh))
+            .collect();
+        assert_eq!(
+            timings,
+            [
+                (800, 600, 60),
+                (640, 480, 75),
+                (640, 480, 60),
+                (720, 400, 70),
+                (1280, 1024, 75),
+                (1024, 768, 75),
+                (1024, 768, 60),
+                (800, 600, 75),
+                (1152, 870, 75),
+            ]
+        );
+        assert!(edid.established_timings.iter().all(|t| !t.interlaced));
+    }
+
+    #[test]
+    fn standard_timings() {
+        let edid = Edid::parse(MONITOR_4K).unwrap();
+        let timings: Vec<_> = edid
+            .standard_timings
+            .iter()
+            .map(|t| (t.width, t.height, t.refresh))
+            .collect();
+        // The aspect ratio code zero means 16:10 in EDID 1.3.
+        assert_eq!(
+            timings,
+            [
+                (1920, 1080, 60),
+                (1280, 1024, 60),
+                (1280, 720, 60),
+                (1600, 1200, 60),
+                (1680, 1050, 60),
+                (1440, 900, 60),
+                (1152, 864, 75),
+            ]
+        );
+    }
+
+    #[test]
+    fn preferred_detailed_timing() {
+        let edid = Edid::parse(MONITOR_4K).unwrap();
+        assert_eq!(edid.detailed_timings.len(), 1);
+        let timing = edid.preferred_timing().unwrap();
+        assert_eq!((timing.width_mm, timing.height_mm), (600, 340));
+        assert!(timing.hsync_positive() && timing.vsync_positive());
+
+        let modes = edid.detailed_modes();
+        assert_eq!(modes.len(), 1);
+        let mode = &modes[0];
+        // The same timings as CTA-861 VIC 97, 3840x2160p@60.
+        assert!(mode.same_timings(&vic_mode(97).unwrap()));
+        assert_eq!(mode.name, b"3840x2160");
+        assert_eq!(mode.vrefresh, 60);
+        assert!(mode.is_preferred());
+    }
+
+    #[test]
+    fn interlaced_detailed_timing() {
+        // The 1920x1080i@60 descriptor that appears in many TVs' EDIDs.
+        let raw = [
+            0x01, 0x1d, 0x80, 0x18, 0x71, 0x1c, 0x16, 0x20, 0x58, 0x2c, 0x25, 0x00, 0xc4, 0x8e,
+            0x21, 0x00, 0x00, 0x9e,
+        ];
+        let timing = DetailedTiming::parse(&raw).unwrap();
+        // The vertical values in the descriptor are for each field.
+        assert_eq!((timing.vactive, timing.vblank), (540, 22));
+        assert!(timing.interlaced);
+
+        let mode = timing.to_mode_info();
+        assert!(mode.is_interlaced());
+        assert_eq!(
+            (mode.hdisplay, mode.hsync_start, mode.hsync_end, mode.htotal),
+            (1920, 2008, 2052, 2200)
+        );
+        assert_eq!(
+            (mode.vdisplay, mode.vsync_start, mode.vsync_end, mode.vtotal),
+            (1080, 1084, 1094, 1125)
+        );
+        assert!(mode.same_timings(&vic_mode(5).unwrap()));
+        assert_eq!(mode.name, b"1920x1080i");
+        assert_eq!(mode.vrefresh, 60);
+    }
+
+    #[test]
+    fn invalid_base_block() {
+        let mut raw = MONITOR_4K.to_vec();
+        raw[20] ^= 0x01;
+        assert_eq!(Edid::parse(&raw), Err(Error::Invalid));
+        assert_eq!(Edid::parse(&MONITOR_4K[..127]), Err(Error::Invalid));
+
+        // A corrupt extension block doesn't prevent using the base block.
+        let mut raw = MONITOR_4K.to_vec();
+        raw[BLOCK_SIZE + 20] ^= 0x01;
+        let edid = Edid::parse(&raw).unwrap();
+        assert_eq!(edid.extension_blocks().count(), 0);
+    }
+}
@@ -0,0 +1,1942 @@
+use alloc::vec::Vec;
+
+use super::{block_checksum_valid, DetailedTiming, BLOCK_SIZE};
+use crate::ioctl;
+use crate::modeset::ModeInfo;
+use crate::result::Error;
+
+/// The tag byte that identifies a CTA-861 extension block.
+pub const CTA_EXTENSION_TAG: u8 = 0x02;
+
+/// A parsed CTA-861 extension block, as used by most HDMI sinks to describe
+/// their video, audio, and HDR capabilities.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CtaExtension {
+    pub revision: u8,
+    /// True if the sink underscans IT video formats by default.
+    pub underscan: bool,
+    /// True if the sink supports basic audio.
+    pub basic_audio: bool,
+    pub ycbcr444: bool,
+    pub ycbcr422: bool,
+    /// The number of detailed timings that describe native formats.
+    pub native_format_count: u8,
+    /// The short video descriptors from all video data blocks.
+    pub video: Vec<ShortVideoDescriptor>,
+    /// The short audio descriptors from all audio data blocks.
+    pub audio: Vec<ShortAudioDescriptor>,
+    pub speaker_allocation: Option<SpeakerAllocation>,
+    pub hdmi: Option<HdmiVsdb>,
+    pub hdmi_forum: Option<HdmiForumVsdb>,
+    pub colorimetry: Option<Colorimetry>,
+    pub hdr_static_metadata: Option<HdrStaticMetadata>,
+    pub detailed_timings: Vec<DetailedTiming>,
+}
+
+/// A reference to one of the standard video formats defined by CTA-861,
+/// from a video data block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShortVideoDescriptor {
+    /// The Video Identification Code.
+    pub vic: u8,
+    /// True if the sink considers this to be one of its native formats.
+    pub native: bool,
+}
+
+impl ShortVideoDescriptor {
+    fn parse(raw: u8) -> Option<Self> {
+        match raw {
+            0 | 128 | 254 | 255 => None,
+            129..=192 => Some(Self {
+                vic: raw & 0x7f,
+                native: true,
+            }),
+            _ => Some(Self {
+                vic: raw,
+                native: false,
+            }),
+        }
+    }
+
+    /// Returns the mode for this descriptor's video format, if this
+    /// library knows the format.
+    pub fn mode(&self) -> Option<ModeInfo> {
+        vic_mode(self.vic)
+    }
+
+    /// Returns the picture aspect ratio for this descriptor's video format,
+    /// if this library knows the format.
+    pub fn aspect_ratio(&self) -> Option<(u16, u16)> {
+        vic_aspect_ratio(self.vic)
+    }
+}
+
+/// The audio formats that can appear in a [`ShortAudioDescriptor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioFormat {
+    Lpcm,
+    Ac3,
+    Mpeg1,
+    Mp3,
+    Mpeg2,
+    AacLc,
+    Dts,
+    Atrac,
+    OneBitAudio,
+    EnhancedAc3,
+    DtsHd,
+    Mat,
+    Dst,
+    WmaPro,
+    /// A format identified by an extension type code.
+    Extended(u8),
+    Other(u8),
+}
+
+/// Describes one audio format that the sink supports, from an audio data
+/// block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShortAudioDescriptor {
+    pub format: AudioFormat,
+    pub max_channels: u8,
+    /// Bitmask of supported sample rates, where bits 0 through 6 represent
+    /// 32, 44.1, 48, 88.2, 96, 176.4 and 192kHz respectively.
+    pub sample_rates: u8,
+    /// The format-dependent third byte of the descriptor. For LPCM this is
+    /// a bitmask of supported sample sizes; see
+    /// [`Self::lpcm_bit_depths`].
+    pub detail: u8,
+}
+
+const SAMPLE_RATES_HZ: [u32; 7] = [32_000, 44_100, 48_000, 88_200, 96_000, 176_400, 192_000];
+
+impl ShortAudioDescriptor {
+    fn parse(raw: &[u8]) -> Self {
+        let code = (raw[0] >> 3) & 0xf;
+        let format = match code {
+            1 => AudioFormat::Lpcm,
+            2 => AudioFormat::Ac3,
+            3 => AudioFormat::Mpeg1,
+            4 => AudioFormat::Mp3,
+            5 => AudioFormat::Mpeg2,
+            6 => AudioFormat::AacLc,
+            7 => AudioFormat::Dts,
+            8 => AudioFormat::Atrac,
+            9 => AudioFormat::OneBitAudio,
+            10 => AudioFormat::EnhancedAc3,
+            11 => AudioFormat::DtsHd,
+            12 => AudioFormat::Mat,
+            13 => AudioFormat::Dst,
+            14 => AudioFormat::WmaPro,
+            15 => AudioFormat::Extended(raw[2] >> 3),
+            _ => AudioFormat::Other(code),
+        };
+        Self {
+            format,
+            max_channels: (raw[0] & 0x7) + 1,
+            sample_rates: raw[1] & 0x7f,
+            detail: raw[2],
+        }
+    }
+
+    /// Returns the supported sample rates in hertz.
+    pub fn sample_rates_hz(&self) -> impl Iterator<Item = u32> + '_ {
+        SAMPLE_RATES_HZ
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| (self.sample_rates & (1 << i)) != 0)
+            .map(|(_, rate)| *rate)
+    }
+
+    /// Returns the supported sample sizes in bits, for LPCM formats.
+    pub fn lpcm_bit_depths(&self) -> impl Iterator<Item = u8> + '_ {
+        let mask = if self.format == AudioFormat::Lpcm {
+            self.detail & 0x7
+        } else {
+            0
+        };
+        [16, 20, 24]
+            .into_iter()
+            .enumerate()
+            .filter(move |(i, _)| (mask & (1 << i)) != 0)
+            .map(|(_, bits)| bits)
+    }
+
+    /// Returns the maximum bit rate in kilobits per second, for the
+    /// compressed formats that specify one.
+    pub fn max_bitrate_kbps(&self) -> Option<u32> {
+        match self.format {
+            AudioFormat::Ac3
+            | AudioFormat::Mpeg1
+            | AudioFormat::Mp3
+            | AudioFormat::Mpeg2
+            | AudioFormat::AacLc
+            | AudioFormat::Dts
+            | AudioFormat::Atrac => Some(self.detail as u32 * 8),
+            _ => None,
+        }
+    }
+}
+
+/// The speakers that are present, from a speaker allocation data block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct SpeakerAllocation(pub u32);
+
+impl SpeakerAllocation {
+    /// Front left and front right.
+    pub const FL_FR: Self = Self(1 << 0);
+    /// Low frequency effects.
+    pub const LFE1: Self = Self(1 << 1);
+    /// Front center.
+    pub const FC: Self = Self(1 << 2);
+    /// Back left and back right.
+    pub const BL_BR: Self = Self(1 << 3);
+    /// Back center.
+    pub const BC: Self = Self(1 << 4);
+    /// Front left center and front right center.
+    pub const FLC_FRC: Self = Self(1 << 5);
+    /// Rear left center and rear right center.
+    pub const RLC_RRC: Self = Self(1 << 6);
+    /// Front left wide and front right wide.
+    pub const FLW_FRW: Self = Self(1 << 7);
+    /// Top front left and top front right.
+    pub const TPFL_TPFR: Self = Self(1 << 8);
+    /// Top center.
+    pub const TPC: Self = Self(1 << 9);
+    /// Top front center.
+    pub const TPFC: Self = Self(1 << 10);
+    /// Left surround and right surround.
+    pub const LS_RS: Self = Self(1 << 11);
+    /// Second low frequency effects.
+    pub const LFE2: Self = Self(1 << 12);
+    /// Top back center.
+    pub const TPBC: Self = Self(1 << 13);
+    /// Side indirect left and side indirect right.
+    pub const SIL_SIR: Self = Self(1 << 14);
+    /// Top side left and top side right.
+    pub const TPSIL_TPSIR: Self = Self(1 << 15);
+    /// Top back left and top back right.
+    pub const TPBL_TPBR: Self = Self(1 << 16);
+    /// Bottom front center.
+    pub const BTFC: Self = Self(1 << 17);
+    /// Bottom front left and bottom front right.
+    pub const BTFL_BTFR: Self = Self(1 << 18);
+    /// Top left surround and top right surround.
+    pub const TPLS_TPRS: Self = Self(1 << 19);
+
+    #[inline(always)]
+    pub const fn contains(self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+}
+
+/// The HDMI vendor-specific data block, as defined by HDMI 1.4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HdmiVsdb {
+    /// The CEC physical address of the sink, as the four components of
+    /// `a.b.c.d`.
+    pub physical_address: [u8; 4],
+    pub supports_ai: bool,
+    pub deep_color_30: bool,
+    pub deep_color_36: bool,
+    pub deep_color_48: bool,
+    /// True if deep color is also supported in YCbCr 4:4:4.
+    pub deep_color_y444: bool,
+    pub dvi_dual: bool,
+    /// The maximum TMDS clock rate, if specified.
+    pub max_tmds_clock_khz: Option<u32>,
+}
+
+impl HdmiVsdb {
+    const OUI: [u8; 3] = [0x03, 0x0c, 0x00];
+
+    fn parse(payload: &[u8]) -> Option<Self> {
+        if payload.len() < 5 {
+            return None;
+        }
+        let flags = payload.get(5).copied().unwrap_or(0);
+        Some(Self {
+            physical_address: [
+                payload[3] >> 4,
+                payload[3] & 0xf,
+                payload[4] >> 4,
+                payload[4] & 0xf,
+            ],
+            supports_ai: (flags & 0x80) != 0,
+            deep_color_48: (flags & 0x40) != 0,
+            deep_color_36: (flags & 0x20) != 0,
+            deep_color_30: (flags & 0x10) != 0,
+            deep_color_y444: (flags & 0x08) != 0,
+            dvi_dual: (flags & 0x01) != 0,
+            max_tmds_clock_khz: match payload.get(6) {
+                None | Some(0) => None,
+                Some(v) => Some(*v as u32 * 5000),
+            },
+        })
+    }
+}
+
+/// The HDMI Forum vendor-specific data block (or the equivalent sink
+/// capability data structure data block), as defined by HDMI 2.0 and 2.1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HdmiForumVsdb {
+    pub version: u8,
+    /// The maximum TMDS character rate, if it's above 340MHz.
+    pub max_tmds_character_rate_khz: Option<u32>,
+    pub scdc_present: bool,
+    pub scdc_read_request: bool,
+    /// True if the sink supports scrambling for TMDS character rates at or
+    /// below 340MHz.
+    pub lte_340mcsc_scramble: bool,
+    pub deep_color_420_30: bool,
+    pub deep_color_420_36: bool,
+    pub deep_color_420_48: bool,
+    /// The raw maximum fixed rate link capability, where zero means that
+    /// FRL isn't supported.
+    pub max_frl_rate: u8,
+    /// Auto low-latency mode.
+    pub allm: bool,
+    /// Fast vactive.
+    pub fva: bool,
+    pub cinema_vrr: bool,
+    /// Quick media switching.
+    pub qms: bool,
+    /// The supported variable refresh rate range, if VRR is supported.
+    pub vrr: Option<VrrRange>,
+}
+
+/// A range of supported refresh rates for variable refresh rate operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VrrRange {
+    pub min_hz: u16,
+    /// The maximum refresh rate, or `None` if the maximum is the refresh
+    /// rate of whichever video format is in use.
+    pub max_hz: Option<u16>,
+}
+
+impl HdmiForumVsdb {
+    const OUI: [u8; 3] = [0xd8, 0x5d, 0xc4];
+
+    /// Parse the payload of either the vendor-specific data block or the
+    /// sink capability data structure block, both of which place the
+    /// version number at offset 3.
+    fn parse(payload: &[u8]) -> Option<Self> {
+        if payload.len() < 6 {
+            return None;
+        }
+        let byte = |i: usize| payload.get(i).copied().unwrap_or(0);
+        let vrr_min = (byte(8) & 0x3f) as u16;
+        let vrr_max = (((byte(8) >> 6) as u16) << 8) | byte(9) as u16;
+        Some(Self {
+            version: byte(3),
+            max_tmds_character_rate_khz: match byte(4) {
+                0 => None,
+                v => Some(v as u32 * 5000),
+            },
+            scdc_present: (byte(5) & 0x80) != 0,
+            scdc_read_request: (byte(5) & 0x40) != 0,
+            lte_340mcsc_scramble: (byte(5) & 0x08) != 0,
+            max_frl_rate: byte(6) >> 4,
+            deep_color_420_48: (byte(6) & 0x04) != 0,
+            deep_color_420_36: (byte(6) & 0x02) != 0,
+            deep_color_420_30: (byte(6) & 0x01) != 0,
+            qms: (byte(7) & 0x40) != 0,
+            cinema_vrr: (byte(7) & 0x10) != 0,
+            fva: (byte(7) & 0x04) != 0,
+            allm: (byte(7) & 0x02) != 0,
+            vrr: match (vrr_min, vrr_max) {
+                (0, _) => None,
+                (min_hz, 0) => Some(VrrRange {
+                    min_hz,
+                    max_hz: None,
+                }),
+                (min_hz, max_hz) => Some(VrrRange {
+                    min_hz,
+                    max_hz: Some(max_hz),
+                }),
+            },
+        })
+    }
+}
+
+/// The colorimetry standards that the sink supports, from a colorimetry
+/// data block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Colorimetry(pub u16);
+
+impl Colorimetry {
+    pub const XVYCC_601: Self = Self(1 << 0);
+    pub const XVYCC_709: Self = Self(1 << 1);
+    pub const SYCC_601: Self = Self(1 << 2);
+    pub const OPYCC_601: Self = Self(1 << 3);
+    pub const OPRGB: Self = Self(1 << 4);
+    pub const BT2020_CYCC: Self = Self(1 << 5);
+    pub const BT2020_YCC: Self = Self(1 << 6);
+    pub const BT2020_RGB: Self = Self(1 << 7);
+    pub const ICTCP: Self = Self(1 << 13);
+    pub const ST2113_RGB: Self = Self(1 << 14);
+    pub const DCI_P3: Self = Self(1 << 15);
+
+    #[inline(always)]
+    pub const fn contains(self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+
+    /// Returns the bitmask of supported gamut metadata profiles, MD0
+    /// through MD3.
+    #[inline(always)]
+    pub const fn metadata_profiles(self) -> u8 {
+        ((self.0 >> 8) & 0xf) as u8
+    }
+}
+
+/// The sink's HDR capabilities, from an HDR static metadata data block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HdrStaticMetadata {
+    /// Bitmask of supported electro-optical transfer functions. See the
+    /// `EOTF_` constants.
+    pub eotfs: u8,
+    /// Bitmask of supported static metadata descriptor types, where bit 0
+    /// represents Static Metadata Type 1.
+    pub descriptors: u8,
+    /// The raw desired content max luminance code value, if specified.
+    pub max_luminance_code: Option<u8>,
+    /// The raw desired content max frame-average luminance code value, if
+    /// specified.
+    pub max_frame_avg_luminance_code: Option<u8>,
+    /// The raw desired content min luminance code value, if specified.
+    pub min_luminance_code: Option<u8>,
+}
+
+impl HdrStaticMetadata {
+    pub const EOTF_TRADITIONAL_SDR: u8 = 1 << 0;
+    pub const EOTF_TRADITIONAL_HDR: u8 = 1 << 1;
+    /// SMPTE ST 2084, also known as "PQ".
+    pub const EOTF_ST2084: u8 = 1 << 2;
+    /// Hybrid Log-Gamma.
+    pub const EOTF_HLG: u8 = 1 << 3;
+
+    fn parse(payload: &[u8]) -> Option<Self> {
+        if payload.len() < 2 {
+            return None;
+        }
+        let code = |i: usize| payload.get(i).copied().filter(|v| *v != 0);
+        Some(Self {
+            eotfs: payload[0] & 0x3f,
+            descriptors: payload[1],
+            max_luminance_code: code(2),
+            max_frame_avg_luminance_code: code(3),
+            min_luminance_code: code(4),
+        })
+    }
+
+    #[inline(always)]
+    pub const fn supports_eotf(&self, eotf: u8) -> bool {
+        (self.eotfs & eotf) != 0
+    }
+
+    /// Returns the desired content max luminance in cd/m², if specified.
+    pub fn max_luminance(&self) -> Option<f64> {
+        self.max_luminance_code.map(luminance_from_code)
+    }
+
+    /// Returns the desired content max frame-average luminance in cd/m²,
+    /// if specified.
+    pub fn max_frame_avg_luminance(&self) -> Option<f64> {
+        self.max_frame_avg_luminance_code.map(luminance_from_code)
+    }
+
+    /// Returns the desired content min luminance in cd/m², if specified.
+    ///
+    /// The minimum is defined relative to the max luminance, so this
+    /// returns `None` if the max luminance isn't specified either.
+    pub fn min_luminance(&self) -> Option<f64> {
+        let max = self.max_luminance()?;
+        let cv = self.min_luminance_code? as f64 / 255.0;
+        Some(max * cv * cv / 100.0)
+    }
+}
+
+/// Convert a luminance code value to cd/m², as `50 * 2^(cv / 32)`.
+fn luminance_from_code(cv: u8) -> f64 {
+    50.0 * crate::math::exp2(cv as f64 / 32.0)
+}
+
+impl CtaExtension {
+    /// Parse a CTA-861 extension block.
+    ///
+    /// Returns [`Error::Invalid`] if the block is not a CTA extension block
+    /// or has an incorrect checksum. Data blocks that this library doesn't
+    /// understand are ignored.
+    pub fn parse(block: &[u8; BLOCK_SIZE]) -> Result<Self, Error> {
+        if block[0] != CTA_EXTENSION_TAG || !block_checksum_valid(block) {
+            return Err(Error::Invalid);
+        }
+        let revision = block[1];
+        let dtd_start = (block[2] as usize).min(BLOCK_SIZE - 1);
+        let mut ret = Self {
+            revision,
+            underscan: (block[3] & 0x80) != 0,
+            basic_audio: (block[3] & 0x40) != 0,
+            ycbcr444: (block[3] & 0x20) != 0,
+            ycbcr422: (block[3] & 0x10) != 0,
+            native_format_count: block[3] & 0x0f,
+            video: Vec::new(),
+            audio: Vec::new(),
+            speaker_allocation: None,
+            hdmi: None,
+            hdmi_forum: None,
+            colorimetry: None,
+            hdr_static_metadata: None,
+            detailed_timings: Vec::new(),
+        };
+
+        // Revision 1 extensions have no data block collection.
+        if revision >= 3 && dtd_start >= 4 {
+            let mut data = &block[4..dtd_start];
+            while let Some((header, rest)) = data.split_first() {
+                let len = (header & 0x1f) as usize;
+                if len > rest.len() {
+                    break;
+                }
+                let (payload, rest) = rest.split_at(len);
+                ret.parse_data_block(header >> 5, payload);
+                data = rest;
+            }
+        }
+
+        // Offsets 1 to 3 are within the header and so are malformed, and
+        // zero means there are no detailed timings at all.
+        if dtd_start >= 4 {
+            for desc in block[dtd_start..BLOCK_SIZE - 1].chunks_exact(18) {
+                match DetailedTiming::parse(desc.try_into().unwrap()) {
+                    Some(timing) => ret.detailed_timings.push(timing),
+                    None => break,
+                }
+            }
+        }
+
+        Ok(ret)
+    }
+
+    fn parse_data_block(&mut self, tag: u8, payload: &[u8]) {
+        match tag {
+            1 => {
+                for sad in payload.chunks_exact(3) {
+                    self.audio.push(ShortAudioDescriptor::parse(sad));
+                }
+            }
+            2 => {
+                self.video.extend(
+                    payload
+                        .iter()
+                        .filter_map(|v| ShortVideoDescriptor::parse(*v)),
+                );
+            }
+            3 if payload.len() >= 3 => {
+                let oui = &payload[0..3];
+                if oui == HdmiVsdb::OUI {
+                    self.hdmi = HdmiVsdb::parse(payload);
+                } else if oui == HdmiForumVsdb::OUI {
+                    self.hdmi_forum = HdmiForumVsdb::parse(payload);
+                }
+            }
+            4 if payload.len() >= 3 => {
+                let v = u32::from_le_bytes([payload[0], payload[1], payload[2], 0]);
+                self.speaker_allocation = Some(SpeakerAllocation(v));
+            }
+            7 => {
+                let Some((ext_tag, ext_payload)) = payload.split_first() else {
+                    return;
+                };
+                match ext_tag {
+                    5 if ext_payload.len() >= 2 => {
+                        let v = u16::from_le_bytes([ext_payload[0], ext_payload[1]]);
+                        self.colorimetry = Some(Colorimetry(v));
+                    }
+                    6 => self.hdr_static_metadata = HdrStaticMetadata::parse(ext_payload),
+                    // The HDMI Forum sink capability data structure has the
+                    // same layout as its vendor-specific data block, except
+                    // that the extended tag and two reserved bytes take the
+                    // place of the OUI.
+                    0x79 if self.hdmi_forum.is_none() => {
+                        self.hdmi_forum = HdmiForumVsdb::parse(payload);
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the modes for all of the video formats that the extension
+    /// describes, including both its short video descriptors and its
+    /// detailed timings.
+    ///
+    /// Video formats that this library doesn't know are skipped.
+    pub fn modes(&self) -> Vec<ModeInfo> {
+        let mut ret: Vec<ModeInfo> = self.video.iter().filter_map(|svd| svd.mode()).collect();
+        ret.extend(
+            self.detailed_timings
+                .iter()
+                .map(DetailedTiming::to_mode_info),
+        );
+        ret
+    }
+}
+
+/// Returns the mode for the CTA-861 video format with the given Video
+/// Identification Code, if this library knows it.
+///
+/// The resulting mode does not include a picture aspect ratio flag, because
+/// the kernel rejects modes with such flags unless the client has enabled
+/// [`crate::ClientCap::AspectRatio`]. Use [`vic_aspect_ratio`] to find the
+/// aspect ratio separately.
+pub fn vic_mode(vic: u8) -> Option<ModeInfo> {
+    let t = vic_timing(vic)?;
+    let mut mode = ModeInfo {
+        name: Vec::new(),
+        clock: t.clock,
+        hdisplay: t.h[0],
+        hsync_start: t.h[1],
+        hsync_end: t.h[2],
+        htotal: t.h[3],
+        hskew: 0,
+        vdisplay: t.v[0],
+        vsync_start: t.v[1],
+        vsync_end: t.v[2],
+        vtotal: t.v[3],
+        vscan: 0,
+        vrefresh: 0,
+        flags: t.flags,
+        typ: ioctl::DRM_MODE_TYPE_DRIVER,
+    };
+    mode.fill_vrefresh_and_name();
+    Some(mode)
+}
+
+/// Returns the picture aspect ratio of the CTA-861 video format with the
+/// given Video Identification Code, as `(width, height)`.
+pub fn vic_aspect_ratio(vic: u8) -> Option<(u16, u16)> {
+    vic_timing(vic).map(|t| t.aspect)
+}
+
+fn vic_timing(vic: u8) -> Option<&'static VicTiming> {
+    match vic {
+        1..=127 => VICS_1.get(vic as usize - 1),
+        193..=219 => VICS_193.get(vic as usize - 193),
+        _ => None,
+    }
+}
+
+struct VicTiming {
+    clock: u32,
+    h: [u16; 4],
+    v: [u16; 4],
+    flags: u32,
+    aspect: (u16, u16),
+}
+
+const fn vic(clock: u32, h: [u16; 4], v: [u16; 4], flags: u32, aspect: (u16, u16)) -> VicTiming {
+    VicTiming {
+        clock,
+        h,
+        v,
+        flags,
+        aspect,
+    }
+}
+
+const P: u32 = ioctl::DRM_MODE_FLAG_PHSYNC | ioctl::DRM_MODE_FLAG_PVSYNC;
+const N: u32 = ioctl::DRM_MODE_FLAG_NHSYNC | ioctl::DRM_MODE_FLAG_NVSYNC;
+const PN: u32 = ioctl::DRM_MODE_FLAG_PHSYNC | ioctl::DRM_MODE_FLAG_NVSYNC;
+const I: u32 = ioctl::DRM_MODE_FLAG_INTERLACE;
+const D: u32 = ioctl::DRM_MODE_FLAG_DBLCLK;
+const AR_4_3: (u16, u16) = (4, 3);
+const AR_16_9: (u16, u16) = (16, 9);
+const AR_64_27: (u16, u16) = (64, 27);
+const AR_256_135: (u16, u16) = (256, 135);
+
+// The timings for each of the CTA-861 video formats, with the horizontal
+// and vertical values given as (display, sync start, sync end, total).
+static VICS_1: [VicTiming; 127] = [
+    vic(25175, [640, 656, 752, 800], [480, 490, 492, 525], N, AR_4_3), // 1: 640x480p@59.94
+    vic(27000, [720, 736, 798, 858], [480, 489, 495, 525], N, AR_4_3), // 2: 720x480p@59.94
+    vic(
+        27000,
+        [720, 736, 798, 858],
+        [480, 489, 495, 525],
+        N,
+        AR_16_9,
+    ), // 3: 720x480p@59.94
+    vic(
+        74250,
+        [1280, 1390, 1430, 1650],
+        [720, 725, 730, 750],
+        P,
+        AR_16_9,
+    ), // 4: 1280x720p@60.0
+    vic(
+        74250,
+        [1920, 2008, 2052, 2200],
+        [1080, 1084, 1094, 1125],
+        P | I,
+        AR_16_9,
+    ), // 5: 1920x1080i@60.0
+    vic(
+        13500,
+        [720, 739, 801, 858],
+        [480, 488, 494, 525],
+        N | I | D,
+        AR_4_3,
+    ), // 6: 720x480i@59.94
+    vic(
+        13500,
+        [720, 739, 801, 858],
+        [480, 488, 494, 525],
+        N | I | D,
+        AR_16_9,
+    ), // 7: 720x480i@59.94
+    vic(
+        13500,
+        [720, 739, 801, 858],
+        [240, 244, 247, 262],
+        N | D,
+        AR_4_3,
+    ), // 8: 720x240p@60.05
+    vic(
+        13500,
+        [720, 739, 801, 858],
+        [240, 244, 247, 262],
+        N | D,
+        AR_16_9,
+    ), // 9: 720x240p@60.05
+    vic(
+        54000,
+        [2880, 2956, 3204, 3432],
+        [480, 488, 494, 525],
+        N | I,
+        AR_4_3,
+    ), // 10: 2880x480i@59.94
+    vic(
+        54000,
+        [2880, 2956, 3204, 3432],
+        [480, 488, 494, 525],
+        N | I,
+        AR_16_9,
+    ), // 11: 2880x480i@59.94
+    vic(
+        54000,
+        [2880, 2956, 3204, 3432],
+        [240, 244, 247, 262],
+        N,
+        AR_4_3,
+    ), // 12: 2880x240p@60.05
+    vic(
+        54000,
+        [2880, 2956, 3204, 3432],
+        [240, 244, 247, 262],
+        N,
+        AR_16_9,
+    ), // 13: 2880x240p@60.05
+    vic(
+        54000,
+        [1440, 1472, 1596, 1716],
+        [480, 489, 495, 525],
+        N,
+        AR_4_3,
+    ), // 14: 1440x480p@59.94
+    vic(
+        54000,
+        [1440, 1472, 1596, 1716],
+        [480, 489, 495, 525],
+        N,
+        AR_16_9,
+    ), // 15: 1440x480p@59.94
+    vic(
+        148500,
+        [1920, 2008, 2052, 2200],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_16_9,
+    ), // 16: 1920x1080p@60.0
+    vic(27000, [720, 732, 796, 864], [576, 581, 586, 625], N, AR_4_3), // 17: 720x576p@50.0
+    vic(
+        27000,
+        [720, 732, 796, 864],
+        [576, 581, 586, 625],
+        N,
+        AR_16_9,
+    ), // 18: 720x576p@50.0
+    vic(
+        74250,
+        [1280, 1720, 1760, 1980],
+        [720, 725, 730, 750],
+        P,
+        AR_16_9,
+    ), // 19: 1280x720p@50.0
+    vic(
+        74250,
+        [1920, 2448, 2492, 2640],
+        [1080, 1084, 1094, 1125],
+        P | I,
+        AR_16_9,
+    ), // 20: 1920x1080i@50.0
+    vic(
+        13500,
+        [720, 732, 795, 864],
+        [576, 580, 586, 625],
+        N | I | D,
+        AR_4_3,
+    ), // 21: 720x576i@50.0
+    vic(
+        13500,
+        [720, 732, 795, 864],
+        [576, 580, 586, 625],
+        N | I | D,
+        AR_16_9,
+    ), // 22: 720x576i@50.0
+    vic(
+        13500,
+        [720, 732, 795, 864],
+        [288, 290, 293, 312],
+        N | D,
+        AR_4_3,
+    ), // 23: 720x288p@50.08
+    vic(
+        13500,
+        [720, 732, 795, 864],
+        [288, 290, 293, 312],
+        N | D,
+        AR_16_9,
+    ), // 24: 720x288p@50.08
+    vic(
+        54000,
+        [2880, 2928, 3180, 3456],
+        [576, 580, 586, 625],
+        N | I,
+        AR_4_3,
+    ), // 25: 2880x576i@50.0
+    vic(
+        54000,
+        [2880, 2928, 3180, 3456],
+        [576, 580, 586, 625],
+        N | I,
+        AR_16_9,
+    ), // 26: 2880x576i@50.0
+    vic(
+        54000,
+        [2880, 2928, 3180, 3456],
+        [288, 290, 293, 312],
+        N,
+        AR_4_3,
+    ), // 27: 2880x288p@50.08
+    vic(
+        54000,
+        [2880, 2928, 3180, 3456],
+        [288, 290, 293, 312],
+        N,
+        AR_16_9,
+    ), // 28: 2880x288p@50.08
+    vic(
+        54000,
+        [1440, 1464, 1592, 1728],
+        [576, 581, 586, 625],
+        N,
+        AR_4_3,
+    ), // 29: 1440x576p@50.0
+    vic(
+        54000,
+        [1440, 1464, 1592, 1728],
+        [576, 581, 586, 625],
+        N,
+        AR_16_9,
+    ), // 30: 1440x576p@50.0
+    vic(
+        148500,
+        [1920, 2448, 2492, 2640],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_16_9,
+    ), // 31: 1920x1080p@50.0
+    vic(
+        74250,
+        [1920, 2558, 2602, 2750],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_16_9,
+    ), // 32: 1920x1080p@24.0
+    vic(
+        74250,
+        [1920, 2448, 2492, 2640],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_16_9,
+    ), // 33: 1920x1080p@25.0
+    vic(
+        74250,
+        [1920, 2008, 2052, 2200],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_16_9,
+    ), // 34: 1920x1080p@30.0
+    vic(
+        108000,
+        [2880, 2944, 3192, 3432],
+        [480, 489, 495, 525],
+        N,
+        AR_4_3,
+    ), // 35: 2880x480p@59.94
+    vic(
+        108000,
+        [2880, 2944, 3192, 3432],
+        [480, 489, 495, 525],
+        N,
+        AR_16_9,
+    ), // 36: 2880x480p@59.94
+    vic(
+        108000,
+        [2880, 2928, 3184, 3456],
+        [576, 581, 586, 625],
+        N,
+        AR_4_3,
+    ), // 37: 2880x576p@50.0
+    vic(
+        108000,
+        [2880, 2928, 3184, 3456],
+        [576, 581, 586, 625],
+        N,
+        AR_16_9,
+    ), // 38: 2880x576p@50.0
+    vic(
+        72000,
+        [1920, 1952, 2120, 2304],
+        [1080, 1126, 1136, 1250],
+        PN | I,
+        AR_16_9,
+    ), // 39: 1920x1080i@50.0
+    vic(
+        148500,
+        [1920, 2448, 2492, 2640],
+        [1080, 1084, 1094, 1125],
+        P | I,
+        AR_16_9,
+    ), // 40: 1920x1080i@100.0
+    vic(
+        148500,
+        [1280, 1720, 1760, 1980],
+        [720, 725, 730, 750],
+        P,
+        AR_16_9,
+    ), // 41: 1280x720p@100.0
+    vic(54000, [720, 732, 796, 864], [576, 581, 586, 625], N, AR_4_3), // 42: 720x576p@100.0
+    vic(
+        54000,
+        [720, 732, 796, 864],
+        [576, 581, 586, 625],
+        N,
+        AR_16_9,
+    ), // 43: 720x576p@100.0
+    vic(
+        27000,
+        [720, 732, 795, 864],
+        [576, 580, 586, 625],
+        N | I | D,
+        AR_4_3,
+    ), // 44: 720x576i@100.0
+    vic(
+        27000,
+        [720, 732, 795, 864],
+        [576, 580, 586, 625],
+        N | I | D,
+        AR_16_9,
+    ), // 45: 720x576i@100.0
+    vic(
+        148500,
+        [1920, 2008, 2052, 2200],
+        [1080, 1084, 1094, 1125],
+        P | I,
+        AR_16_9,
+    ), // 46: 1920x1080i@120.0
+    vic(
+        148500,
+        [1280, 1390, 1430, 1650],
+        [720, 725, 730, 750],
+        P,
+        AR_16_9,
+    ), // 47: 1280x720p@120.0
+    vic(54000, [720, 736, 798, 858], [480, 489, 495, 525], N, AR_4_3), // 48: 720x480p@119.88
+    vic(
+        54000,
+        [720, 736, 798, 858],
+        [480, 489, 495, 525],
+        N,
+        AR_16_9,
+    ), // 49: 720x480p@119.88
+    vic(
+        27000,
+        [720, 739, 801, 858],
+        [480, 488, 494, 525],
+        N | I | D,
+        AR_4_3,
+    ), // 50: 720x480i@119.88
+    vic(
+        27000,
+        [720, 739, 801, 858],
+        [480, 488, 494, 525],
+        N | I | D,
+        AR_16_9,
+    ), // 51: 720x480i@119.88
+    vic(
+        108000,
+        [720, 732, 796, 864],
+        [576, 581, 586, 625],
+        N,
+        AR_4_3,
+    ), // 52: 720x576p@200.0
+    vic(
+        108000,
+        [720, 732, 796, 864],
+        [576, 581, 586, 625],
+        N,
+        AR_16_9,
+    ), // 53: 720x576p@200.0
+    vic(
+        54000,
+        [720, 732, 795, 864],
+        [576, 580, 586, 625],
+        N | I | D,
+        AR_4_3,
+    ), // 54: 720x576i@200.0
+    vic(
+        54000,
+        [720, 732, 795, 864],
+        [576, 580, 586, 625],
+        N | I | D,
+        AR_16_9,
+    ), // 55: 720x576i@200.0
+    vic(
+        108000,
+        [720, 736, 798, 858],
+        [480, 489, 495, 525],
+        N,
+        AR_4_3,
+    ), // 56: 720x480p@239.76
+    vic(
+        108000,
+        [720, 736, 798, 858],
+        [480, 489, 495, 525],
+        N,
+        AR_16_9,
+    ), // 57: 720x480p@239.76
+    vic(
+        54000,
+        [720, 739, 801, 858],
+        [480, 488, 494, 525],
+        N | I | D,
+        AR_4_3,
+    ), // 58: 720x480i@239.76
+    vic(
+        54000,
+        [720, 739, 801, 858],
+        [480, 488, 494, 525],
+        N | I | D,
+        AR_16_9,
+    ), // 59: 720x480i@239.76
+    vic(
+        59400,
+        [1280, 3040, 3080, 3300],
+        [720, 725, 730, 750],
+        P,
+        AR_16_9,
+    ), // 60: 1280x720p@24.0
+    vic(
+        74250,
+        [1280, 3700, 3740, 3960],
+        [720, 725, 730, 750],
+        P,
+        AR_16_9,
+    ), // 61: 1280x720p@25.0
+    vic(
+        74250,
+        [1280, 3040, 3080, 3300],
+        [720, 725, 730, 750],
+        P,
+        AR_16_9,
+    ), // 62: 1280x720p@30.0
+    vic(
+        297000,
+        [1920, 2008, 2052, 2200],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_16_9,
+    ), // 63: 1920x1080p@120.0
+    vic(
+        297000,
+        [1920, 2448, 2492, 2640],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_16_9,
+    ), // 64: 1920x1080p@100.0
+    vic(
+        59400,
+        [1280, 3040, 3080, 3300],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 65: 1280x720p@24.0
+    vic(
+        74250,
+        [1280, 3700, 3740, 3960],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 66: 1280x720p@25.0
+    vic(
+        74250,
+        [1280, 3040, 3080, 3300],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 67: 1280x720p@30.0
+    vic(
+        74250,
+        [1280, 1720, 1760, 1980],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 68: 1280x720p@50.0
+    vic(
+        74250,
+        [1280, 1390, 1430, 1650],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 69: 1280x720p@60.0
+    vic(
+        148500,
+        [1280, 1720, 1760, 1980],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 70: 1280x720p@100.0
+    vic(
+        148500,
+        [1280, 1390, 1430, 1650],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 71: 1280x720p@120.0
+    vic(
+        74250,
+        [1920, 2558, 2602, 2750],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_64_27,
+    ), // 72: 1920x1080p@24.0
+    vic(
+        74250,
+        [1920, 2448, 2492, 2640],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_64_27,
+    ), // 73: 1920x1080p@25.0
+    vic(
+        74250,
+        [1920, 2008, 2052, 2200],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_64_27,
+    ), // 74: 1920x1080p@30.0
+    vic(
+        148500,
+        [1920, 2448, 2492, 2640],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_64_27,
+    ), // 75: 1920x1080p@50.0
+    vic(
+        148500,
+        [1920, 2008, 2052, 2200],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_64_27,
+    ), // 76: 1920x1080p@60.0
+    vic(
+        297000,
+        [1920, 2448, 2492, 2640],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_64_27,
+    ), // 77: 1920x1080p@100.0
+    vic(
+        297000,
+        [1920, 2008, 2052, 2200],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_64_27,
+    ), // 78: 1920x1080p@120.0
+    vic(
+        59400,
+        [1680, 3040, 3080, 3300],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 79: 1680x720p@24.0
+    vic(
+        59400,
+        [1680, 2908, 2948, 3168],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 80: 1680x720p@25.0
+    vic(
+        59400,
+        [1680, 2380, 2420, 2640],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 81: 1680x720p@30.0
+    vic(
+        82500,
+        [1680, 1940, 1980, 2200],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 82: 1680x720p@50.0
+    vic(
+        99000,
+        [1680, 1940, 1980, 2200],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 83: 1680x720p@60.0
+    vic(
+        165000,
+        [1680, 1740, 1780, 2000],
+        [720, 725, 730, 825],
+        P,
+        AR_64_27,
+    ), // 84: 1680x720p@100.0
+    vic(
+        198000,
+        [1680, 1740, 1780, 2000],
+        [720, 725, 730, 825],
+        P,
+        AR_64_27,
+    ), // 85: 1680x720p@120.0
+    vic(
+        99000,
+        [2560, 3558, 3602, 3750],
+        [1080, 1084, 1089, 1100],
+        P,
+        AR_64_27,
+    ), // 86: 2560x1080p@24.0
+    vic(
+        90000,
+        [2560, 3008, 3052, 3200],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_64_27,
+    ), // 87: 2560x1080p@25.0
+    vic(
+        118800,
+        [2560, 3328, 3372, 3520],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_64_27,
+    ), // 88: 2560x1080p@30.0
+    vic(
+        185625,
+        [2560, 3108, 3152, 3300],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_64_27,
+    ), // 89: 2560x1080p@50.0
+    vic(
+        198000,
+        [2560, 2808, 2852, 3000],
+        [1080, 1084, 1089, 1100],
+        P,
+        AR_64_27,
+    ), // 90: 2560x1080p@60.0
+    vic(
+        371250,
+        [2560, 2778, 2822, 2970],
+        [1080, 1084, 1089, 1250],
+        P,
+        AR_64_27,
+    ), // 91: 2560x1080p@100.0
+    vic(
+        495000,
+        [2560, 3108, 3152, 3300],
+        [1080, 1084, 1089, 1250],
+        P,
+        AR_64_27,
+    ), // 92: 2560x1080p@120.0
+    vic(
+        297000,
+        [3840, 5116, 5204, 5500],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_16_9,
+    ), // 93: 3840x2160p@24.0
+    vic(
+        297000,
+        [3840, 4896, 4984, 5280],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_16_9,
+    ), // 94: 3840x2160p@25.0
+    vic(
+        297000,
+        [3840, 4016, 4104, 4400],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_16_9,
+    ), // 95: 3840x2160p@30.0
+    vic(
+        594000,
+        [3840, 4896, 4984, 5280],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_16_9,
+    ), // 96: 3840x2160p@50.0
+    vic(
+        594000,
+        [3840, 4016, 4104, 4400],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_16_9,
+    ), // 97: 3840x2160p@60.0
+    vic(
+        297000,
+        [4096, 5116, 5204, 5500],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_256_135,
+    ), // 98: 4096x2160p@24.0
+    vic(
+        297000,
+        [4096, 5064, 5152, 5280],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_256_135,
+    ), // 99: 4096x2160p@25.0
+    vic(
+        297000,
+        [4096, 4184, 4272, 4400],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_256_135,
+    ), // 100: 4096x2160p@30.0
+    vic(
+        594000,
+        [4096, 5064, 5152, 5280],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_256_135,
+    ), // 101: 4096x2160p@50.0
+    vic(
+        594000,
+        [4096, 4184, 4272, 4400],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_256_135,
+    ), // 102: 4096x2160p@60.0
+    vic(
+        297000,
+        [3840, 5116, 5204, 5500],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_64_27,
+    ), // 103: 3840x2160p@24.0
+    vic(
+        297000,
+        [3840, 4896, 4984, 5280],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_64_27,
+    ), // 104: 3840x2160p@25.0
+    vic(
+        297000,
+        [3840, 4016, 4104, 4400],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_64_27,
+    ), // 105: 3840x2160p@30.0
+    vic(
+        594000,
+        [3840, 4896, 4984, 5280],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_64_27,
+    ), // 106: 3840x2160p@50.0
+    vic(
+        594000,
+        [3840, 4016, 4104, 4400],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_64_27,
+    ), // 107: 3840x2160p@60.0
+    vic(
+        90000,
+        [1280, 2240, 2280, 2500],
+        [720, 725, 730, 750],
+        P,
+        AR_16_9,
+    ), // 108: 1280x720p@48.0
+    vic(
+        90000,
+        [1280, 2240, 2280, 2500],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 109: 1280x720p@48.0
+    vic(
+        99000,
+        [1680, 2490, 2530, 2750],
+        [720, 725, 730, 750],
+        P,
+        AR_64_27,
+    ), // 110: 1680x720p@48.0
+    vic(
+        148500,
+        [1920, 2558, 2602, 2750],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_16_9,
+    ), // 111: 1920x1080p@48.0
+    vic(
+        148500,
+        [1920, 2558, 2602, 2750],
+        [1080, 1084, 1089, 1125],
+        P,
+        AR_64_27,
+    ), // 112: 1920x1080p@48.0
+    vic(
+        198000,
+        [2560, 3558, 3602, 3750],
+        [1080, 1084, 1089, 1100],
+        P,
+        AR_64_27,
+    ), // 113: 2560x1080p@48.0
+    vic(
+        594000,
+        [3840, 5116, 5204, 5500],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_16_9,
+    ), // 114: 3840x2160p@48.0
+    vic(
+        594000,
+        [4096, 5116, 5204, 5500],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_256_135,
+    ), // 115: 4096x2160p@48.0
+    vic(
+        594000,
+        [3840, 5116, 5204, 5500],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_64_27,
+    ), // 116: 3840x2160p@48.0
+    vic(
+        1188000,
+        [3840, 4896, 4984, 5280],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_16_9,
+    ), // 117: 3840x2160p@100.0
+    vic(
+        1188000,
+        [3840, 4016, 4104, 4400],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_16_9,
+    ), // 118: 3840x2160p@120.0
+    vic(
+        1188000,
+        [3840, 4896, 4984, 5280],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_64_27,
+    ), // 119: 3840x2160p@100.0
+    vic(
+        1188000,
+        [3840, 4016, 4104, 4400],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_64_27,
+    ), // 120: 3840x2160p@120.0
+    vic(
+        396000,
+        [5120, 7116, 7204, 7500],
+        [2160, 2168, 2178, 2200],
+        P,
+        AR_64_27,
+    ), // 121: 5120x2160p@24.0
+    vic(
+        396000,
+        [5120, 6816, 6904, 7200],
+        [2160, 2168, 2178, 2200],
+        P,
+        AR_64_27,
+    ), // 122: 5120x2160p@25.0
+    vic(
+        396000,
+        [5120, 5784, 5872, 6000],
+        [2160, 2168, 2178, 2200],
+        P,
+        AR_64_27,
+    ), // 123: 5120x2160p@30.0
+    vic(
+        742500,
+        [5120, 5866, 5954, 6250],
+        [2160, 2168, 2178, 2475],
+        P,
+        AR_64_27,
+    ), // 124: 5120x2160p@48.0
+    vic(
+        742500,
+        [5120, 6216, 6304, 6600],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_64_27,
+    ), // 125: 5120x2160p@50.0
+    vic(
+        742500,
+        [5120, 5284, 5372, 5500],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_64_27,
+    ), // 126: 5120x2160p@60.0
+    vic(
+        1485000,
+        [5120, 6216, 6304, 6600],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_64_27,
+    ), // 127: 5120x2160p@100.0
+];
+
+static VICS_193: [VicTiming; 27] = [
+    vic(
+        1485000,
+        [5120, 5284, 5372, 5500],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_64_27,
+    ), // 193: 5120x2160p@120.0
+    vic(
+        1188000,
+        [7680, 10232, 10408, 11000],
+        [4320, 4336, 4356, 4500],
+        P,
+        AR_16_9,
+    ), // 194: 7680x4320p@24.0
+    vic(
+        1188000,
+        [7680, 10032, 10208, 10800],
+        [4320, 4336, 4356, 4400],
+        P,
+        AR_16_9,
+    ), // 195: 7680x4320p@25.0
+    vic(
+        1188000,
+        [7680, 8232, 8408, 9000],
+        [4320, 4336, 4356, 4400],
+        P,
+        AR_16_9,
+    ), // 196: 7680x4320p@30.0
+    vic(
+        2376000,
+        [7680, 10232, 10408, 11000],
+        [4320, 4336, 4356, 4500],
+        P,
+        AR_16_9,
+    ), // 197: 7680x4320p@48.0
+    vic(
+        2376000,
+        [7680, 10032, 10208, 10800],
+        [4320, 4336, 4356, 4400],
+        P,
+        AR_16_9,
+    ), // 198: 7680x4320p@50.0
+    vic(
+        2376000,
+        [7680, 8232, 8408, 9000],
+        [4320, 4336, 4356, 4400],
+        P,
+        AR_16_9,
+    ), // 199: 7680x4320p@60.0
+    vic(
+        4752000,
+        [7680, 9792, 9968, 10560],
+        [4320, 4336, 4356, 4500],
+        P,
+        AR_16_9,
+    ), // 200: 7680x4320p@100.0
+    vic(
+        4752000,
+        [7680, 8032, 8208, 8800],
+        [4320, 4336, 4356, 4500],
+        P,
+        AR_16_9,
+    ), // 201: 7680x4320p@120.0
+    vic(
+        1188000,
+        [7680, 10232, 10408, 11000],
+        [4320, 4336, 4356, 4500],
+        P,
+        AR_64_27,
+    ), // 202: 7680x4320p@24.0
+    vic(
+        1188000,
+        [7680, 10032, 10208, 10800],
+        [4320, 4336, 4356, 4400],
+        P,
+        AR_64_27,
+    ), // 203: 7680x4320p@25.0
+    vic(
+        1188000,
+        [7680, 8232, 8408, 9000],
+        [4320, 4336, 4356, 4400],
+        P,
+        AR_64_27,
+    ), // 204: 7680x4320p@30.0
+    vic(
+        2376000,
+        [7680, 10232, 10408, 11000],
+        [4320, 4336, 4356, 4500],
+        P,
+        AR_64_27,
+    ), // 205: 7680x4320p@48.0
+    vic(
+        2376000,
+        [7680, 10032, 10208, 10800],
+        [4320, 4336, 4356, 4400],
+        P,
+        AR_64_27,
+    ), // 206: 7680x4320p@50.0
+    vic(
+        2376000,
+        [7680, 8232, 8408, 9000],
+        [4320, 4336, 4356, 4400],
+        P,
+        AR_64_27,
+    ), // 207: 7680x4320p@60.0
+    vic(
+        4752000,
+        [7680, 9792, 9968, 10560],
+        [4320, 4336, 4356, 4500],
+        P,
+        AR_64_27,
+    ), // 208: 7680x4320p@100.0
+    vic(
+        4752000,
+        [7680, 8032, 8208, 8800],
+        [4320, 4336, 4356, 4500],
+        P,
+        AR_64_27,
+    ), // 209: 7680x4320p@120.0
+    vic(
+        1485000,
+        [10240, 11732, 11908, 12500],
+        [4320, 4336, 4356, 4950],
+        P,
+        AR_64_27,
+    ), // 210: 10240x4320p@24.0
+    vic(
+        1485000,
+        [10240, 12732, 12908, 13500],
+        [4320, 4336, 4356, 4400],
+        P,
+        AR_64_27,
+    ), // 211: 10240x4320p@25.0
+    vic(
+        1485000,
+        [10240, 10528, 10704, 11000],
+        [4320, 4336, 4356, 4500],
+        P,
+        AR_64_27,
+    ), // 212: 10240x4320p@30.0
+    vic(
+        2970000,
+        [10240, 11732, 11908, 12500],
+        [4320, 4336, 4356, 4950],
+        P,
+        AR_64_27,
+    ), // 213: 10240x4320p@48.0
+    vic(
+        2970000,
+        [10240, 12732, 12908, 13500],
+        [4320, 4336, 4356, 4400],
+        P,
+        AR_64_27,
+    ), // 214: 10240x4320p@50.0
+    vic(
+        2970000,
+        [10240, 10528, 10704, 11000],
+        [4320, 4336, 4356, 4500],
+        P,
+        AR_64_27,
+    ), // 215: 10240x4320p@60.0
+    vic(
+        5940000,
+        [10240, 12432, 12608, 13200],
+        [4320, 4336, 4356, 4500],
+        P,
+        AR_64_27,
+    ), // 216: 10240x4320p@100.0
+    vic(
+        5940000,
+        [10240, 10528, 10704, 11000],
+        [4320, 4336, 4356, 4500],
+        P,
+        AR_64_27,
+    ), // 217: 10240x4320p@120.0
+    vic(
+        1188000,
+        [4096, 4896, 4984, 5280],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_256_135,
+    ), // 218: 4096x2160p@100.0
+    vic(
+        1188000,
+        [4096, 4184, 4272, 4400],
+        [2160, 2168, 2178, 2250],
+        P,
+        AR_256_135,
+    ), // 219: 4096x2160p@120.0
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edid::Edid;
+
+    /// The same fixture as the base block tests, whose second block is a
+    /// CTA-861 extension for an HDMI 2.0 sink with HDR support.
+    const MONITOR_4K: &[u8] = include_bytes!("../../testdata/edid/4k-monitor.bin");
+
+    fn cta_block() -> [u8; BLOCK_SIZE] {
+        MONITOR_4K[BLOCK_SIZE..].try_into().unwrap()
+    }
+
+    fn parse_fixture() -> CtaExtension {
+        let edid = Edid::parse(MONITOR_4K).unwrap();
+        let mut exts: Vec<_> = edid.cta_extensions().collect();
+        assert_eq!(exts.len(), 1);
+        exts.remove(0)
+    }
+
+    #[test]
+    fn header_flags() {
+        let cta = parse_fixture();
+        assert_eq!(cta.revision, 3);
+        assert!(!cta.underscan);
+        assert!(cta.basic_audio);
+        assert!(cta.ycbcr444 && cta.ycbcr422);
+        assert_eq!(cta.native_format_count, 1);
+    }
+
+    #[test]
+    fn video_data_block() {
+        let cta = parse_fixture();
+        let vics: Vec<_> = cta.video.iter().map(|svd| (svd.vic, svd.native)).collect();
+        assert_eq!(
+            vics,
+            [
+                (16, true),
+                (4, false),
+                (5, false),
+                (97, false),
+                (95, false),
+                (3, false),
+                (18, false),
+                (1, false),
+            ]
+        );
+        assert_eq!(cta.video[0].aspect_ratio(), Some((16, 9)));
+
+        // The video formats come first, followed by the detailed timings.
+        let modes = cta.modes();
+        assert_eq!(modes.len(), 10);
+        assert_eq!(modes[0].name, b"1920x1080");
+        assert_eq!(modes[8].name, b"1920x1080i");
+        assert!(modes[8].same_timings(&vic_mode(5).unwrap()));
+        assert!(modes[9].same_timings(&vic_mode(16).unwrap()));
+    }
+
+    #[test]
+    fn vic_lookup() {
+        let mode = vic_mode(97).unwrap();
+        assert_eq!(mode.clock, 594000);
+        assert_eq!(
+            (mode.hdisplay, mode.hsync_start, mode.hsync_end, mode.htotal),
+            (3840, 4016, 4104, 4400)
+        );
+        assert_eq!(
+            (mode.vdisplay, mode.vsync_start, mode.vsync_end, mode.vtotal),
+            (2160, 2168, 2178, 2250)
+        );
+        assert_eq!(mode.vrefresh, 60);
+        assert_eq!(vic_aspect_ratio(97), Some((16, 9)));
+
+        let mode = vic_mode(1).unwrap();
+        assert_eq!((mode.clock, mode.htotal, mode.vtotal), (25175, 800, 525));
+        assert_eq!(vic_aspect_ratio(1), Some((4, 3)));
+
+        // The 480i formats use pixel repetition.
+        let mode = vic_mode(6).unwrap();
+        assert!(mode.is_interlaced() && mode.is_dblclk());
+
+        for vic in [0, 128, 192, 220, 255] {
+            assert_eq!(vic_mode(vic), None, "VIC {vic}");
+        }
+        assert!(vic_mode(127).is_some());
+        assert!(vic_mode(193).is_some());
+        assert!(vic_mode(219).is_some());
+    }
+
+    #[test]
+    fn audio_data_block() {
+        let cta = parse_fixture();
+        assert_eq!(cta.audio.len(), 2);
+
+        let lpcm = &cta.audio[0];
+        assert_eq!(lpcm.format, AudioFormat::Lpcm);
+        assert_eq!(lpcm.max_channels, 2);
+        assert_eq!(
+            lpcm.sample_rates_hz().collect::<Vec<_>>(),
+            [32_000, 44_100, 48_000]
+        );
+        assert_eq!(lpcm.lpcm_bit_depths().collect::<Vec<_>>(), [16, 20, 24]);
+        assert_eq!(lpcm.max_bitrate_kbps(), None);
+
+        let ac3 = &cta.audio[1];
+        assert_eq!(ac3.format, AudioFormat::Ac3);
+        assert_eq!(ac3.max_channels, 6);
+        assert_eq!(ac3.lpcm_bit_depths().count(), 0);
+        assert_eq!(ac3.max_bitrate_kbps(), Some(640));
+
+        let speakers = cta.speaker_allocation.unwrap();
+        assert!(speakers.contains(SpeakerAllocation::FL_FR));
+        assert!(speakers.contains(SpeakerAllocation::LFE1));
+        assert!(speakers.contains(SpeakerAllocation::FC));
+        assert!(speakers.contains(SpeakerAllocation::BL_BR));
+        assert!(!speakers.contains(SpeakerAllocation::BC));
+    }
+
+    #[test]
+    fn hdmi_data_blocks() {
+        let cta = parse_fixture();
+        assert_eq!(
+            cta.hdmi,
+            Some(HdmiVsdb {
+                physical_address: [1, 0, 0, 0],
+                supports_ai: true,
+                deep_color_30: true,
+                deep_color_36: true,
+                deep_color_48: false,
+                deep_color_y444: true,
+                dvi_dual: false,
+                max_tmds_clock_khz: Some(300_000),
+            })
+        );
+        assert_eq!(
+            cta.hdmi_forum,
+            Some(HdmiForumVsdb {
+                version: 1,
+                max_tmds_character_rate_khz: Some(600_000),
+                scdc_present: true,
+                scdc_read_request: false,
+                lte_340mcsc_scramble: true,
+                deep_color_420_30: true,
+                deep_color_420_36: true,
+                deep_color_420_48: false,
+                max_frl_rate: 0,
+                allm: true,
+                fva: false,
+                cinema_vrr: false,
+                qms: false,
+                vrr: Some(VrrRange {
+                    min_hz: 48,
+                    max_hz: Some(60),
+                }),
+            })
+        );
+        let edid = Edid::parse(MONITOR_4K).unwrap();
+        assert_eq!(
+            edid.hdmi_vrr_range(),
+            Some(VrrRange {
+                min_hz: 48,
+                max_hz: Some(60),
+            })
+        );
+    }
+
+    #[test]
+    fn hdr_static_metadata_block() {
+        let cta = parse_fixture();
+        let colorimetry = cta.colorimetry.unwrap();
+        assert!(colorimetry.contains(Colorimetry::BT2020_RGB));
+        assert!(colorimetry.contains(Colorimetry::BT2020_YCC));
+        assert!(!colorimetry.contains(Colorimetry::DCI_P3));
+        assert_eq!(colorimetry.metadata_profiles(), 0);
+
+        let hdr = cta.hdr_static_metadata.unwrap();
+        assert!(hdr.supports_eotf(HdrStaticMetadata::EOTF_TRADITIONAL_SDR));
+        assert!(hdr.supports_eotf(HdrStaticMetadata::EOTF_ST2084));
+        assert!(!hdr.supports_eotf(HdrStaticMetadata::EOTF_HLG));
+        assert_eq!(hdr.descriptors, 1);
+        let close = |actual: Option<f64>, expected: f64| {
+            let actual = actual.unwrap();
+            assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+        };
+        close(hdr.max_luminance(), 672.717132);
+        close(hdr.max_frame_avg_luminance(), 417.709513);
+        close(hdr.min_luminance(), 0.423752);
+
+        let edid = Edid::parse(MONITOR_4K).unwrap();
+        assert_eq!(edid.hdr_static_metadata(), Some(hdr));
+    }
+
+    #[test]
+    fn detailed_timings() {
+        let cta = parse_fixture();
+        assert_eq!(cta.detailed_timings.len(), 2);
+        assert!(cta.detailed_timings[0].interlaced);
+        assert!(!cta.detailed_timings[1].interlaced);
+    }
+
+    #[test]
+    fn malformed_dtd_start() {
+        // Offsets inside the header would make the header and data blocks
+        // be read as detailed timings.
+        for dtd_start in [0, 1, 2, 3] {
+            let mut block = cta_block();
+            let old = block[2];
+            block[2] = dtd_start;
+            block[BLOCK_SIZE - 1] = block[BLOCK_SIZE - 1].wrapping_add(old.wrapping_sub(dtd_start));
+            let cta = CtaExtension::parse(&block).unwrap();
+            assert!(cta.detailed_timings.is_empty(), "dtd_start {dtd_start}");
+            assert!(cta.video.is_empty(), "dtd_start {dtd_start}");
+        }
+    }
+
+    #[test]
+    fn invalid_block() {
+        let mut block = cta_block();
+        block[4] ^= 0x01;
+        assert_eq!(CtaExtension::parse(&block), Err(Error::Invalid));
+        let mut block = cta_block();
+        block[0] = 0x70;
+        assert_eq!(CtaExtension::parse(&block), Err(Error::Invalid));
+    }
+}
@@ -0,0 +1,539 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::{block_checksum_valid, ColorCharacteristics, BLOCK_SIZE};
+use crate::ioctl;
+use crate::modeset::ModeInfo;
+use crate::result::Error;
+
+/// The tag byte that identifies an EDID extension block containing a
+/// DisplayID section.
+pub const DISPLAYID_EXTENSION_TAG: u8 = 0x70;
+
+/// Parsed DisplayID data, either from a standalone DisplayID structure or
+/// from one or more EDID extension blocks.
+///
+/// Only the data blocks most relevant to modesetting are decoded, and any
+/// others are ignored.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DisplayId {
+    /// The structure version, such as `0x13` for DisplayID 1.3 or `0x20`
+    /// for DisplayID 2.0.
+    pub version: u8,
+    /// The display product type (for 1.x) or primary use case (for 2.0)
+    /// from the first section.
+    pub product_type: u8,
+    pub product: Option<DisplayIdProduct>,
+    pub parameters: Option<DisplayIdParameters>,
+    /// The timings from all type I and type VII detailed timing blocks.
+    pub timings: Vec<DisplayIdTiming>,
+    pub tiled_topology: Option<TiledTopology>,
+}
+
+/// The content of a product identification data block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayIdProduct {
+    /// The manufacturer's PNP id (for 1.x) or IEEE OUI (for 2.0).
+    pub vendor: [u8; 3],
+    pub product_code: u16,
+    pub serial_number: u32,
+    /// The week of manufacture, if specified.
+    pub manufacture_week: Option<u8>,
+    /// The year of manufacture, or the model year if
+    /// [`Self::is_model_year`] is set.
+    pub manufacture_year: u16,
+    pub is_model_year: bool,
+    pub name: Option<String>,
+}
+
+/// The content of a display parameters data block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayIdParameters {
+    /// The physical size of the image in millimeters, as
+    /// `(width, height)`.
+    pub image_size_mm: (f64, f64),
+    /// The native resolution in pixels, as `(width, height)`.
+    pub native_resolution: (u16, u16),
+    /// The display transfer characteristic (gamma), if specified.
+    pub gamma: Option<f64>,
+    /// The native number of bits per color component, if specified.
+    pub native_bpc: Option<u8>,
+    /// The native color primaries and white point, which are only present
+    /// in DisplayID 2.0.
+    pub primaries: Option<ColorCharacteristics>,
+    /// The maximum luminance in cd/m² with a full white screen, if
+    /// specified. Only present in DisplayID 2.0.
+    pub max_luminance_full: Option<f64>,
+    /// The maximum luminance in cd/m² with white covering 10% of the
+    /// screen, if specified. Only present in DisplayID 2.0.
+    pub max_luminance_10pct: Option<f64>,
+    /// The minimum luminance in cd/m², if specified. Only present in
+    /// DisplayID 2.0.
+    pub min_luminance: Option<f64>,
+}
+
+/// A detailed timing from a type I (DisplayID 1.x) or type VII
+/// (DisplayID 2.0) timing data block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DisplayIdTiming {
+    pub pixel_clock_khz: u32,
+    pub hactive: u16,
+    pub hblank: u16,
+    pub hsync_offset: u16,
+    pub hsync_width: u16,
+    pub hsync_positive: bool,
+    pub vactive: u16,
+    pub vblank: u16,
+    pub vsync_offset: u16,
+    pub vsync_width: u16,
+    pub vsync_positive: bool,
+    pub interlaced: bool,
+    /// True if this is the display's preferred timing.
+    pub preferred: bool,
+    /// The raw aspect ratio code.
+    pub aspect_ratio: u8,
+    /// The raw 3D stereo support code, which is zero for monoscopic-only
+    /// timings.
+    pub stereo: u8,
+}
+
+impl DisplayIdTiming {
+    const SIZE: usize = 20;
+
+    /// Parse a detailed timing descriptor, returning `None` if any of its
+    /// sizes, totals or sync positions are too large to represent in a
+    /// mode.
+    fn parse(raw: &[u8], clock_in_khz: bool) -> Option<Self> {
+        let word = |i: usize| u16::from_le_bytes([raw[i], raw[i + 1]]);
+        // Most fields are stored as one less than their actual value.
+        let plus_one = |i: usize| word(i).checked_add(1);
+        let clock = u32::from_le_bytes([raw[0], raw[1], raw[2], 0]) + 1;
+        let ret = Self {
+            pixel_clock_khz: if clock_in_khz { clock } else { clock * 10 },
+            aspect_ratio: raw[3] & 0x0f,
+            interlaced: (raw[3] & 0x10) != 0,
+            stereo: (raw[3] >> 5) & 0x3,
+            preferred: (raw[3] & 0x80) != 0,
+            hactive: plus_one(4)?,
+            hblank: plus_one(6)?,
+            hsync_offset: (word(8) & 0x7fff) + 1,
+            hsync_positive: (word(8) & 0x8000) != 0,
+            hsync_width: plus_one(10)?,
+            vactive: plus_one(12)?,
+            vblank: plus_one(14)?,
+            vsync_offset: (word(16) & 0x7fff) + 1,
+            vsync_positive: (word(16) & 0x8000) != 0,
+            vsync_width: plus_one(18)?,
+        };
+        // The mode's totals and sync positions must also fit, so that
+        // to_mode_info can't overflow.
+        ret.hactive.checked_add(ret.hblank)?;
+        ret.hactive
+            .checked_add(ret.hsync_offset)?
+            .checked_add(ret.hsync_width)?;
+        ret.vactive.checked_add(ret.vblank)?;
+        ret.vactive
+            .checked_add(ret.vsync_offset)?
+            .checked_add(ret.vsync_width)?;
+        Some(ret)
+    }
+
+    /// Convert the timing into a mode that could be used with a CRTC,
+    /// with the mode type `DRM_MODE_TYPE_DRIVER` and, if this is the
+    /// preferred timing, `DRM_MODE_TYPE_PREFERRED`.
+    pub fn to_mode_info(&self) -> ModeInfo {
+        let hsync_start = self.hactive + self.hsync_offset;
+        let vsync_start = self.vactive + self.vsync_offset;
+        let mut mode = ModeInfo {
+            name: Vec::new(),
+            clock: self.pixel_clock_khz,
+            hdisplay: self.hactive,
+            hsync_start,
+            hsync_end: hsync_start + self.hsync_width,
+            htotal: self.hactive + self.hblank,
+            hskew: 0,
+            vdisplay: self.vactive,
+            vsync_start,
+            vsync_end: vsync_start + self.vsync_width,
+            vtotal: self.vactive + self.vblank,
+            vscan: 0,
+            vrefresh: 0,
+            flags: 0,
+            typ: ioctl::DRM_MODE_TYPE_DRIVER,
+        };
+        mode.flags |= if self.hsync_positive {
+            ioctl::DRM_MODE_FLAG_PHSYNC
+        } else {
+            ioctl::DRM_MODE_FLAG_NHSYNC
+        };
+        mode.flags |= if self.vsync_positive {
+            ioctl::DRM_MODE_FLAG_PVSYNC
+        } else {
+            ioctl::DRM_MODE_FLAG_NVSYNC
+        };
+        if self.interlaced {
+            mode.flags |= ioctl::DRM_MODE_FLAG_INTERLACE;
+        }
+        if self.preferred {
+            mode.typ |= ioctl::DRM_MODE_TYPE_PREFERRED;
+        }
+        mode.fill_vrefresh_and_name();
+        mode
+    }
+}
+
+/// The content of a tiled display topology data block, which describes
+/// one tile of a display that is driven through multiple connectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TiledTopology {
+    /// True if all of the tiles are in a single physical enclosure.
+    pub single_enclosure: bool,
+    pub num_h_tiles: u8,
+    pub num_v_tiles: u8,
+    /// The horizontal position of this tile, counting from zero at the left.
+    pub h_location: u8,
+    /// The vertical position of this tile, counting from zero at the top.
+    pub v_location: u8,
+    pub tile_width: u16,
+    pub tile_height: u16,
+    /// The multiplier used to convert the bezel sizes into pixels, in
+    /// units of 0.1.
+    pub bezel_pixel_multiplier: u8,
+    /// The bezel sizes as `[top, bottom, right, left]`.
+    pub bezel: [u8; 4],
+    /// The vendor, product code and serial number that together identify
+    /// the group of tiles this tile belongs to.
+    pub topology_vendor: [u8; 3],
+    pub topology_product_code: u16,
+    pub topology_serial_number: u32,
+}
+
+impl TiledTopology {
+    fn parse(payload: &[u8]) -> Option<Self> {
+        if payload.len() < 22 {
+            return None;
+        }
+        let topo = &payload[1..4];
+        Some(Self {
+            single_enclosure: (payload[0] & 0x80) != 0,
+            num_h_tiles: ((topo[0] >> 4) | ((topo[2] >> 2) & 0x30)) + 1,
+            num_v_tiles: ((topo[0] & 0xf) | (topo[2] & 0x30)) + 1,
+            h_location: (topo[1] >> 4) | (((topo[2] >> 2) & 0x3) << 4),
+            v_location: (topo[1] & 0xf) | ((topo[2] & 0x3) << 4),
+            tile_width: u16::from_le_bytes([payload[4], payload[5]]).checked_add(1)?,
+            tile_height: u16::from_le_bytes([payload[6], payload[7]]).checked_add(1)?,
+            bezel_pixel_multiplier: payload[8],
+            bezel: [payload[9], payload[10], payload[11], payload[12]],
+            topology_vendor: [payload[13], payload[14], payload[15]],
+            topology_product_code: u16::from_le_bytes([payload[16], payload[17]]),
+            topology_serial_number: u32::from_le_bytes([
+                payload[18],
+                payload[19],
+                payload[20],
+                payload[21],
+            ]),
+        })
+    }
+}
+
+impl DisplayId {
+    /// Parse a standalone DisplayID structure, which consists of a base
+    /// section optionally followed by extension sections.
+    ///
+    /// Returns [`Error::Invalid`] if the base section is truncated or has
+    /// an incorrect checksum. Extension sections with problems are ignored.
+    pub fn parse(raw: &[u8]) -> Result<Self, Error> {
+        let mut ret = Self::default();
+        let (ext_count, mut remain) = ret.parse_section(raw, true)?;
+        for _ in 0..ext_count {
+            match ret.parse_section(remain, false) {
+                Ok((_, rest)) => remain = rest,
+                Err(_) => break,
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Parse the DisplayID section in an EDID extension block.
+    ///
+    /// Returns [`Error::Invalid`] if the block isn't a DisplayID extension
+    /// block or if it, or the section within it, has an incorrect checksum.
+    pub fn parse_edid_extension(block: &[u8; BLOCK_SIZE]) -> Result<Self, Error> {
+        let mut ret = Self::default();
+        ret.merge_edid_extension(block)?;
+        Ok(ret)
+    }
+
+    /// Parse the DisplayID section in an EDID extension block, adding its
+    /// content to this object.
+    ///
+    /// This is for EDIDs that spread a DisplayID structure across multiple
+    /// extension blocks.
+    pub fn merge_edid_extension(&mut self, block: &[u8; BLOCK_SIZE]) -> Result<(), Error> {
+        if block[0] != DISPLAYID_EXTENSION_TAG || !block_checksum_valid(block) {
+            return Err(Error::Invalid);
+        }
+        let first = self.version == 0;
+        self.parse_section(&block[1..BLOCK_SIZE - 1], first)?;
+        Ok(())
+    }
+
+    /// Parse a single section, returning its extension count and whatever
+    /// data follows it.
+    fn parse_section<'a>(&mut self, raw: &'a [u8], first: bool) -> Result<(u8, &'a [u8]), Error> {
+        if raw.len() < 5 {
+            return Err(Error::Invalid);
+        }
+        let len = 4 + raw[1] as usize + 1;
+        if raw.len() < len || !block_checksum_valid(&raw[..len]) {
+            return Err(Error::Invalid);
+        }
+        if first {
+            self.version = raw[0];
+            self.product_type = raw[2];
+        }
+
+        let mut data = &raw[4..len - 1];
+        while data.len() >= 3 {
+            let tag = data[0];
+            let block_len = data[2] as usize;
+            if data.len() < 3 + block_len || (tag == 0 && block_len == 0) {
+                // Either a truncated block or the start of padding.
+                break;
+            }
+            self.parse_data_block(tag, data[1], &data[3..3 + block_len]);
+            data = &data[3 + block_len..];
+        }
+        Ok((raw[3], &raw[len..]))
+    }
+
+    fn parse_data_block(&mut self, tag: u8, rev: u8, payload: &[u8]) {
+        match tag {
+            0x00 | 0x20 => self.product = parse_product(payload, tag == 0x20),
+            0x01 => self.parameters = parse_parameters_v1(payload),
+            0x21 => self.parameters = parse_parameters_v2(rev, payload),
+            0x03 | 0x22 => {
+                // Timings that can't be represented are skipped, so that
+                // one bad descriptor doesn't hide the others.
+                let timings = payload
+                    .chunks_exact(DisplayIdTiming::SIZE)
+                    .filter_map(|raw| DisplayIdTiming::parse(raw, tag == 0x22));
+                self.timings.extend(timings);
+            }
+            0x12 | 0x28 => self.tiled_topology = TiledTopology::parse(payload),
+            _ => {}
+        }
+    }
+
+    /// Returns the modes for all of the detailed timings.
+    pub fn modes(&self) -> Vec<ModeInfo> {
+        self.timings
+            .iter()
+            .map(DisplayIdTiming::to_mode_info)
+            .collect()
+    }
+}
+
+fn parse_product(payload: &[u8], is_v2: bool) -> Option<DisplayIdProduct> {
+    if payload.len() < 12 {
+        return None;
+    }
+    let name_len = payload[11] as usize;
+    let name = payload
+        .get(12..12 + name_len)
+        .filter(|name| !name.is_empty())
+        .map(|name| name.iter().map(|b| *b as char).collect::<String>());
+    let week = payload[9];
+    Some(DisplayIdProduct {
+        vendor: [payload[0], payload[1], payload[2]],
+        product_code: u16::from_le_bytes([payload[3], payload[4]]),
+        serial_number: u32::from_le_bytes([payload[5], payload[6], payload[7], payload[8]]),
+        manufacture_week: match week {
+            0 | 0xff => None,
+            week => Some(week),
+        },
+        // DisplayID 2.0 uses the week value 0xff to indicate a model
+        // year, while 1.x has no such provision.
+        is_model_year: is_v2 && week == 0xff,
+        manufacture_year: payload[10] as u16 + 2000,
+        name,
+    })
+}
+
+fn parse_parameters_v1(payload: &[u8]) -> Option<DisplayIdParameters> {
+    if payload.len() < 12 {
+        return None;
+    }
+    let word = |i: usize| u16::from_le_bytes([payload[i], payload[i + 1]]);
+    Some(DisplayIdParameters {
+        image_size_mm: (word(0) as f64 / 10.0, word(2) as f64 / 10.0),
+        native_resolution: (word(4), word(6)),
+        gamma: match payload[9] {
+            0xff => None,
+            v => Some((v as f64 + 100.0) / 100.0),
+        },
+        native_bpc: Some((payload[11] & 0xf) + 1),
+        primaries: None,
+        max_luminance_full: None,
+        max_luminance_10pct: None,
+        min_luminance: None,
+    })
+}
+
+fn parse_parameters_v2(rev: u8, payload: &[u8]) -> Option<DisplayIdParameters> {
+    if payload.len() < 29 {
+        return None;
+    }
+    let word = |i: usize| u16::from_le_bytes([payload[i], payload[i + 1]]);
+    // Bit 7 of the block revision selects 0.1mm units for the image size,
+    // rather than 1mm.
+    let scale = if (rev & 0x80) != 0 { 10.0 } else { 1.0 };
+    let coords = |i: usize| {
+        let x = payload[i] as u16 | ((payload[i + 1] & 0xf) as u16) << 8;
+        let y = (payload[i + 1] >> 4) as u16 | (payload[i + 2] as u16) << 4;
+        (x as f64 / 4096.0, y as f64 / 4096.0)
+    };
+    let luminance = |i: usize| match word(i) {
+        0 => None,
+        v => Some(half_to_f64(v)),
+    };
+    Some(DisplayIdParameters {
+        image_size_mm: (word(0) as f64 / scale, word(2) as f64 / scale),
+        native_resolution: (word(4), word(6)),
+        gamma: match payload[28] {
+            0xff => None,
+            v => Some((v as f64 + 100.0) / 100.0),
+        },
+        native_bpc: match payload[27] & 0x7 {
+            1 => Some(6),
+            2 => Some(8),
+            3 => Some(10),
+            4 => Some(12),
+            5 => Some(16),
+            _ => None,
+        },
+        primaries: Some(ColorCharacteristics {
+            red: coords(9),
+            green: coords(12),
+            blue: coords(15),
+            white: coords(18),
+        }),
+        max_luminance_full: luminance(21),
+        max_luminance_10pct: luminance(23),
+        min_luminance: luminance(25),
+    })
+}
+
+/// Convert an IEEE 754 half-precision floating point value to `f64`.
+fn half_to_f64(v: u16) -> f64 {
+    let sign = if (v & 0x8000) != 0 { -1.0 } else { 1.0 };
+    let exp = ((v >> 10) & 0x1f) as i32;
+    let frac = (v & 0x3ff) as f64;
+    let magnitude = match exp {
+        0 => frac / 1024.0 / (1 << 14) as f64,
+        0x1f if frac == 0.0 => f64::INFINITY,
+        0x1f => f64::NAN,
+        _ => (1.0 + frac / 1024.0) * crate::math::exp2((exp - 15) as f64),
+    };
+    sign * magnitude
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a standalone DisplayID section containing the given data
+    /// blocks, each given as its tag, revision and payload, with a correct
+    /// checksum.
+    fn section(blocks: &[(u8, u8, &[u8])]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for (tag, rev, payload) in blocks {
+            data.extend_from_slice(&[*tag, *rev, payload.len() as u8]);
+            data.extend_from_slice(payload);
+        }
+        let mut ret = alloc::vec![0x12, data.len() as u8, 0x00, 0x00];
+        ret.extend_from_slice(&data);
+        let sum = ret.iter().fold(0_u8, |acc, b| acc.wrapping_add(*b));
+        ret.push(0_u8.wrapping_sub(sum));
+        ret
+    }
+
+    #[test]
+    fn type_i_timing() {
+        // 1920x1080@60 (CEA-861 VIC 16), preferred.
+        let mut timing = [0_u8; 20];
+        timing[0..3].copy_from_slice(&(14850_u32 - 1).to_le_bytes()[..3]);
+        timing[3] = 0x80;
+        for (i, v) in [1920, 280, 88, 44, 1080, 45, 4, 5].into_iter().enumerate() {
+            let v: u16 = v - 1;
+            timing[4 + i * 2..6 + i * 2].copy_from_slice(&v.to_le_bytes());
+        }
+        timing[9] |= 0x80;
+        timing[17] |= 0x80;
+        let id = DisplayId::parse(&section(&[(0x03, 0, &timing)])).unwrap();
+        assert_eq!(id.timings.len(), 1);
+        let mode = id.timings[0].to_mode_info();
+        assert_eq!(mode.clock, 148500);
+        assert_eq!(
+            (mode.hdisplay, mode.hsync_start, mode.hsync_end, mode.htotal),
+            (1920, 2008, 2052, 2200)
+        );
+        assert_eq!(
+            (mode.vdisplay, mode.vsync_start, mode.vsync_end, mode.vtotal),
+            (1080, 1084, 1089, 1125)
+        );
+        assert!(mode.is_preferred());
+        assert_eq!(mode.vrefresh, 60);
+    }
+
+    #[test]
+    fn saturated_timing_is_skipped() {
+        let bad = [0xff_u8; 20];
+        let id = DisplayId::parse(&section(&[(0x03, 0, &bad), (0x22, 0, &bad)])).unwrap();
+        assert!(id.timings.is_empty());
+    }
+
+    #[test]
+    fn overflowing_sync_is_skipped() {
+        // Each field fits on its own, but the sync end and total don't.
+        let mut timing = [0_u8; 20];
+        for (i, v) in [0xfff0, 0x20, 0x8, 0x10, 1080, 45, 4, 5]
+            .into_iter()
+            .enumerate()
+        {
+            let v: u16 = v - 1;
+            timing[4 + i * 2..6 + i * 2].copy_from_slice(&v.to_le_bytes());
+        }
+        let id = DisplayId::parse(&section(&[(0x03, 0, &timing)])).unwrap();
+        assert!(id.timings.is_empty());
+    }
+
+    #[test]
+    fn parameters_v2_image_size() {
+        let mut payload = [0_u8; 29];
+        payload[0..2].copy_from_slice(&5976_u16.to_le_bytes());
+        payload[2..4].copy_from_slice(&3361_u16.to_le_bytes());
+        payload[4..6].copy_from_slice(&3840_u16.to_le_bytes());
+        payload[6..8].copy_from_slice(&2160_u16.to_le_bytes());
+        // The feature flags byte must not affect the image size units.
+        payload[8] = 0x80;
+        payload[28] = 0xff;
+
+        // Bit 7 of the block revision selects 0.1mm units.
+        let id = DisplayId::parse(&section(&[(0x21, 0x80, &payload)])).unwrap();
+        let params = id.parameters.unwrap();
+        assert_eq!(params.image_size_mm, (597.6, 336.1));
+        assert_eq!(params.native_resolution, (3840, 2160));
+        assert_eq!(params.gamma, None);
+
+        let id = DisplayId::parse(&section(&[(0x21, 0x00, &payload)])).unwrap();
+        assert_eq!(id.parameters.unwrap().image_size_mm, (5976.0, 3361.0));
+    }
+
+    #[test]
+    fn saturated_tile_size_is_ignored() {
+        let bad = [0xff_u8; 22];
+        let id = DisplayId::parse(&section(&[(0x12, 0, &bad), (0x28, 0, &bad)])).unwrap();
+        assert_eq!(id.tiled_topology, None);
+    }
+}
@@ -1,4 +1,7 @@
 pub mod raw;
+mod uevent;
+
+pub use uevent::*;
 
 extern crate alloc;
 use alloc::vec::Vec;
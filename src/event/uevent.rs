@@ -0,0 +1,128 @@
+use crate::modeset::{ConnectorId, PropertyId};
+
+/// A hotplug notification that the kernel sent for a DRM card through the
+/// kernel uevent mechanism.
+///
+/// The kernel sends these when connectors are connected or disconnected,
+/// and also when it changes certain connector properties itself, such as
+/// `link-status` and `Content Protection`. They are not delivered through
+/// the card's file descriptor, so callers must receive them separately,
+/// such as from a `NETLINK_KOBJECT_UEVENT` socket or through udev.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotplugEvent {
+    /// The minor number of the card's device, from the `MINOR` key.
+    pub minor: Option<u32>,
+    /// The connector that the event relates to, if the kernel specified
+    /// one. Events without a connector can relate to any connector.
+    pub connector_id: Option<ConnectorId>,
+    /// The connector property that the kernel changed, if the event
+    /// represents a property change.
+    pub property_id: Option<PropertyId>,
+}
+
+impl HotplugEvent {
+    /// Parse the body of a kernel uevent message, which consists of
+    /// NUL-separated `KEY=value` pairs, optionally preceded by an
+    /// `action@devpath` summary.
+    ///
+    /// Returns `None` if the message isn't a DRM hotplug event. Messages
+    /// that udev rebroadcasts use a different format, which this function
+    /// doesn't support.
+    pub fn parse(raw: &[u8]) -> Option<Self> {
+        let mut is_drm = false;
+        let mut is_hotplug = false;
+        let mut ret = Self {
+            minor: None,
+            connector_id: None,
+            property_id: None,
+        };
+        for field in raw.split(|b| *b == 0) {
+            let Ok(field) = core::str::from_utf8(field) else {
+                continue;
+            };
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+            match key {
+                "SUBSYSTEM" => is_drm = value == "drm",
+                "HOTPLUG" => is_hotplug = value == "1",
+                "MINOR" => ret.minor = value.parse().ok(),
+                "CONNECTOR" => ret.connector_id = value.parse().ok().map(ConnectorId),
+                "PROPERTY" => ret.property_id = value.parse().ok().map(PropertyId),
+                _ => {}
+            }
+        }
+        (is_drm && is_hotplug).then_some(ret)
+    }
+
+    /// Returns true if the event might relate to the given connector.
+    #[inline]
+    pub fn may_affect_connector(&self, connector_id: ConnectorId) -> bool {
+        self.connector_id.is_none_or(|id| id == connector_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_property_change() {
+        // As sent by the kernel when HDCP protection changes state.
+        let raw = b"change@/devices/pci0000:00/0000:00:02.0/drm/card0\0\
+            ACTION=change\0\
+            DEVPATH=/devices/pci0000:00/0000:00:02.0/drm/card0\0\
+            SUBSYSTEM=drm\0\
+            HOTPLUG=1\0\
+            CONNECTOR=95\0\
+            PROPERTY=21\0\
+            DEVNAME=dri/card0\0\
+            DEVTYPE=drm_minor\0\
+            SEQNUM=4242\0\
+            MAJOR=226\0\
+            MINOR=0\0";
+        let event = HotplugEvent::parse(raw).unwrap();
+        assert_eq!(
+            event,
+            HotplugEvent {
+                minor: Some(0),
+                connector_id: Some(ConnectorId(95)),
+                property_id: Some(PropertyId(21)),
+            }
+        );
+        assert!(event.may_affect_connector(ConnectorId(95)));
+        assert!(!event.may_affect_connector(ConnectorId(96)));
+    }
+
+    #[test]
+    fn parse_general_hotplug() {
+        let raw = b"change@/devices/pci0000:00/0000:00:02.0/drm/card1\0\
+            ACTION=change\0\
+            SUBSYSTEM=drm\0\
+            HOTPLUG=1\0\
+            MINOR=1\0";
+        let event = HotplugEvent::parse(raw).unwrap();
+        assert_eq!(event.minor, Some(1));
+        assert_eq!(event.connector_id, None);
+        assert_eq!(event.property_id, None);
+        assert!(event.may_affect_connector(ConnectorId(95)));
+    }
+
+    #[test]
+    fn parse_other_events() {
+        let usb = b"add@/devices/pci0000:00/0000:00:14.0/usb1/1-2\0\
+            ACTION=add\0\
+            SUBSYSTEM=usb\0\
+            DEVTYPE=usb_device\0\
+            SEQNUM=4243\0";
+        assert_eq!(HotplugEvent::parse(usb), None);
+
+        // DRM events other than hotplugs, such as when a card is added.
+        let add = b"add@/devices/pci0000:00/0000:00:02.0/drm/card0\0\
+            ACTION=add\0\
+            SUBSYSTEM=drm\0\
+            MINOR=0\0";
+        assert_eq!(HotplugEvent::parse(add), None);
+        assert_eq!(HotplugEvent::parse(b""), None);
+    }
+}
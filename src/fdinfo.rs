@@ -0,0 +1,459 @@
+use alloc::collections::BTreeMap;
+use alloc::ffi::CString;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::result::Error;
+
+/// A sample of the usage statistics for a single DRM client.
+///
+/// Each sample reflects cumulative counters at the time it was read, so
+/// to measure engine utilization take two samples some time apart and
+/// compare them using [`ClientUsage::engine_utilization`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ClientUsage {
+    /// The name of the driver that the DRM file descriptor belongs to.
+    pub driver: String,
+    /// The PCI slot address of the device, if reported.
+    pub pdev: Option<String>,
+    /// The unique id of the client, if reported.
+    ///
+    /// Multiple file descriptors that refer to the same open file share
+    /// the same client id.
+    pub client_id: Option<u64>,
+    /// The name that the client chose for itself, if any.
+    pub client_name: Option<String>,
+    /// Usage statistics for each engine, keyed by engine name.
+    pub engines: BTreeMap<String, EngineUsage>,
+    /// Memory usage statistics for each memory region, keyed by region name.
+    pub memory: BTreeMap<String, MemoryUsage>,
+}
+
+/// Usage statistics for a single engine, from the `drm-engine-*`,
+/// `drm-engine-capacity-*`, `drm-cycles-*`, `drm-total-cycles-*` and
+/// `drm-maxfreq-*` keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineUsage {
+    /// Total time the engine has spent busy with this client's work,
+    /// in nanoseconds.
+    pub busy_ns: Option<u64>,
+    /// The number of parallel units of this engine type, which is one
+    /// unless the driver says otherwise.
+    pub capacity: u64,
+    /// Total number of cycles the engine has spent busy with this
+    /// client's work.
+    pub cycles: Option<u64>,
+    /// Total number of cycles the engine has been active, across all
+    /// clients.
+    pub total_cycles: Option<u64>,
+    /// The maximum frequency of the engine, in hertz.
+    pub max_freq_hz: Option<u64>,
+}
+
+impl Default for EngineUsage {
+    fn default() -> Self {
+        Self {
+            busy_ns: None,
+            capacity: 1,
+            cycles: None,
+            total_cycles: None,
+            max_freq_hz: None,
+        }
+    }
+}
+
+/// Memory usage statistics for a single memory region, in bytes.
+///
+/// Drivers report only a subset of these values, so any that are absent
+/// are `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryUsage {
+    /// Total memory allocated for the client's objects, from `drm-total-*`.
+    pub total: Option<u64>,
+    /// Memory shared with other clients, from `drm-shared-*`.
+    pub shared: Option<u64>,
+    /// Memory currently resident in the region, from `drm-resident-*`
+    /// or, for older drivers, the legacy `drm-memory-*` key.
+    pub resident: Option<u64>,
+    /// Memory that the driver may reclaim, from `drm-purgeable-*`.
+    pub purgeable: Option<u64>,
+    /// Memory in use by active work, from `drm-active-*`.
+    pub active: Option<u64>,
+}
+
+/// The fraction of time an engine was busy between two samples, as
+/// returned by [`ClientUsage::engine_utilization`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineUtilization {
+    pub engine: String,
+    /// A value between 0.0 (idle) and 1.0 (fully busy).
+    pub busy: f64,
+}
+
+impl ClientUsage {
+    /// Parse the content of an `fdinfo` file.
+    ///
+    /// Lines that are not DRM usage statistics, or that are malformed, are
+    /// ignored. Returns `None` if there is no `drm-driver` key, which means
+    /// that the file descriptor doesn't belong to a DRM device or that the
+    /// driver doesn't support usage statistics.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut ret = Self::default();
+        let mut have_driver = false;
+        let mut legacy_memory = BTreeMap::<String, u64>::new();
+
+        for line in text.lines() {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            let Some(key) = key.trim().strip_prefix("drm-") else {
+                continue;
+            };
+
+            match key {
+                "driver" => {
+                    ret.driver = value.to_string();
+                    have_driver = true;
+                }
+                "pdev" => ret.pdev = Some(value.to_string()),
+                "client-id" => ret.client_id = value.parse().ok(),
+                "client-name" => ret.client_name = Some(value.to_string()),
+                _ => {
+                    if let Some(engine) = key.strip_prefix("engine-capacity-") {
+                        if let Ok(v) = value.parse() {
+                            ret.engine(engine).capacity = v;
+                        }
+                    } else if let Some(engine) = key.strip_prefix("engine-") {
+                        if let Some(v) = parse_with_unit(value, "ns") {
+                            ret.engine(engine).busy_ns = Some(v);
+                        }
+                    } else if let Some(engine) = key.strip_prefix("total-cycles-") {
+                        if let Ok(v) = value.parse() {
+                            ret.engine(engine).total_cycles = Some(v);
+                        }
+                    } else if let Some(engine) = key.strip_prefix("cycles-") {
+                        if let Ok(v) = value.parse() {
+                            ret.engine(engine).cycles = Some(v);
+                        }
+                    } else if let Some(engine) = key.strip_prefix("maxfreq-") {
+                        if let Some(v) = parse_frequency(value) {
+                            ret.engine(engine).max_freq_hz = Some(v);
+                        }
+                    } else if let Some(region) = key.strip_prefix("memory-") {
+                        if let Some(v) = parse_memory(value) {
+                            legacy_memory.insert(region.to_string(), v);
+                        }
+                    } else {
+                        let Some((kind, region)) = key.split_once('-') else {
+                            continue;
+                        };
+                        let field: fn(&mut MemoryUsage) -> &mut Option<u64> = match kind {
+                            "total" => |m| &mut m.total,
+                            "shared" => |m| &mut m.shared,
+                            "resident" => |m| &mut m.resident,
+                            "purgeable" => |m| &mut m.purgeable,
+                            "active" => |m| &mut m.active,
+                            _ => continue,
+                        };
+                        let Some(v) = parse_memory(value) else {
+                            continue;
+                        };
+                        let mem = ret.memory.entry(region.to_string()).or_default();
+                        *field(mem) = Some(v);
+                    }
+                }
+            }
+        }
+
+        if !have_driver {
+            return None;
+        }
+        // The legacy drm-memory-* keys are equivalent to drm-resident-*,
+        // so we'll use them only when the newer key isn't present.
+        for (region, v) in legacy_memory {
+            let mem = ret.memory.entry(region).or_default();
+            if mem.resident.is_none() {
+                mem.resident = Some(v);
+            }
+        }
+        Some(ret)
+    }
+
+    fn engine(&mut self, name: &str) -> &mut EngineUsage {
+        self.engines.entry(name.to_string()).or_default()
+    }
+
+    /// Compare this sample with an earlier sample of the same client to
+    /// determine how busy each engine was in the intervening time.
+    ///
+    /// `elapsed_ns` is the time between the two samples, in nanoseconds.
+    /// It's used only for engines that report busy time; engines that report
+    /// only cycle counts are measured against the change in total cycles.
+    ///
+    /// Returns `None` if the two samples have different client ids, in
+    /// which case they cannot be meaningfully compared. Engines that don't
+    /// have enough information in both samples are omitted from the result,
+    /// as are engines whose counters went backwards, such as after the
+    /// driver reset them.
+    pub fn engine_utilization(
+        &self,
+        earlier: &ClientUsage,
+        elapsed_ns: u64,
+    ) -> Option<Vec<EngineUtilization>> {
+        if self.client_id != earlier.client_id {
+            return None;
+        }
+        let mut ret = Vec::new();
+        for (name, now) in self.engines.iter() {
+            let Some(before) = earlier.engines.get(name) else {
+                continue;
+            };
+            let capacity = now.capacity.max(1) as f64;
+            let (busy, available) = if let (Some(a), Some(b)) = (before.busy_ns, now.busy_ns) {
+                (b.checked_sub(a), elapsed_ns as f64)
+            } else if let (Some(a), Some(b), Some(ta), Some(tb)) = (
+                before.cycles,
+                now.cycles,
+                before.total_cycles,
+                now.total_cycles,
+            ) {
+                let Some(total) = tb.checked_sub(ta) else {
+                    continue;
+                };
+                (b.checked_sub(a), total as f64)
+            } else {
+                continue;
+            };
+            let Some(busy) = busy else {
+                continue;
+            };
+            if available == 0.0 {
+                continue;
+            }
+            let busy = busy as f64 / (available * capacity);
+            ret.push(EngineUtilization {
+                engine: name.clone(),
+                busy: busy.clamp(0.0, 1.0),
+            });
+        }
+        Some(ret)
+    }
+}
+
+/// Read and parse the DRM usage statistics for the file descriptor `fd`
+/// in the process with id `pid`.
+///
+/// Returns [`Error::NotSupported`] if the file descriptor doesn't belong to
+/// a DRM device or its driver doesn't publish usage statistics.
+pub fn read_client_usage(pid: u32, fd: i32) -> Result<ClientUsage, Error> {
+    read_fdinfo(&alloc::format!("/proc/{pid}/fdinfo/{fd}"))
+}
+
+pub(crate) fn read_fdinfo(path: &str) -> Result<ClientUsage, Error> {
+    let path = CString::new(path).map_err(|_| Error::Invalid)?;
+    let f = linux_io::File::open(&path, linux_io::OpenOptions::read_only())?;
+    let mut buf = Vec::<u8>::new();
+    loop {
+        buf.try_reserve(1024)?;
+        let start = buf.len();
+        buf.resize(buf.capacity(), 0);
+        let n = f.read(&mut buf[start..])?;
+        buf.truncate(start + n);
+        if n == 0 {
+            break;
+        }
+    }
+    let text = core::str::from_utf8(&buf).map_err(|_| Error::Invalid)?;
+    ClientUsage::parse(text).ok_or(Error::NotSupported)
+}
+
+fn parse_with_unit(value: &str, unit: &str) -> Option<u64> {
+    let value = value.strip_suffix(unit).unwrap_or(value);
+    value.trim().parse().ok()
+}
+
+fn parse_memory(value: &str) -> Option<u64> {
+    let (num, unit) = value.split_once(' ').unwrap_or((value, ""));
+    let num: u64 = num.parse().ok()?;
+    let scale: u64 = match unit.trim() {
+        "" | "B" => 1,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        _ => return None,
+    };
+    num.checked_mul(scale)
+}
+
+fn parse_frequency(value: &str) -> Option<u64> {
+    let (num, unit) = value.split_once(' ').unwrap_or((value, "Hz"));
+    let num: u64 = num.parse().ok()?;
+    let scale: u64 = match unit.trim() {
+        "Hz" => 1,
+        "KHz" | "kHz" => 1_000,
+        "MHz" => 1_000_000,
+        "GHz" => 1_000_000_000,
+        _ => return None,
+    };
+    num.checked_mul(scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const I915: &str = include_str!("../testdata/fdinfo/i915.txt");
+    const AMDGPU: &str = include_str!("../testdata/fdinfo/amdgpu.txt");
+    const XE: &str = include_str!("../testdata/fdinfo/xe.txt");
+
+    fn utilization(now: &ClientUsage, before: &ClientUsage, elapsed_ns: u64) -> Vec<(String, f64)> {
+        now.engine_utilization(before, elapsed_ns)
+            .unwrap()
+            .into_iter()
+            .map(|u| (u.engine, u.busy))
+            .collect()
+    }
+
+    #[test]
+    fn parse_i915() {
+        let usage = ClientUsage::parse(I915).unwrap();
+        assert_eq!(usage.driver, "i915");
+        assert_eq!(usage.client_id, Some(56));
+        assert_eq!(usage.pdev.as_deref(), Some("0000:00:02.0"));
+        assert_eq!(usage.client_name, None);
+
+        let system = usage.memory["system0"];
+        assert_eq!(system.total, Some(20 << 20));
+        assert_eq!(system.resident, Some(20 << 20));
+        assert_eq!(system.shared, Some(0));
+        assert_eq!(usage.memory["stolen-system0"].total, Some(0));
+
+        assert_eq!(usage.engines.len(), 4);
+        assert_eq!(usage.engines["render"].busy_ns, Some(25662044495));
+        assert_eq!(usage.engines["render"].capacity, 1);
+        assert_eq!(usage.engines["video"].capacity, 2);
+        assert_eq!(usage.engines["video-enhance"].busy_ns, Some(0));
+    }
+
+    #[test]
+    fn parse_amdgpu() {
+        let usage = ClientUsage::parse(AMDGPU).unwrap();
+        assert_eq!(usage.driver, "amdgpu");
+        assert_eq!(usage.client_id, Some(1044));
+        // Only the legacy key is present for these regions...
+        assert_eq!(usage.memory["vram"].resident, Some(1600 << 10));
+        assert_eq!(usage.memory["cpu"].resident, Some(0));
+        // ...but the newer key takes precedence where both are present.
+        assert_eq!(usage.memory["gtt"].resident, Some(2 << 20));
+        // The driver-specific amd-* keys are ignored.
+        assert!(!usage.memory.contains_key("visible-vram"));
+        assert_eq!(usage.engines["gfx"].busy_ns, Some(1018209210));
+        assert_eq!(usage.engines["enc_1"].busy_ns, Some(0));
+    }
+
+    #[test]
+    fn parse_xe() {
+        let usage = ClientUsage::parse(XE).unwrap();
+        assert_eq!(usage.driver, "xe");
+        assert_eq!(usage.client_name.as_deref(), Some("compositor"));
+        assert_eq!(usage.memory["gtt"].total, Some(192 << 10));
+        assert_eq!(usage.memory["vram0"].shared, Some(16 << 20));
+
+        let rcs = &usage.engines["rcs"];
+        assert_eq!(rcs.busy_ns, None);
+        assert_eq!(rcs.cycles, Some(28257900));
+        assert_eq!(rcs.total_cycles, Some(7655183225));
+        assert_eq!(usage.engines["vcs"].capacity, 2);
+        assert_eq!(usage.engines["ccs"].capacity, 4);
+        // The total-cycles keys belong to the engines, not to a memory
+        // region called "cycles-rcs".
+        assert!(!usage.memory.keys().any(|k| k.starts_with("cycles")));
+    }
+
+    #[test]
+    fn parse_units_and_unknown_keys() {
+        let usage = ClientUsage::parse(
+            "drm-driver:\tmsm\n\
+             drm-total-a:\t3 GiB\n\
+             drm-total-b:\t7 B\n\
+             drm-total-c:\t1 TiB\n\
+             drm-maxfreq-gpu:\t800000000 Hz\n\
+             drm-maxfreq-npu:\t1 GHz\n\
+             drm-engine-gpu:\tlots ns\n\
+             drm-something-new:\t42\n\
+             drm-unknown\n\
+             not-drm:\t1\n",
+        )
+        .unwrap();
+        assert_eq!(usage.memory["a"].total, Some(3 << 30));
+        assert_eq!(usage.memory["b"].total, Some(7));
+        assert!(!usage.memory.contains_key("c"));
+        assert_eq!(usage.engines["gpu"].max_freq_hz, Some(800_000_000));
+        assert_eq!(usage.engines["gpu"].busy_ns, None);
+        assert_eq!(usage.engines["npu"].max_freq_hz, Some(1_000_000_000));
+        assert!(!usage.memory.contains_key("new"));
+    }
+
+    #[test]
+    fn parse_not_drm() {
+        assert_eq!(ClientUsage::parse("pos:\t0\nflags:\t02\n"), None);
+    }
+
+    #[test]
+    fn utilization_busy_time() {
+        let before = ClientUsage::parse(I915).unwrap();
+        let now = ClientUsage::parse(&I915.replace("25662044495 ns", "25912044495 ns").replace(
+            "drm-engine-video:\t1000000 ns",
+            "drm-engine-video:\t501000000 ns",
+        ))
+        .unwrap();
+        let result = utilization(&now, &before, 1_000_000_000);
+        assert!(result.contains(&(String::from("render"), 0.25)));
+        assert!(result.contains(&(String::from("copy"), 0.0)));
+        // The video engine has two units, so 500ms of busy time in one
+        // second is a quarter of its capacity.
+        assert!(result.contains(&(String::from("video"), 0.25)));
+    }
+
+    #[test]
+    fn utilization_cycles() {
+        let before = ClientUsage::parse(XE).unwrap();
+        let now = ClientUsage::parse(
+            &XE.replace("drm-cycles-rcs:\t28257900", "drm-cycles-rcs:\t78257900")
+                .replace("drm-cycles-vcs:\t0", "drm-cycles-vcs:\t100000000")
+                .replace("7655183225", "7755183225"),
+        )
+        .unwrap();
+        let result = utilization(&now, &before, 0);
+        assert!(result.contains(&(String::from("rcs"), 0.5)));
+        assert!(result.contains(&(String::from("vcs"), 0.5)));
+        assert!(result.contains(&(String::from("ccs"), 0.0)));
+    }
+
+    #[test]
+    fn utilization_counter_reset() {
+        let before = ClientUsage::parse(I915).unwrap();
+        let now = ClientUsage::parse(&I915.replace("25662044495 ns", "1000 ns")).unwrap();
+        let result = utilization(&now, &before, 1_000_000_000);
+        assert!(!result.iter().any(|(engine, _)| engine == "render"));
+        assert!(result.iter().any(|(engine, _)| engine == "copy"));
+
+        let before = ClientUsage::parse(XE).unwrap();
+        let now = ClientUsage::parse(&XE.replace(
+            "drm-total-cycles-rcs:\t7655183225",
+            "drm-total-cycles-rcs:\t5",
+        ))
+        .unwrap();
+        let result = utilization(&now, &before, 0);
+        assert!(!result.iter().any(|(engine, _)| engine == "rcs"));
+    }
+
+    #[test]
+    fn utilization_different_clients() {
+        let before = ClientUsage::parse(I915).unwrap();
+        let now =
+            ClientUsage::parse(&I915.replace("drm-client-id:\t56", "drm-client-id:\t57")).unwrap();
+        assert_eq!(now.engine_utilization(&before, 1_000_000_000), None);
+    }
+}
@@ -0,0 +1,631 @@
+use core::fmt;
+
+mod modifier;
+mod plane_formats;
+
+pub use modifier::*;
+pub use plane_formats::*;
+
+/// A DRM pixel format code, as defined in the kernel's `drm_fourcc.h`.
+///
+/// Each format code is four ASCII characters packed into a little-endian
+/// `u32`, optionally with [`Fourcc::BIG_ENDIAN`] set in the most significant
+/// bit. Use [`Fourcc::info`] to find metadata about a format, such as
+/// how many planes it has and how many bits each pixel occupies.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Fourcc(pub u32);
+
+impl Fourcc {
+    /// The flag bit that marks a format as using big-endian byte order.
+    pub const BIG_ENDIAN: u32 = 1 << 31;
+
+    /// The invalid format, used to represent the absence of a format.
+    pub const INVALID: Self = Self(0);
+
+    /// Build a format code from its four characters.
+    #[inline(always)]
+    pub const fn from_chars(a: u8, b: u8, c: u8, d: u8) -> Self {
+        Self((a as u32) | ((b as u32) << 8) | ((c as u32) << 16) | ((d as u32) << 24))
+    }
+
+    /// Build a format code from an array of its four characters.
+    #[inline(always)]
+    pub const fn from_bytes(v: &[u8; 4]) -> Self {
+        Self::from_chars(v[0], v[1], v[2], v[3])
+    }
+
+    /// Find the format whose constant name in `drm_fourcc.h` matches the
+    /// given name, without the `DRM_FORMAT_` prefix.
+    ///
+    /// For example, `Fourcc::from_name("XRGB8888")` returns
+    /// [`Fourcc::XRGB8888`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        FORMATS
+            .iter()
+            .find(|info| info.name == name)
+            .map(|info| info.format)
+    }
+
+    /// Returns the raw `u32` value of the format code.
+    #[inline(always)]
+    pub const fn to_raw(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the four characters of the format code, excluding the
+    /// big-endian flag.
+    #[inline]
+    pub const fn chars(self) -> [u8; 4] {
+        let v = self.0 & !Self::BIG_ENDIAN;
+        [v as u8, (v >> 8) as u8, (v >> 16) as u8, (v >> 24) as u8]
+    }
+
+    /// Returns true if the format has the big-endian flag set.
+    #[inline(always)]
+    pub const fn is_big_endian(self) -> bool {
+        (self.0 & Self::BIG_ENDIAN) != 0
+    }
+
+    /// Returns the byte order of the components within each pixel.
+    #[inline]
+    pub const fn endianness(self) -> Endianness {
+        if self.is_big_endian() {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        }
+    }
+
+    /// Returns the same format with the given byte order.
+    #[inline]
+    pub const fn with_endianness(self, endianness: Endianness) -> Self {
+        match endianness {
+            Endianness::Little => Self(self.0 & !Self::BIG_ENDIAN),
+            Endianness::Big => Self(self.0 | Self::BIG_ENDIAN),
+        }
+    }
+
+    /// Returns metadata about the format, or `None` if this library doesn't
+    /// know the format.
+    ///
+    /// The big-endian flag is ignored when looking up the format, so the
+    /// result describes the layout of the format's components but not
+    /// their byte order. Use [`Self::endianness`] for that.
+    pub fn info(self) -> Option<&'static FormatInfo> {
+        let format = self.with_endianness(Endianness::Little);
+        FORMATS.iter().find(|info| info.format == format)
+    }
+
+    /// Returns the name of the format as used in `drm_fourcc.h`, without
+    /// the `DRM_FORMAT_` prefix, or `None` if this library doesn't know
+    /// the format.
+    pub fn name(self) -> Option<&'static str> {
+        self.info().map(|info| info.name)
+    }
+
+    /// Returns the number of bits used for each pixel, including any
+    /// padding bits, for single-plane formats.
+    ///
+    /// Returns `None` for formats that this library doesn't know, for
+    /// multi-plane formats, and for formats that don't have a whole
+    /// number of bits per pixel.
+    pub fn bits_per_pixel(self) -> Option<u32> {
+        let info = self.info()?;
+        if info.num_planes != 1 || info.bits_per_pixel[0] == 0 {
+            return None;
+        }
+        Some(info.bits_per_pixel[0] as u32)
+    }
+
+    /// Returns the color depth that the legacy `DRM_IOCTL_MODE_ADDFB`
+    /// request would use to describe this format, if any.
+    ///
+    /// Only a small number of formats can be described in that way, and so
+    /// other formats must be used with `DRM_IOCTL_MODE_ADDFB2` instead.
+    pub const fn legacy_depth(self) -> Option<u32> {
+        Some(match self {
+            Self::C8 => 8,
+            Self::XRGB1555 => 15,
+            Self::RGB565 => 16,
+            Self::RGB888 | Self::XRGB8888 => 24,
+            Self::XRGB2101010 => 30,
+            Self::ARGB8888 => 32,
+            _ => return None,
+        })
+    }
+}
+
+/// The byte order of the components in a pixel format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+/// Metadata about a pixel format, as returned by [`Fourcc::info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FormatInfo {
+    pub format: Fourcc,
+    /// The name of the format as used in `drm_fourcc.h`, without the
+    /// `DRM_FORMAT_` prefix.
+    pub name: &'static str,
+    /// The number of separate planes (memory regions) that make up an
+    /// image in this format.
+    pub num_planes: u8,
+    /// The number of bits that each pixel occupies in each plane.
+    ///
+    /// For planes that carry subsampled chroma, this counts the bits for
+    /// each sample rather than for each pixel of the full image. Unused
+    /// planes have zero, as do planes of formats whose pixels don't
+    /// occupy a whole number of bits, which are typically usable only in
+    /// conjunction with certain format modifiers.
+    pub bits_per_pixel: [u8; 4],
+    /// The horizontal chroma subsampling factor, which is one for formats
+    /// without subsampling.
+    pub hsub: u8,
+    /// The vertical chroma subsampling factor, which is one for formats
+    /// without subsampling.
+    pub vsub: u8,
+    /// True if the format includes an alpha channel.
+    pub has_alpha: bool,
+    /// True if the format uses YCbCr color encoding, rather than RGB.
+    pub is_yuv: bool,
+}
+
+impl FormatInfo {
+    /// Returns the number of bytes that each pixel occupies in the given
+    /// plane, or `None` if the plane doesn't exist or doesn't have a whole
+    /// number of bytes per pixel.
+    pub const fn bytes_per_pixel(&self, plane: usize) -> Option<u32> {
+        if plane >= self.num_planes as usize {
+            return None;
+        }
+        let bits = self.bits_per_pixel[plane] as u32;
+        if bits == 0 || !bits.is_multiple_of(8) {
+            return None;
+        }
+        Some(bits / 8)
+    }
+
+    /// Returns true if the format has chroma subsampling in either
+    /// direction.
+    #[inline]
+    pub const fn is_subsampled(&self) -> bool {
+        self.hsub > 1 || self.vsub > 1
+    }
+
+    /// Returns the width in pixels of the given plane for an image of the
+    /// given width, taking chroma subsampling into account.
+    pub const fn plane_width(&self, plane: usize, width: u32) -> u32 {
+        if plane == 0 || !self.is_yuv {
+            width
+        } else {
+            width.div_ceil(self.hsub as u32)
+        }
+    }
+
+    /// Returns the height in pixels of the given plane for an image of the
+    /// given height, taking chroma subsampling into account.
+    pub const fn plane_height(&self, plane: usize, height: u32) -> u32 {
+        if plane == 0 || !self.is_yuv {
+            height
+        } else {
+            height.div_ceil(self.vsub as u32)
+        }
+    }
+}
+
+impl From<u32> for Fourcc {
+    #[inline(always)]
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Fourcc> for u32 {
+    #[inline(always)]
+    fn from(value: Fourcc) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for Fourcc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.chars() {
+            let c = if c.is_ascii_graphic() || c == b' ' {
+                c as char
+            } else {
+                '?'
+            };
+            fmt::Write::write_char(f, c)?;
+        }
+        if self.is_big_endian() {
+            f.write_str(" (big-endian)")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Fourcc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.name() {
+            Some(name) if !self.is_big_endian() => write!(f, "Fourcc::{name}"),
+            _ => write!(f, "Fourcc(\"{self}\")"),
+        }
+    }
+}
+
+const fn plane_bits(bits: &[u8]) -> [u8; 4] {
+    let mut ret = [0; 4];
+    let mut i = 0;
+    while i < bits.len() {
+        ret[i] = bits[i];
+        i += 1;
+    }
+    ret
+}
+
+macro_rules! formats {
+    ($(
+        $(#[$attr:meta])*
+        $name:ident = $code:literal, [$($bits:literal),*], ($hsub:literal, $vsub:literal), $alpha:literal, $yuv:literal;
+    )*) => {
+        impl Fourcc {
+            $(
+                $(#[$attr])*
+                pub const $name: Self = Self::from_bytes($code);
+            )*
+        }
+
+        static FORMATS: &[FormatInfo] = &[
+            $(
+                FormatInfo {
+                    format: Fourcc::$name,
+                    name: stringify!($name),
+                    num_planes: [$($bits),*].len() as u8,
+                    bits_per_pixel: plane_bits(&[$($bits),*]),
+                    hsub: $hsub,
+                    vsub: $vsub,
+                    has_alpha: $alpha,
+                    is_yuv: $yuv,
+                },
+            )*
+        ];
+    };
+}
+
+// Components are listed from the most significant bit of each pixel, as in
+// `drm_fourcc.h`, so XRGB8888 stores blue in the first byte in memory.
+formats! {
+    /// 1-bit color index.
+    C1 = b"C1  ", [1], (1, 1), false, false;
+    /// 2-bit color index.
+    C2 = b"C2  ", [2], (1, 1), false, false;
+    /// 4-bit color index.
+    C4 = b"C4  ", [4], (1, 1), false, false;
+    /// 8-bit color index.
+    C8 = b"C8  ", [8], (1, 1), false, false;
+    /// 1-bit darkness, where 0 is white and 1 is black.
+    D1 = b"D1  ", [1], (1, 1), false, false;
+    /// 2-bit darkness.
+    D2 = b"D2  ", [2], (1, 1), false, false;
+    /// 4-bit darkness.
+    D4 = b"D4  ", [4], (1, 1), false, false;
+    /// 8-bit darkness.
+    D8 = b"D8  ", [8], (1, 1), false, false;
+    /// 1-bit red (or monochrome).
+    R1 = b"R1  ", [1], (1, 1), false, false;
+    /// 2-bit red.
+    R2 = b"R2  ", [2], (1, 1), false, false;
+    /// 4-bit red.
+    R4 = b"R4  ", [4], (1, 1), false, false;
+    /// 8-bit red.
+    R8 = b"R8  ", [8], (1, 1), false, false;
+    /// 10-bit red in a 16-bit container.
+    R10 = b"R10 ", [16], (1, 1), false, false;
+    /// 12-bit red in a 16-bit container.
+    R12 = b"R12 ", [16], (1, 1), false, false;
+    /// 16-bit red.
+    R16 = b"R16 ", [16], (1, 1), false, false;
+    /// 16-bit red and green, 8:8 bits.
+    RG88 = b"RG88", [16], (1, 1), false, false;
+    /// 16-bit green and red, 8:8 bits.
+    GR88 = b"GR88", [16], (1, 1), false, false;
+    /// 32-bit red and green, 16:16 bits.
+    RG1616 = b"RG32", [32], (1, 1), false, false;
+    /// 32-bit green and red, 16:16 bits.
+    GR1616 = b"GR32", [32], (1, 1), false, false;
+    /// 8-bit RGB, 3:3:2 bits.
+    RGB332 = b"RGB8", [8], (1, 1), false, false;
+    /// 8-bit BGR, 2:3:3 bits.
+    BGR233 = b"BGR8", [8], (1, 1), false, false;
+    /// 16-bit RGB with four unused bits, X:R:G:B 4:4:4:4 bits.
+    XRGB4444 = b"XR12", [16], (1, 1), false, false;
+    /// 16-bit BGR with four unused bits, X:B:G:R 4:4:4:4 bits.
+    XBGR4444 = b"XB12", [16], (1, 1), false, false;
+    /// 16-bit RGB with four unused bits, R:G:B:X 4:4:4:4 bits.
+    RGBX4444 = b"RX12", [16], (1, 1), false, false;
+    /// 16-bit BGR with four unused bits, B:G:R:X 4:4:4:4 bits.
+    BGRX4444 = b"BX12", [16], (1, 1), false, false;
+    /// 16-bit ARGB, 4:4:4:4 bits.
+    ARGB4444 = b"AR12", [16], (1, 1), true, false;
+    /// 16-bit ABGR, 4:4:4:4 bits.
+    ABGR4444 = b"AB12", [16], (1, 1), true, false;
+    /// 16-bit RGBA, 4:4:4:4 bits.
+    RGBA4444 = b"RA12", [16], (1, 1), true, false;
+    /// 16-bit BGRA, 4:4:4:4 bits.
+    BGRA4444 = b"BA12", [16], (1, 1), true, false;
+    /// 16-bit RGB with one unused bit, X:R:G:B 1:5:5:5 bits.
+    XRGB1555 = b"XR15", [16], (1, 1), false, false;
+    /// 16-bit BGR with one unused bit, X:B:G:R 1:5:5:5 bits.
+    XBGR1555 = b"XB15", [16], (1, 1), false, false;
+    /// 16-bit RGB with one unused bit, R:G:B:X 5:5:5:1 bits.
+    RGBX5551 = b"RX15", [16], (1, 1), false, false;
+    /// 16-bit BGR with one unused bit, B:G:R:X 5:5:5:1 bits.
+    BGRX5551 = b"BX15", [16], (1, 1), false, false;
+    /// 16-bit ARGB, 1:5:5:5 bits.
+    ARGB1555 = b"AR15", [16], (1, 1), true, false;
+    /// 16-bit ABGR, 1:5:5:5 bits.
+    ABGR1555 = b"AB15", [16], (1, 1), true, false;
+    /// 16-bit RGBA, 5:5:5:1 bits.
+    RGBA5551 = b"RA15", [16], (1, 1), true, false;
+    /// 16-bit BGRA, 5:5:5:1 bits.
+    BGRA5551 = b"BA15", [16], (1, 1), true, false;
+    /// 16-bit RGB, 5:6:5 bits.
+    RGB565 = b"RG16", [16], (1, 1), false, false;
+    /// 16-bit BGR, 5:6:5 bits.
+    BGR565 = b"BG16", [16], (1, 1), false, false;
+    /// 24-bit RGB, 8:8:8 bits.
+    RGB888 = b"RG24", [24], (1, 1), false, false;
+    /// 24-bit BGR, 8:8:8 bits.
+    BGR888 = b"BG24", [24], (1, 1), false, false;
+    /// 32-bit RGB with eight unused bits, X:R:G:B 8:8:8:8 bits.
+    XRGB8888 = b"XR24", [32], (1, 1), false, false;
+    /// 32-bit BGR with eight unused bits, X:B:G:R 8:8:8:8 bits.
+    XBGR8888 = b"XB24", [32], (1, 1), false, false;
+    /// 32-bit RGB with eight unused bits, R:G:B:X 8:8:8:8 bits.
+    RGBX8888 = b"RX24", [32], (1, 1), false, false;
+    /// 32-bit BGR with eight unused bits, B:G:R:X 8:8:8:8 bits.
+    BGRX8888 = b"BX24", [32], (1, 1), false, false;
+    /// 32-bit ARGB, 8:8:8:8 bits.
+    ARGB8888 = b"AR24", [32], (1, 1), true, false;
+    /// 32-bit ABGR, 8:8:8:8 bits.
+    ABGR8888 = b"AB24", [32], (1, 1), true, false;
+    /// 32-bit RGBA, 8:8:8:8 bits.
+    RGBA8888 = b"RA24", [32], (1, 1), true, false;
+    /// 32-bit BGRA, 8:8:8:8 bits.
+    BGRA8888 = b"BA24", [32], (1, 1), true, false;
+    /// 32-bit RGB with two unused bits, X:R:G:B 2:10:10:10 bits.
+    XRGB2101010 = b"XR30", [32], (1, 1), false, false;
+    /// 32-bit BGR with two unused bits, X:B:G:R 2:10:10:10 bits.
+    XBGR2101010 = b"XB30", [32], (1, 1), false, false;
+    /// 32-bit RGB with two unused bits, R:G:B:X 10:10:10:2 bits.
+    RGBX1010102 = b"RX30", [32], (1, 1), false, false;
+    /// 32-bit BGR with two unused bits, B:G:R:X 10:10:10:2 bits.
+    BGRX1010102 = b"BX30", [32], (1, 1), false, false;
+    /// 32-bit ARGB, 2:10:10:10 bits.
+    ARGB2101010 = b"AR30", [32], (1, 1), true, false;
+    /// 32-bit ABGR, 2:10:10:10 bits.
+    ABGR2101010 = b"AB30", [32], (1, 1), true, false;
+    /// 32-bit RGBA, 10:10:10:2 bits.
+    RGBA1010102 = b"RA30", [32], (1, 1), true, false;
+    /// 32-bit BGRA, 10:10:10:2 bits.
+    BGRA1010102 = b"BA30", [32], (1, 1), true, false;
+    /// 48-bit RGB, 16:16:16 bits.
+    RGB161616 = b"RG48", [48], (1, 1), false, false;
+    /// 48-bit BGR, 16:16:16 bits.
+    BGR161616 = b"BG48", [48], (1, 1), false, false;
+    /// 64-bit RGB with sixteen unused bits, X:R:G:B 16:16:16:16 bits.
+    XRGB16161616 = b"XR48", [64], (1, 1), false, false;
+    /// 64-bit BGR with sixteen unused bits, X:B:G:R 16:16:16:16 bits.
+    XBGR16161616 = b"XB48", [64], (1, 1), false, false;
+    /// 64-bit ARGB, 16:16:16:16 bits.
+    ARGB16161616 = b"AR48", [64], (1, 1), true, false;
+    /// 64-bit ABGR, 16:16:16:16 bits.
+    ABGR16161616 = b"AB48", [64], (1, 1), true, false;
+    /// 64-bit RGB with sixteen unused bits, as half-precision floating point.
+    XRGB16161616F = b"XR4H", [64], (1, 1), false, false;
+    /// 64-bit BGR with sixteen unused bits, as half-precision floating point.
+    XBGR16161616F = b"XB4H", [64], (1, 1), false, false;
+    /// 64-bit ARGB as half-precision floating point.
+    ARGB16161616F = b"AR4H", [64], (1, 1), true, false;
+    /// 64-bit ABGR as half-precision floating point.
+    ABGR16161616F = b"AB4H", [64], (1, 1), true, false;
+    /// 64-bit ABGR with 10 bits per component, each in the high bits of a 16-bit container.
+    AXBXGXRX106106106106 = b"AB10", [64], (1, 1), true, false;
+    /// Packed 4:2:2 YCbCr, Y0:Cb0:Y1:Cr0 8:8:8:8 bits.
+    YUYV = b"YUYV", [16], (2, 1), false, true;
+    /// Packed 4:2:2 YCbCr, Y0:Cr0:Y1:Cb0 8:8:8:8 bits.
+    YVYU = b"YVYU", [16], (2, 1), false, true;
+    /// Packed 4:2:2 YCbCr, Cb0:Y0:Cr0:Y1 8:8:8:8 bits.
+    UYVY = b"UYVY", [16], (2, 1), false, true;
+    /// Packed 4:2:2 YCbCr, Cr0:Y0:Cb0:Y1 8:8:8:8 bits.
+    VYUY = b"VYUY", [16], (2, 1), false, true;
+    /// Packed 4:4:4 YCbCr with alpha, A:Y:Cb:Cr 8:8:8:8 bits.
+    AYUV = b"AYUV", [32], (1, 1), true, true;
+    /// Packed 4:4:4 YCbCr with alpha, A:Cr:Cb:Y 8:8:8:8 bits.
+    AVUY8888 = b"AVUY", [32], (1, 1), true, true;
+    /// Packed 4:4:4 YCbCr with eight unused bits, X:Y:Cb:Cr 8:8:8:8 bits.
+    XYUV8888 = b"XYUV", [32], (1, 1), false, true;
+    /// Packed 4:4:4 YCbCr with eight unused bits, X:Cr:Cb:Y 8:8:8:8 bits.
+    XVUY8888 = b"XVUY", [32], (1, 1), false, true;
+    /// Packed 4:4:4 YCbCr, Cr:Cb:Y 8:8:8 bits.
+    VUY888 = b"VU24", [24], (1, 1), false, true;
+    /// Packed 4:4:4 YCbCr with 10 bits per component, only usable with compressed modifiers.
+    VUY101010 = b"VU30", [0], (1, 1), false, true;
+    /// Packed 4:2:2 YCbCr with 10 bits per component in 16-bit containers.
+    Y210 = b"Y210", [32], (2, 1), false, true;
+    /// Packed 4:2:2 YCbCr with 12 bits per component in 16-bit containers.
+    Y212 = b"Y212", [32], (2, 1), false, true;
+    /// Packed 4:2:2 YCbCr with 16 bits per component.
+    Y216 = b"Y216", [32], (2, 1), false, true;
+    /// Packed 4:4:4 YCbCr with alpha, A:Cr:Y:Cb 2:10:10:10 bits.
+    Y410 = b"Y410", [32], (1, 1), true, true;
+    /// Packed 4:4:4 YCbCr with alpha, 12 bits per component in 16-bit containers.
+    Y412 = b"Y412", [64], (1, 1), true, true;
+    /// Packed 4:4:4 YCbCr with alpha, 16 bits per component.
+    Y416 = b"Y416", [64], (1, 1), true, true;
+    /// Packed 4:4:4 YCbCr with two unused bits, X:Cr:Y:Cb 2:10:10:10 bits.
+    XVYU2101010 = b"XV30", [32], (1, 1), false, true;
+    /// Packed 4:4:4 YCbCr with 12 bits per component in 16-bit containers.
+    XVYU12_16161616 = b"XV36", [64], (1, 1), false, true;
+    /// Packed 4:4:4 YCbCr with 16 bits per component.
+    XVYU16161616 = b"XV48", [64], (1, 1), false, true;
+    /// Packed 4:2:0 YCbCr with alpha in 2x2 blocks, 10 bits per component.
+    Y0L0 = b"Y0L0", [16], (2, 2), true, true;
+    /// Packed 4:2:0 YCbCr in 2x2 blocks, 10 bits per component.
+    X0L0 = b"X0L0", [16], (2, 2), false, true;
+    /// Packed 4:2:0 YCbCr with alpha in 2x2 blocks, 10 bits per component.
+    Y0L2 = b"Y0L2", [16], (2, 2), true, true;
+    /// Packed 4:2:0 YCbCr in 2x2 blocks, 10 bits per component.
+    X0L2 = b"X0L2", [16], (2, 2), false, true;
+    /// 4:2:0 YCbCr with 8 bits per component, only usable with compressed modifiers.
+    YUV420_8BIT = b"YU08", [0], (2, 2), false, true;
+    /// 4:2:0 YCbCr with 10 bits per component, only usable with compressed modifiers.
+    YUV420_10BIT = b"YU10", [0], (2, 2), false, true;
+    /// XRGB8888 with a separate 8-bit alpha plane.
+    XRGB8888_A8 = b"XRA8", [32, 8], (1, 1), true, false;
+    /// XBGR8888 with a separate 8-bit alpha plane.
+    XBGR8888_A8 = b"XBA8", [32, 8], (1, 1), true, false;
+    /// RGBX8888 with a separate 8-bit alpha plane.
+    RGBX8888_A8 = b"RXA8", [32, 8], (1, 1), true, false;
+    /// BGRX8888 with a separate 8-bit alpha plane.
+    BGRX8888_A8 = b"BXA8", [32, 8], (1, 1), true, false;
+    /// RGB888 with a separate 8-bit alpha plane.
+    RGB888_A8 = b"R8A8", [24, 8], (1, 1), true, false;
+    /// BGR888 with a separate 8-bit alpha plane.
+    BGR888_A8 = b"B8A8", [24, 8], (1, 1), true, false;
+    /// RGB565 with a separate 8-bit alpha plane.
+    RGB565_A8 = b"R5A8", [16, 8], (1, 1), true, false;
+    /// BGR565 with a separate 8-bit alpha plane.
+    BGR565_A8 = b"B5A8", [16, 8], (1, 1), true, false;
+    /// 2-plane 4:2:0 YCbCr, with interleaved Cb:Cr.
+    NV12 = b"NV12", [8, 16], (2, 2), false, true;
+    /// 2-plane 4:2:0 YCbCr, with interleaved Cr:Cb.
+    NV21 = b"NV21", [8, 16], (2, 2), false, true;
+    /// 2-plane 4:2:2 YCbCr, with interleaved Cb:Cr.
+    NV16 = b"NV16", [8, 16], (2, 1), false, true;
+    /// 2-plane 4:2:2 YCbCr, with interleaved Cr:Cb.
+    NV61 = b"NV61", [8, 16], (2, 1), false, true;
+    /// 2-plane 4:4:4 YCbCr, with interleaved Cb:Cr.
+    NV24 = b"NV24", [8, 16], (1, 1), false, true;
+    /// 2-plane 4:4:4 YCbCr, with interleaved Cr:Cb.
+    NV42 = b"NV42", [8, 16], (1, 1), false, true;
+    /// 2-plane 4:2:0 YCbCr with tightly-packed 10-bit components.
+    NV15 = b"NV15", [10, 20], (2, 2), false, true;
+    /// 2-plane 4:2:2 YCbCr with tightly-packed 10-bit components.
+    NV20 = b"NV20", [10, 20], (2, 1), false, true;
+    /// 2-plane 4:4:4 YCbCr with tightly-packed 10-bit components.
+    NV30 = b"NV30", [10, 20], (1, 1), false, true;
+    /// 2-plane 4:2:2 YCbCr with 10 bits per component in 16-bit containers.
+    P210 = b"P210", [16, 32], (2, 1), false, true;
+    /// 2-plane 4:2:0 YCbCr with 10 bits per component in 16-bit containers.
+    P010 = b"P010", [16, 32], (2, 2), false, true;
+    /// 2-plane 4:2:0 YCbCr with 12 bits per component in 16-bit containers.
+    P012 = b"P012", [16, 32], (2, 2), false, true;
+    /// 2-plane 4:2:0 YCbCr with 16 bits per component.
+    P016 = b"P016", [16, 32], (2, 2), false, true;
+    /// 2-plane 4:2:0 YCbCr with three 10-bit components packed into each 32 bits.
+    P030 = b"P030", [0, 0], (2, 2), false, true;
+    /// 3-plane 4:4:4 YCbCr with 10 bits per component in 16-bit containers, Y:Cb:Cr plane order.
+    Q410 = b"Q410", [16, 16, 16], (1, 1), false, true;
+    /// 3-plane 4:4:4 YCbCr with 10 bits per component in 16-bit containers, Y:Cr:Cb plane order.
+    Q401 = b"Q401", [16, 16, 16], (1, 1), false, true;
+    /// 3-plane 4:1:0 YCbCr, Y:Cb:Cr plane order.
+    YUV410 = b"YUV9", [8, 8, 8], (4, 4), false, true;
+    /// 3-plane 4:1:0 YCbCr, Y:Cr:Cb plane order.
+    YVU410 = b"YVU9", [8, 8, 8], (4, 4), false, true;
+    /// 3-plane 4:1:1 YCbCr, Y:Cb:Cr plane order.
+    YUV411 = b"YU11", [8, 8, 8], (4, 1), false, true;
+    /// 3-plane 4:1:1 YCbCr, Y:Cr:Cb plane order.
+    YVU411 = b"YV11", [8, 8, 8], (4, 1), false, true;
+    /// 3-plane 4:2:0 YCbCr, Y:Cb:Cr plane order.
+    YUV420 = b"YU12", [8, 8, 8], (2, 2), false, true;
+    /// 3-plane 4:2:0 YCbCr, Y:Cr:Cb plane order.
+    YVU420 = b"YV12", [8, 8, 8], (2, 2), false, true;
+    /// 3-plane 4:2:2 YCbCr, Y:Cb:Cr plane order.
+    YUV422 = b"YU16", [8, 8, 8], (2, 1), false, true;
+    /// 3-plane 4:2:2 YCbCr, Y:Cr:Cb plane order.
+    YVU422 = b"YV16", [8, 8, 8], (2, 1), false, true;
+    /// 3-plane 4:4:4 YCbCr, Y:Cb:Cr plane order.
+    YUV444 = b"YU24", [8, 8, 8], (1, 1), false, true;
+    /// 3-plane 4:4:4 YCbCr, Y:Cr:Cb plane order.
+    YVU444 = b"YV24", [8, 8, 8], (1, 1), false, true;
+    /// 3-plane 4:2:0 YCbCr with 10 bits per component in 16-bit containers.
+    S010 = b"S010", [16, 16, 16], (2, 2), false, true;
+    /// 3-plane 4:2:2 YCbCr with 10 bits per component in 16-bit containers.
+    S210 = b"S210", [16, 16, 16], (2, 1), false, true;
+    /// 3-plane 4:4:4 YCbCr with 10 bits per component in 16-bit containers.
+    S410 = b"S410", [16, 16, 16], (1, 1), false, true;
+    /// 3-plane 4:2:0 YCbCr with 12 bits per component in 16-bit containers.
+    S012 = b"S012", [16, 16, 16], (2, 2), false, true;
+    /// 3-plane 4:2:2 YCbCr with 12 bits per component in 16-bit containers.
+    S212 = b"S212", [16, 16, 16], (2, 1), false, true;
+    /// 3-plane 4:4:4 YCbCr with 12 bits per component in 16-bit containers.
+    S412 = b"S412", [16, 16, 16], (1, 1), false, true;
+    /// 3-plane 4:2:0 YCbCr with 16 bits per component.
+    S016 = b"S016", [16, 16, 16], (2, 2), false, true;
+    /// 3-plane 4:2:2 YCbCr with 16 bits per component.
+    S216 = b"S216", [16, 16, 16], (2, 1), false, true;
+    /// 3-plane 4:4:4 YCbCr with 16 bits per component.
+    S416 = b"S416", [16, 16, 16], (1, 1), false, true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packed_rgb() {
+        assert_eq!(Fourcc::XRGB8888.to_raw(), 0x34325258);
+        assert_eq!(Fourcc::XRGB8888.bits_per_pixel(), Some(32));
+        assert_eq!(Fourcc::XRGB8888.legacy_depth(), Some(24));
+        assert_eq!(Fourcc::RGB565.bits_per_pixel(), Some(16));
+        assert_eq!(Fourcc::RGB565.legacy_depth(), Some(16));
+        assert_eq!(Fourcc::ARGB8888.legacy_depth(), Some(32));
+        assert_eq!(Fourcc::RGBX8888.legacy_depth(), None);
+
+        let info = Fourcc::XRGB8888.info().unwrap();
+        assert_eq!(info.num_planes, 1);
+        assert!(!info.has_alpha);
+        assert!(!info.is_yuv);
+        assert!(Fourcc::ARGB8888.info().unwrap().has_alpha);
+    }
+
+    #[test]
+    fn multi_plane_yuv() {
+        // NV12 has an 8-bit luma plane and a half-resolution plane of
+        // 16-bit chroma pairs, so there's no single bits-per-pixel value.
+        assert_eq!(Fourcc::NV12.bits_per_pixel(), None);
+        assert_eq!(Fourcc::NV12.legacy_depth(), None);
+        let info = Fourcc::NV12.info().unwrap();
+        assert_eq!(info.num_planes, 2);
+        assert!(info.is_yuv);
+        assert!(info.is_subsampled());
+        assert_eq!(info.bytes_per_pixel(0), Some(1));
+        assert_eq!(info.bytes_per_pixel(1), Some(2));
+        assert_eq!(info.plane_width(1, 1921), 961);
+        assert_eq!(info.plane_height(1, 1080), 540);
+    }
+
+    #[test]
+    fn names() {
+        for info in FORMATS {
+            let name = info.format.name().unwrap();
+            assert_eq!(Fourcc::from_name(name), Some(info.format), "{name}");
+        }
+        assert_eq!(Fourcc::from_name("XRGB8888"), Some(Fourcc::XRGB8888));
+        assert_eq!(Fourcc::XRGB8888.name(), Some("XRGB8888"));
+        assert_eq!(Fourcc::from_name("DRM_FORMAT_XRGB8888"), None);
+        assert_eq!(Fourcc::from_name("xrgb8888"), None);
+        assert_eq!(Fourcc::INVALID.name(), None);
+
+        // The big-endian flag doesn't change the format's name.
+        let big = Fourcc::RGB565.with_endianness(Endianness::Big);
+        assert!(big.is_big_endian());
+        assert_eq!(big.name(), Some("RGB565"));
+        assert_eq!(big.chars(), *b"RG16");
+    }
+}
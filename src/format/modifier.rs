@@ -0,0 +1,975 @@
+use core::fmt;
+
+/// A DRM format modifier, as defined in the kernel's `drm_fourcc.h`.
+///
+/// A modifier describes how the pixels of a buffer are arranged in memory
+/// beyond what the [`super::Fourcc`] format code describes, such as tiling
+/// or compression. The most significant byte identifies the vendor that
+/// defined the modifier, and the remaining 56 bits have a vendor-specific
+/// meaning that [`Modifier::layout`] can decode for some vendors.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Modifier(pub u64);
+
+const VALUE_MASK: u64 = (1 << 56) - 1;
+
+impl Modifier {
+    /// The buffer is not tiled or compressed, and so pixels are arranged
+    /// in row-major order with each row starting at a multiple of the
+    /// pitch.
+    pub const LINEAR: Self = Self(0);
+
+    /// Represents the absence of a modifier, or that the buffer's layout
+    /// is decided implicitly by the driver.
+    pub const INVALID: Self = Self::new(ModifierVendor::None as u8, VALUE_MASK);
+
+    /// Intel legacy X tiling.
+    pub const INTEL_X_TILED: Self = Self::new(ModifierVendor::Intel as u8, 1);
+    /// Intel legacy Y tiling.
+    pub const INTEL_Y_TILED: Self = Self::new(ModifierVendor::Intel as u8, 2);
+    /// Intel Yf tiling.
+    pub const INTEL_YF_TILED: Self = Self::new(ModifierVendor::Intel as u8, 3);
+    /// Intel Tile4 tiling.
+    pub const INTEL_4_TILED: Self = Self::new(ModifierVendor::Intel as u8, 9);
+    /// Broadcom VC4 "T" tiling.
+    pub const BROADCOM_VC4_T_TILED: Self = Self::new(ModifierVendor::Broadcom as u8, 1);
+    /// Broadcom V3D "UIF" tiling.
+    pub const BROADCOM_UIF: Self = Self::new(ModifierVendor::Broadcom as u8, 6);
+    /// Qualcomm compressed format.
+    pub const QCOM_COMPRESSED: Self = Self::new(ModifierVendor::Qcom as u8, 1);
+    /// Qualcomm Tiled2 tiling.
+    pub const QCOM_TILED2: Self = Self::new(ModifierVendor::Qcom as u8, 2);
+    /// Qualcomm Tiled3 tiling.
+    pub const QCOM_TILED3: Self = Self::new(ModifierVendor::Qcom as u8, 3);
+    /// NVIDIA Tegra tiling.
+    pub const NVIDIA_TEGRA_TILED: Self = Self::new(ModifierVendor::Nvidia as u8, 1);
+
+    /// Build a modifier from a vendor code and a vendor-specific value.
+    ///
+    /// Only the least significant 56 bits of `value` are used.
+    #[inline(always)]
+    pub const fn new(vendor: u8, value: u64) -> Self {
+        Self(((vendor as u64) << 56) | (value & VALUE_MASK))
+    }
+
+    /// Returns the raw `u64` value of the modifier.
+    #[inline(always)]
+    pub const fn to_raw(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the code of the vendor that defined the modifier.
+    #[inline(always)]
+    pub const fn vendor_code(self) -> u8 {
+        (self.0 >> 56) as u8
+    }
+
+    /// Returns the vendor that defined the modifier.
+    #[inline]
+    pub const fn vendor(self) -> ModifierVendor {
+        ModifierVendor::from_code(self.vendor_code())
+    }
+
+    /// Returns the vendor-specific part of the modifier.
+    #[inline(always)]
+    pub const fn value(self) -> u64 {
+        self.0 & VALUE_MASK
+    }
+
+    #[inline(always)]
+    pub const fn is_linear(self) -> bool {
+        self.0 == Self::LINEAR.0
+    }
+
+    #[inline(always)]
+    pub const fn is_invalid(self) -> bool {
+        self.0 == Self::INVALID.0
+    }
+
+    /// Decode the modifier into a description of the memory layout it
+    /// represents, if it belongs to a vendor whose modifiers this library
+    /// understands.
+    pub fn layout(self) -> ModifierLayout {
+        if self.is_linear() {
+            return ModifierLayout::Linear;
+        }
+        if self.is_invalid() {
+            return ModifierLayout::Invalid;
+        }
+        let value = self.value();
+        let ret = match self.vendor() {
+            ModifierVendor::Intel => IntelModifier::decode(value).map(ModifierLayout::Intel),
+            ModifierVendor::Amd => AmdModifier::decode(value).map(ModifierLayout::Amd),
+            ModifierVendor::Nvidia => NvidiaModifier::decode(value).map(ModifierLayout::Nvidia),
+            ModifierVendor::Qcom => QcomModifier::decode(value).map(ModifierLayout::Qcom),
+            ModifierVendor::Broadcom => {
+                BroadcomModifier::decode(value).map(ModifierLayout::Broadcom)
+            }
+            ModifierVendor::Arm => ArmModifier::decode(value).map(ModifierLayout::Arm),
+            _ => None,
+        };
+        ret.unwrap_or(ModifierLayout::Unknown)
+    }
+
+    /// Returns true if the modifier is known to describe a compressed
+    /// layout, which typically requires additional planes for metadata
+    /// and cannot be accessed directly by software.
+    pub fn is_compressed(self) -> bool {
+        match self.layout() {
+            ModifierLayout::Intel(m) => m.ccs.is_some(),
+            ModifierLayout::Amd(m) => m.dcc,
+            ModifierLayout::Nvidia(NvidiaModifier::BlockLinear(m)) => m.compression != 0,
+            ModifierLayout::Qcom(m) => m == QcomModifier::Compressed,
+            ModifierLayout::Arm(m) => matches!(m, ArmModifier::Afbc(_) | ArmModifier::Afrc(_)),
+            _ => false,
+        }
+    }
+}
+
+impl From<u64> for Modifier {
+    #[inline(always)]
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Modifier> for u64 {
+    #[inline(always)]
+    fn from(value: Modifier) -> Self {
+        value.0
+    }
+}
+
+impl fmt::Display for Modifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.layout() {
+            ModifierLayout::Linear => f.write_str("LINEAR"),
+            ModifierLayout::Invalid => f.write_str("INVALID"),
+            ModifierLayout::Intel(m) => write!(f, "INTEL:{m}"),
+            ModifierLayout::Amd(m) => write!(f, "AMD:{m}"),
+            ModifierLayout::Nvidia(m) => write!(f, "NVIDIA:{m}"),
+            ModifierLayout::Broadcom(m) => write!(f, "BROADCOM:{m}"),
+            ModifierLayout::Qcom(m) => write!(f, "QCOM:{m}"),
+            ModifierLayout::Arm(m) => write!(f, "ARM:{m}"),
+            ModifierLayout::Unknown => match self.vendor().name() {
+                Some(vendor) => write!(f, "{vendor}:{:#x}", self.value()),
+                None => write!(f, "{:#04x}:{:#x}", self.vendor_code(), self.value()),
+            },
+        }
+    }
+}
+
+impl fmt::Debug for Modifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Modifier({:#018x} {self})", self.0)
+    }
+}
+
+/// The vendors that can define format modifiers, as identified by the
+/// most significant byte of a [`Modifier`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum ModifierVendor {
+    None = 0,
+    Intel = 1,
+    Amd = 2,
+    Nvidia = 3,
+    Samsung = 4,
+    Qcom = 5,
+    Vivante = 6,
+    Broadcom = 7,
+    Arm = 8,
+    Allwinner = 9,
+    Amlogic = 10,
+    Mediatek = 11,
+    Apple = 12,
+    Other = !0, // Not used by kernel, but used by us if we see a vendor we don't know
+}
+
+impl ModifierVendor {
+    #[inline]
+    pub const fn from_code(code: u8) -> Self {
+        if code < 13 {
+            // Safety: all values in this range are valid representations
+            // of this enum, as described above.
+            unsafe { core::mem::transmute::<u8, Self>(code) }
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Returns the vendor's name as used in `drm_fourcc.h`, or `None`
+    /// for [`Self::Other`].
+    pub const fn name(self) -> Option<&'static str> {
+        Some(match self {
+            Self::None => "NONE",
+            Self::Intel => "INTEL",
+            Self::Amd => "AMD",
+            Self::Nvidia => "NVIDIA",
+            Self::Samsung => "SAMSUNG",
+            Self::Qcom => "QCOM",
+            Self::Vivante => "VIVANTE",
+            Self::Broadcom => "BROADCOM",
+            Self::Arm => "ARM",
+            Self::Allwinner => "ALLWINNER",
+            Self::Amlogic => "AMLOGIC",
+            Self::Mediatek => "MTK",
+            Self::Apple => "APPLE",
+            Self::Other => return None,
+        })
+    }
+}
+
+impl From<u8> for ModifierVendor {
+    #[inline(always)]
+    fn from(value: u8) -> Self {
+        Self::from_code(value)
+    }
+}
+
+/// A decoded [`Modifier`], as returned by [`Modifier::layout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ModifierLayout {
+    Linear,
+    Invalid,
+    Intel(IntelModifier),
+    Amd(AmdModifier),
+    Nvidia(NvidiaModifier),
+    Broadcom(BroadcomModifier),
+    Qcom(QcomModifier),
+    Arm(ArmModifier),
+    /// The modifier belongs to a vendor whose modifiers this library
+    /// doesn't understand, or uses a value that the vendor hasn't defined.
+    Unknown,
+}
+
+/// A decoded Intel modifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntelModifier {
+    pub tiling: IntelTiling,
+    /// The kind of color control surface used for lossless compression,
+    /// if any.
+    pub ccs: Option<IntelCcs>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntelTiling {
+    X,
+    Y,
+    Yf,
+    Tile4,
+}
+
+/// The kinds of Intel color control surface, each of which is specific to
+/// a particular range of hardware generations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntelCcs {
+    /// Render compression for Skylake through Ice Lake.
+    Ccs,
+    /// Render compression for Tiger Lake and Alder Lake.
+    Gen12Render,
+    /// Media compression for Tiger Lake and Alder Lake.
+    Gen12Media,
+    /// Render compression with clear color for Tiger Lake and Alder Lake.
+    Gen12RenderClearColor,
+    /// Render compression for DG2.
+    Dg2Render,
+    /// Media compression for DG2.
+    Dg2Media,
+    /// Render compression with clear color for DG2.
+    Dg2RenderClearColor,
+    /// Render compression for Meteor Lake.
+    MtlRender,
+    /// Media compression for Meteor Lake.
+    MtlMedia,
+    /// Render compression with clear color for Meteor Lake.
+    MtlRenderClearColor,
+    /// Flat compression for Lunar Lake, which needs no extra planes.
+    Lnl,
+    /// Flat compression for Battlemage, which needs no extra planes.
+    Bmg,
+}
+
+const INTEL_MODIFIERS: &[(u64, &str, IntelTiling, Option<IntelCcs>)] = &[
+    (1, "X_TILED", IntelTiling::X, None),
+    (2, "Y_TILED", IntelTiling::Y, None),
+    (3, "Yf_TILED", IntelTiling::Yf, None),
+    (4, "Y_TILED_CCS", IntelTiling::Y, Some(IntelCcs::Ccs)),
+    (5, "Yf_TILED_CCS", IntelTiling::Yf, Some(IntelCcs::Ccs)),
+    (
+        6,
+        "Y_TILED_GEN12_RC_CCS",
+        IntelTiling::Y,
+        Some(IntelCcs::Gen12Render),
+    ),
+    (
+        7,
+        "Y_TILED_GEN12_MC_CCS",
+        IntelTiling::Y,
+        Some(IntelCcs::Gen12Media),
+    ),
+    (
+        8,
+        "Y_TILED_GEN12_RC_CCS_CC",
+        IntelTiling::Y,
+        Some(IntelCcs::Gen12RenderClearColor),
+    ),
+    (9, "4_TILED", IntelTiling::Tile4, None),
+    (
+        10,
+        "4_TILED_DG2_RC_CCS",
+        IntelTiling::Tile4,
+        Some(IntelCcs::Dg2Render),
+    ),
+    (
+        11,
+        "4_TILED_DG2_MC_CCS",
+        IntelTiling::Tile4,
+        Some(IntelCcs::Dg2Media),
+    ),
+    (
+        12,
+        "4_TILED_DG2_RC_CCS_CC",
+        IntelTiling::Tile4,
+        Some(IntelCcs::Dg2RenderClearColor),
+    ),
+    (
+        13,
+        "4_TILED_MTL_RC_CCS",
+        IntelTiling::Tile4,
+        Some(IntelCcs::MtlRender),
+    ),
+    (
+        14,
+        "4_TILED_MTL_MC_CCS",
+        IntelTiling::Tile4,
+        Some(IntelCcs::MtlMedia),
+    ),
+    (
+        15,
+        "4_TILED_MTL_RC_CCS_CC",
+        IntelTiling::Tile4,
+        Some(IntelCcs::MtlRenderClearColor),
+    ),
+    (
+        16,
+        "4_TILED_LNL_CCS",
+        IntelTiling::Tile4,
+        Some(IntelCcs::Lnl),
+    ),
+    (
+        17,
+        "4_TILED_BMG_CCS",
+        IntelTiling::Tile4,
+        Some(IntelCcs::Bmg),
+    ),
+];
+
+impl IntelModifier {
+    fn decode(value: u64) -> Option<Self> {
+        INTEL_MODIFIERS
+            .iter()
+            .find(|(v, ..)| *v == value)
+            .map(|(_, _, tiling, ccs)| Self {
+                tiling: *tiling,
+                ccs: *ccs,
+            })
+    }
+
+    /// Returns the name of the modifier as used in `drm_fourcc.h`, without
+    /// the `I915_FORMAT_MOD_` prefix.
+    pub fn name(&self) -> &'static str {
+        INTEL_MODIFIERS
+            .iter()
+            .find(|(_, _, tiling, ccs)| *tiling == self.tiling && *ccs == self.ccs)
+            .map_or("UNKNOWN", |(_, name, ..)| name)
+    }
+}
+
+impl fmt::Display for IntelModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A decoded AMD modifier, as used for GFX9 and later hardware.
+///
+/// The meaning of most of the fields depends on [`Self::tile_version`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AmdModifier {
+    pub tile_version: u8,
+    /// The swizzle mode, whose meaning depends on the tile version.
+    pub tile: u8,
+    /// True if the buffer uses delta color compression.
+    pub dcc: bool,
+    /// True if there is an additional displayable DCC plane.
+    pub dcc_retile: bool,
+    pub dcc_pipe_align: bool,
+    pub dcc_independent_64b: bool,
+    pub dcc_independent_128b: bool,
+    /// The maximum compressed block size, as a power of two multiple of
+    /// 64 bytes.
+    pub dcc_max_compressed_block: u8,
+    pub dcc_constant_encode: bool,
+    pub pipe_xor_bits: u8,
+    pub bank_xor_bits: u8,
+    pub packers: u8,
+    pub rb: u8,
+    pub pipe: u8,
+}
+
+impl AmdModifier {
+    pub const TILE_VER_GFX9: u8 = 1;
+    pub const TILE_VER_GFX10: u8 = 2;
+    pub const TILE_VER_GFX10_RBPLUS: u8 = 3;
+    pub const TILE_VER_GFX11: u8 = 4;
+    pub const TILE_VER_GFX12: u8 = 5;
+
+    fn decode(value: u64) -> Option<Self> {
+        let field = |shift: u32, mask: u64| ((value >> shift) & mask) as u8;
+        let ret = Self {
+            tile_version: field(0, 0xff),
+            tile: field(8, 0x1f),
+            dcc: field(13, 1) != 0,
+            dcc_retile: field(14, 1) != 0,
+            dcc_pipe_align: field(15, 1) != 0,
+            dcc_independent_64b: field(16, 1) != 0,
+            dcc_independent_128b: field(17, 1) != 0,
+            dcc_max_compressed_block: field(18, 3),
+            dcc_constant_encode: field(20, 1) != 0,
+            pipe_xor_bits: field(21, 7),
+            bank_xor_bits: field(24, 7),
+            packers: field(27, 7),
+            rb: field(30, 7),
+            pipe: field(33, 7),
+        };
+        if ret.tile_version == 0 || (value >> 36) != 0 {
+            return None;
+        }
+        Some(ret)
+    }
+
+    /// Returns the name of the hardware generation the modifier is for.
+    pub fn tile_version_name(&self) -> Option<&'static str> {
+        Some(match self.tile_version {
+            Self::TILE_VER_GFX9 => "GFX9",
+            Self::TILE_VER_GFX10 => "GFX10",
+            Self::TILE_VER_GFX10_RBPLUS => "GFX10_RBPLUS",
+            Self::TILE_VER_GFX11 => "GFX11",
+            Self::TILE_VER_GFX12 => "GFX12",
+            _ => return None,
+        })
+    }
+
+    /// Returns the name of the swizzle mode, which depends on the tile
+    /// version.
+    pub fn tile_name(&self) -> Option<&'static str> {
+        if self.tile_version >= Self::TILE_VER_GFX12 {
+            return Some(match self.tile {
+                1 => "256B_2D",
+                2 => "4K_2D",
+                3 => "64K_2D",
+                4 => "256K_2D",
+                _ => return None,
+            });
+        }
+        Some(match self.tile {
+            9 => "64K_S",
+            10 => "64K_D",
+            22 => "4K_D_X",
+            25 => "64K_S_X",
+            26 => "64K_D_X",
+            27 => "64K_R_X",
+            31 => "256K_R_X",
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for AmdModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.tile_version_name() {
+            Some(name) => f.write_str(name)?,
+            None => write!(f, "TILE_VER={}", self.tile_version)?,
+        }
+        match self.tile_name() {
+            Some(name) => write!(f, ",{name}")?,
+            None => write!(f, ",TILE={}", self.tile)?,
+        }
+        if self.dcc {
+            f.write_str(",DCC")?;
+            for (flag, name) in [
+                (self.dcc_retile, "DCC_RETILE"),
+                (self.dcc_pipe_align, "DCC_PIPE_ALIGN"),
+                (self.dcc_independent_64b, "DCC_INDEPENDENT_64B"),
+                (self.dcc_independent_128b, "DCC_INDEPENDENT_128B"),
+                (self.dcc_constant_encode, "DCC_CONSTANT_ENCODE"),
+            ] {
+                if flag {
+                    write!(f, ",{name}")?;
+                }
+            }
+            write!(
+                f,
+                ",DCC_MAX_COMPRESSED_BLOCK={}B",
+                64_u32 << self.dcc_max_compressed_block
+            )?;
+        }
+        for (v, name) in [
+            (self.pipe_xor_bits, "PIPE_XOR_BITS"),
+            (self.bank_xor_bits, "BANK_XOR_BITS"),
+            (self.packers, "PACKERS"),
+            (self.rb, "RB"),
+            (self.pipe, "PIPE"),
+        ] {
+            if v != 0 {
+                write!(f, ",{name}={v}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A decoded NVIDIA modifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NvidiaModifier {
+    /// The legacy Tegra tiled layout.
+    TegraTiled,
+    BlockLinear(NvidiaBlockLinear),
+}
+
+/// The parameters of an NVIDIA 2D block-linear layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NvidiaBlockLinear {
+    /// The base-two logarithm of the block height, in GOBs.
+    pub log2_block_height: u8,
+    /// The GPU-specific page kind.
+    pub page_kind: u8,
+    /// The GOB height and page kind generation, which distinguishes
+    /// Tegra from desktop GPUs and older from newer generations.
+    pub generation: u8,
+    /// The sector layout, which is zero for Tegra and one for desktop GPUs.
+    pub sector_layout: u8,
+    /// The lossless compression type, which is zero if the buffer is not
+    /// compressed.
+    pub compression: u8,
+}
+
+impl NvidiaModifier {
+    fn decode(value: u64) -> Option<Self> {
+        if value == 1 {
+            return Some(Self::TegraTiled);
+        }
+        // All block-linear layouts have bit 4 set, and the legacy
+        // "16Bx2" modifiers are a subset of this encoding.
+        if (value & 0x10) == 0 || (value & 0xfe0) != 0 || (value >> 26) != 0 {
+            return None;
+        }
+        Some(Self::BlockLinear(NvidiaBlockLinear {
+            log2_block_height: (value & 0xf) as u8,
+            page_kind: ((value >> 12) & 0xff) as u8,
+            generation: ((value >> 20) & 0x3) as u8,
+            sector_layout: ((value >> 22) & 0x1) as u8,
+            compression: ((value >> 23) & 0x7) as u8,
+        }))
+    }
+}
+
+impl fmt::Display for NvidiaModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TegraTiled => f.write_str("TEGRA_TILED"),
+            Self::BlockLinear(m) => write!(
+                f,
+                "BLOCK_LINEAR_2D(h={},k={:#x},g={},s={},c={})",
+                m.log2_block_height, m.page_kind, m.generation, m.sector_layout, m.compression
+            ),
+        }
+    }
+}
+
+/// A decoded Broadcom modifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroadcomModifier {
+    /// The VC4 "T" tiling layout.
+    Vc4TTiled,
+    /// The "SAND" column layout used for video decoder output, with
+    /// columns of the given width in bytes and height in lines.
+    ///
+    /// A column height of zero means that the height is derived from the
+    /// buffer's height.
+    Sand {
+        column_width: u32,
+        column_height: u64,
+    },
+    /// The V3D "UIF" tiling layout.
+    Uif,
+}
+
+impl BroadcomModifier {
+    fn decode(value: u64) -> Option<Self> {
+        let param = value >> 8;
+        let column_width = match value & 0xff {
+            1 if param == 0 => return Some(Self::Vc4TTiled),
+            6 if param == 0 => return Some(Self::Uif),
+            2 => 32,
+            3 => 64,
+            4 => 128,
+            5 => 256,
+            _ => return None,
+        };
+        Some(Self::Sand {
+            column_width,
+            column_height: param,
+        })
+    }
+}
+
+impl fmt::Display for BroadcomModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Vc4TTiled => f.write_str("VC4_T_TILED"),
+            Self::Sand {
+                column_width,
+                column_height,
+            } => write!(f, "SAND{column_width}(COL_HEIGHT={column_height})"),
+            Self::Uif => f.write_str("UIF"),
+        }
+    }
+}
+
+/// A decoded Qualcomm modifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QcomModifier {
+    Compressed,
+    Tiled2,
+    Tiled3,
+}
+
+impl QcomModifier {
+    fn decode(value: u64) -> Option<Self> {
+        Some(match value {
+            1 => Self::Compressed,
+            2 => Self::Tiled2,
+            3 => Self::Tiled3,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for QcomModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Compressed => "COMPRESSED",
+            Self::Tiled2 => "TILED2",
+            Self::Tiled3 => "TILED3",
+        })
+    }
+}
+
+/// A decoded ARM modifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArmModifier {
+    /// Arm Framebuffer Compression.
+    Afbc(AfbcModifier),
+    /// Arm Fixed-Rate Compression.
+    Afrc(AfrcModifier),
+    /// 16x16 blocks with each block stored in "U" order.
+    BlockUInterleaved16x16,
+}
+
+/// The parameters of an Arm Framebuffer Compression layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AfbcModifier {
+    /// The size of each superblock, in pixels, as `(width, height)`.
+    ///
+    /// For the mixed `32x8_64x4` mode this is the size used for the
+    /// luma plane.
+    pub block_size: (u32, u32),
+    /// True if the mixed `32x8_64x4` block size is in use, where chroma
+    /// planes use 64x4 blocks.
+    pub mixed_block_size: bool,
+    /// Lossless color transform.
+    pub ytr: bool,
+    /// Block split.
+    pub split: bool,
+    /// Sparse layout.
+    pub sparse: bool,
+    /// Copy-block restrict.
+    pub cbr: bool,
+    /// Tiled layout.
+    pub tiled: bool,
+    /// Solid color blocks.
+    pub solid_color: bool,
+    /// Double-buffered header.
+    pub double_buffer: bool,
+    /// Buffer content hints.
+    pub bch: bool,
+    /// Uncompressed storage mode.
+    pub usm: bool,
+}
+
+/// The parameters of an Arm Fixed-Rate Compression layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AfrcModifier {
+    /// The coding unit size in bytes for plane 0, or for the only plane
+    /// of an RGB format.
+    pub cu_size_p0: Option<u32>,
+    /// The coding unit size in bytes for planes 1 and 2 of a YUV format.
+    pub cu_size_p12: Option<u32>,
+    /// True if the buffer uses the scanline-optimized layout rather than
+    /// the rotation-optimized layout.
+    pub scan_layout: bool,
+}
+
+impl ArmModifier {
+    fn decode(value: u64) -> Option<Self> {
+        let typ = value >> 52;
+        let value = value & ((1 << 52) - 1);
+        match typ {
+            0 => AfbcModifier::decode(value).map(Self::Afbc),
+            1 if value == 1 => Some(Self::BlockUInterleaved16x16),
+            2 => AfrcModifier::decode(value).map(Self::Afrc),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ArmModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Afbc(m) => write!(f, "AFBC({m})"),
+            Self::Afrc(m) => write!(f, "AFRC({m})"),
+            Self::BlockUInterleaved16x16 => f.write_str("16X16_BLOCK_U_INTERLEAVED"),
+        }
+    }
+}
+
+impl AfbcModifier {
+    fn decode(value: u64) -> Option<Self> {
+        if (value >> 13) != 0 {
+            return None;
+        }
+        let (block_size, mixed_block_size) = match value & 0xf {
+            1 => ((16, 16), false),
+            2 => ((32, 8), false),
+            3 => ((64, 4), false),
+            4 => ((32, 8), true),
+            _ => return None,
+        };
+        let flag = |bit: u32| (value & (1 << bit)) != 0;
+        Some(Self {
+            block_size,
+            mixed_block_size,
+            ytr: flag(4),
+            split: flag(5),
+            sparse: flag(6),
+            cbr: flag(7),
+            tiled: flag(8),
+            solid_color: flag(9),
+            double_buffer: flag(10),
+            bch: flag(11),
+            usm: flag(12),
+        })
+    }
+}
+
+impl fmt::Display for AfbcModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mixed_block_size {
+            f.write_str("BLOCK_SIZE=32x8_64x4")?;
+        } else {
+            let (w, h) = self.block_size;
+            write!(f, "BLOCK_SIZE={w}x{h}")?;
+        }
+        for (flag, name) in [
+            (self.ytr, "YTR"),
+            (self.split, "SPLIT"),
+            (self.sparse, "SPARSE"),
+            (self.cbr, "CBR"),
+            (self.tiled, "TILED"),
+            (self.solid_color, "SC"),
+            (self.double_buffer, "DB"),
+            (self.bch, "BCH"),
+            (self.usm, "USM"),
+        ] {
+            if flag {
+                write!(f, ",{name}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AfrcModifier {
+    fn decode(value: u64) -> Option<Self> {
+        if (value >> 9) != 0 {
+            return None;
+        }
+        let cu_size = |v: u64| -> Result<Option<u32>, ()> {
+            match v {
+                0 => Ok(None),
+                1 => Ok(Some(16)),
+                2 => Ok(Some(24)),
+                3 => Ok(Some(32)),
+                _ => Err(()),
+            }
+        };
+        Some(Self {
+            cu_size_p0: cu_size(value & 0xf).ok()?,
+            cu_size_p12: cu_size((value >> 4) & 0xf).ok()?,
+            scan_layout: (value & (1 << 8)) != 0,
+        })
+    }
+}
+
+impl fmt::Display for AfrcModifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.cu_size_p0 {
+            Some(v) => write!(f, "P0=CU_SIZE_{v}")?,
+            None => f.write_str("P0=NONE")?,
+        }
+        if let Some(v) = self.cu_size_p12 {
+            write!(f, ",P12=CU_SIZE_{v}")?;
+        }
+        f.write_str(if self.scan_layout { ",SCAN" } else { ",ROT" })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use std::format;
+
+    #[test]
+    fn intel_y_tiled_ccs() {
+        // I915_FORMAT_MOD_Y_TILED_CCS
+        let modifier = Modifier(0x0100000000000004);
+        assert_eq!(modifier.vendor(), ModifierVendor::Intel);
+        assert_eq!(
+            modifier.layout(),
+            ModifierLayout::Intel(IntelModifier {
+                tiling: IntelTiling::Y,
+                ccs: Some(IntelCcs::Ccs),
+            })
+        );
+        assert!(modifier.is_compressed());
+        assert_eq!(format!("{modifier}"), "INTEL:Y_TILED_CCS");
+        assert_eq!(
+            format!("{modifier:?}"),
+            "Modifier(0x0100000000000004 INTEL:Y_TILED_CCS)"
+        );
+        assert!(!Modifier::INTEL_Y_TILED.is_compressed());
+    }
+
+    #[test]
+    fn amd_gfx9_dcc() {
+        // AMD_FMT_MOD | TILE_VERSION(GFX9) | TILE(GFX9_64K_S_X) | DCC(1) |
+        // DCC_INDEPENDENT_64B(1) | DCC_MAX_COMPRESSED_BLOCK(64B) |
+        // PIPE_XOR_BITS(2) | BANK_XOR_BITS(1)
+        let modifier = Modifier(0x0200000001413901);
+        let ModifierLayout::Amd(amd) = modifier.layout() else {
+            panic!("{modifier:?} is not an AMD modifier");
+        };
+        assert_eq!(amd.tile_version, AmdModifier::TILE_VER_GFX9);
+        assert_eq!(amd.tile, 25);
+        assert!(amd.dcc);
+        assert!(amd.dcc_independent_64b);
+        assert!(!amd.dcc_independent_128b);
+        assert!(!amd.dcc_retile);
+        assert_eq!(amd.dcc_max_compressed_block, 0);
+        assert_eq!(amd.pipe_xor_bits, 2);
+        assert_eq!(amd.bank_xor_bits, 1);
+        assert!(modifier.is_compressed());
+        assert_eq!(
+            format!("{modifier}"),
+            "AMD:GFX9,64K_S_X,DCC,DCC_INDEPENDENT_64B,DCC_MAX_COMPRESSED_BLOCK=64B,PIPE_XOR_BITS=2,BANK_XOR_BITS=1"
+        );
+
+        // Bits above the PIPE field aren't defined.
+        assert_eq!(
+            Modifier(0x0200001001413901).layout(),
+            ModifierLayout::Unknown
+        );
+    }
+
+    #[test]
+    fn nvidia_block_linear() {
+        // DRM_FORMAT_MOD_NVIDIA_16BX2_BLOCK_FOUR_GOB
+        let modifier = Modifier(0x0300000000000012);
+        assert_eq!(
+            modifier.layout(),
+            ModifierLayout::Nvidia(NvidiaModifier::BlockLinear(NvidiaBlockLinear {
+                log2_block_height: 2,
+                page_kind: 0,
+                generation: 0,
+                sector_layout: 0,
+                compression: 0,
+            }))
+        );
+        assert!(!modifier.is_compressed());
+        assert_eq!(
+            format!("{modifier}"),
+            "NVIDIA:BLOCK_LINEAR_2D(h=2,k=0x0,g=0,s=0,c=0)"
+        );
+
+        // DRM_FORMAT_MOD_NVIDIA_BLOCK_LINEAR_2D(1, 1, 2, 0xfe, 4)
+        let modifier = Modifier(0x0300000000efe014);
+        assert!(modifier.is_compressed());
+        assert_eq!(
+            format!("{modifier}"),
+            "NVIDIA:BLOCK_LINEAR_2D(h=4,k=0xfe,g=2,s=1,c=1)"
+        );
+
+        assert_eq!(
+            Modifier::NVIDIA_TEGRA_TILED.layout(),
+            ModifierLayout::Nvidia(NvidiaModifier::TegraTiled)
+        );
+    }
+
+    #[test]
+    fn arm_afbc() {
+        // DRM_FORMAT_MOD_ARM_AFBC(AFBC_FORMAT_MOD_BLOCK_SIZE_16x16 |
+        // AFBC_FORMAT_MOD_SPARSE | AFBC_FORMAT_MOD_YTR)
+        let modifier = Modifier(0x0800000000000051);
+        let ModifierLayout::Arm(ArmModifier::Afbc(afbc)) = modifier.layout() else {
+            panic!("{modifier:?} is not an AFBC modifier");
+        };
+        assert_eq!(afbc.block_size, (16, 16));
+        assert!(!afbc.mixed_block_size);
+        assert!(afbc.ytr);
+        assert!(afbc.sparse);
+        assert!(!afbc.split);
+        assert!(!afbc.tiled);
+        assert!(modifier.is_compressed());
+        assert_eq!(
+            format!("{modifier}"),
+            "ARM:AFBC(BLOCK_SIZE=16x16,YTR,SPARSE)"
+        );
+
+        // A block size of zero isn't defined.
+        assert_eq!(
+            Modifier(0x0800000000000050).layout(),
+            ModifierLayout::Unknown
+        );
+    }
+
+    #[test]
+    fn invalid_and_unknown() {
+        // DRM_FORMAT_MOD_INVALID
+        let modifier = Modifier(0x00ffffffffffffff);
+        assert_eq!(modifier, Modifier::INVALID);
+        assert!(modifier.is_invalid());
+        assert!(!modifier.is_linear());
+        assert_eq!(modifier.layout(), ModifierLayout::Invalid);
+        assert!(!modifier.is_compressed());
+        assert_eq!(format!("{modifier}"), "INVALID");
+
+        assert_eq!(format!("{}", Modifier::LINEAR), "LINEAR");
+        assert_eq!(format!("{}", Modifier(0x0400000000000001)), "SAMSUNG:0x1");
+        assert_eq!(format!("{}", Modifier(0x2000000000000001)), "0x20:0x1");
+    }
+}
@@ -0,0 +1,242 @@
+use alloc::vec::Vec;
+
+use super::{Fourcc, Modifier};
+use crate::result::Error;
+
+/// The format/modifier combinations that a plane supports, as described
+/// by its `IN_FORMATS` property.
+///
+/// Use [`crate::Card::plane_formats`] to obtain this for a plane, or
+/// [`PlaneFormats::parse`] if you already have the content of the blob.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaneFormats {
+    formats: Vec<Fourcc>,
+    modifiers: Vec<FormatModifierEntry>,
+}
+
+/// The in-memory form of `struct drm_format_modifier`, which says which
+/// of (up to) 64 consecutive formats support a particular modifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FormatModifierEntry {
+    formats: u64,
+    offset: u32,
+    modifier: Modifier,
+}
+
+impl FormatModifierEntry {
+    fn has_format_index(&self, idx: usize) -> bool {
+        let Some(bit) = idx.checked_sub(self.offset as usize) else {
+            return false;
+        };
+        bit < 64 && (self.formats & (1 << bit)) != 0
+    }
+}
+
+const BLOB_HEADER_SIZE: usize = 24;
+const BLOB_MODIFIER_SIZE: usize = 24;
+
+impl PlaneFormats {
+    /// Parse the content of an `IN_FORMATS` blob, which is a
+    /// `struct drm_format_modifier_blob` followed by its format and
+    /// modifier arrays.
+    ///
+    /// Returns [`Error::Invalid`] if the blob is truncated or its offsets
+    /// refer to locations outside of the blob, and
+    /// [`Error::NotSupported`] if it uses a layout version other than 1.
+    pub fn parse(blob: &[u8]) -> Result<Self, Error> {
+        let u32_at = |bytes: &[u8], offset: usize| {
+            u32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap())
+        };
+        let u64_at = |bytes: &[u8], offset: usize| {
+            u64::from_ne_bytes(bytes[offset..offset + 8].try_into().unwrap())
+        };
+        // Returns the `count` elements of `size` bytes at `offset`, checking
+        // that they fit in the blob before anything is allocated for them.
+        let array = |offset: u32, count: u32, size: usize| -> Result<&[u8], Error> {
+            let start = offset as usize;
+            let end = (count as usize)
+                .checked_mul(size)
+                .and_then(|len| len.checked_add(start))
+                .ok_or(Error::Invalid)?;
+            blob.get(start..end).ok_or(Error::Invalid)
+        };
+        if blob.len() < BLOB_HEADER_SIZE {
+            return Err(Error::Invalid);
+        }
+
+        let version = u32_at(blob, 0);
+        if version != 1 {
+            return Err(Error::NotSupported);
+        }
+        let count_formats = u32_at(blob, 8);
+        let formats_offset = u32_at(blob, 12);
+        let count_modifiers = u32_at(blob, 16);
+        let modifiers_offset = u32_at(blob, 20);
+        let format_data = array(formats_offset, count_formats, 4)?;
+        let modifier_data = array(modifiers_offset, count_modifiers, BLOB_MODIFIER_SIZE)?;
+
+        let mut formats = Vec::new();
+        formats.try_reserve_exact(count_formats as usize)?;
+        formats.extend(
+            format_data
+                .chunks_exact(4)
+                .map(|bytes| Fourcc(u32_at(bytes, 0))),
+        );
+
+        let mut modifiers = Vec::new();
+        modifiers.try_reserve_exact(count_modifiers as usize)?;
+        modifiers.extend(modifier_data.chunks_exact(BLOB_MODIFIER_SIZE).map(|bytes| {
+            FormatModifierEntry {
+                formats: u64_at(bytes, 0),
+                offset: u32_at(bytes, 8),
+                modifier: Modifier(u64_at(bytes, 16)),
+            }
+        }));
+
+        Ok(Self { formats, modifiers })
+    }
+
+    /// Returns all of the formats that the plane supports, in the order
+    /// the driver listed them.
+    pub fn formats(&self) -> &[Fourcc] {
+        &self.formats
+    }
+
+    /// Returns all of the distinct modifiers that the plane supports for
+    /// at least one format, in the order the driver listed them.
+    pub fn modifiers(&self) -> impl Iterator<Item = Modifier> + '_ {
+        // A plane with more than 64 formats has several entries for each
+        // modifier, each covering a different range of formats.
+        self.modifiers
+            .iter()
+            .enumerate()
+            .filter(|(idx, entry)| {
+                !self.modifiers[..*idx]
+                    .iter()
+                    .any(|prev| prev.modifier == entry.modifier)
+            })
+            .map(|(_, entry)| entry.modifier)
+    }
+
+    /// Returns the modifiers that the plane supports for the given format.
+    pub fn modifiers_for(&self, format: Fourcc) -> impl Iterator<Item = Modifier> + '_ {
+        let idx = self.formats.iter().position(|f| *f == format);
+        self.modifiers
+            .iter()
+            .filter(move |entry| idx.is_some_and(|idx| entry.has_format_index(idx)))
+            .map(|entry| entry.modifier)
+    }
+
+    /// Returns the formats that the plane supports with the given modifier.
+    pub fn formats_for(&self, modifier: Modifier) -> impl Iterator<Item = Fourcc> + '_ {
+        self.formats
+            .iter()
+            .enumerate()
+            .filter(move |(idx, _)| {
+                self.modifiers
+                    .iter()
+                    .any(|e| e.modifier == modifier && e.has_format_index(*idx))
+            })
+            .map(|(_, format)| *format)
+    }
+
+    /// Returns true if the plane supports the given format with the given
+    /// modifier.
+    pub fn supports(&self, format: Fourcc, modifier: Modifier) -> bool {
+        self.modifiers_for(format).any(|m| m == modifier)
+    }
+
+    /// Returns every supported format/modifier combination.
+    pub fn pairs(&self) -> impl Iterator<Item = (Fourcc, Modifier)> + '_ {
+        self.modifiers.iter().flat_map(move |entry| {
+            self.formats
+                .iter()
+                .enumerate()
+                .filter(move |(idx, _)| entry.has_format_index(*idx))
+                .map(move |(_, format)| (*format, entry.modifier))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blob(formats: &[u32], modifiers: &[(u64, u32, u64)]) -> Vec<u8> {
+        let formats_offset = BLOB_HEADER_SIZE;
+        let modifiers_offset = formats_offset + formats.len() * 4;
+        let mut raw = Vec::new();
+        for v in [
+            1,
+            0,
+            formats.len() as u32,
+            formats_offset as u32,
+            modifiers.len() as u32,
+            modifiers_offset as u32,
+        ] {
+            raw.extend_from_slice(&v.to_ne_bytes());
+        }
+        for format in formats {
+            raw.extend_from_slice(&format.to_ne_bytes());
+        }
+        for (formats, offset, modifier) in modifiers {
+            raw.extend_from_slice(&formats.to_ne_bytes());
+            raw.extend_from_slice(&offset.to_ne_bytes());
+            raw.extend_from_slice(&0u32.to_ne_bytes());
+            raw.extend_from_slice(&modifier.to_ne_bytes());
+        }
+        raw
+    }
+
+    #[test]
+    fn parse() {
+        let raw = blob(&[0x34325258, 0x34325241], &[(0b01, 0, 0), (0b11, 0, 7)]);
+        let parsed = PlaneFormats::parse(&raw).unwrap();
+        assert_eq!(parsed.formats(), [Fourcc(0x34325258), Fourcc(0x34325241)]);
+        assert!(parsed.supports(Fourcc(0x34325258), Modifier(0)));
+        assert!(parsed.supports(Fourcc(0x34325241), Modifier(7)));
+        assert!(!parsed.supports(Fourcc(0x34325241), Modifier(0)));
+    }
+
+    #[test]
+    fn more_than_64_formats() {
+        // Drivers split the formats of a plane with more than 64 of them
+        // across several entries for the same modifier.
+        let formats: Vec<u32> = (0..70).map(|i| 0x34325200 + i).collect();
+        let raw = blob(
+            &formats,
+            &[(u64::MAX, 0, 0), (0b100001, 64, 0), (0b1, 0, 7)],
+        );
+        let parsed = PlaneFormats::parse(&raw).unwrap();
+        let with_linear: Vec<u32> = parsed.formats_for(Modifier(0)).map(|f| f.0).collect();
+        assert_eq!(with_linear.len(), 66);
+        assert_eq!(with_linear[64..], [0x34325240, 0x34325245]);
+        assert!(parsed.supports(Fourcc(0x34325245), Modifier(0)));
+        assert!(!parsed.supports(Fourcc(0x34325241), Modifier(7)));
+        assert_eq!(
+            parsed.modifiers().collect::<Vec<_>>(),
+            [Modifier(0), Modifier(7)]
+        );
+    }
+
+    #[test]
+    fn parse_truncated() {
+        let raw = blob(&[0x34325258], &[(0b1, 0, 0)]);
+        for len in 0..raw.len() {
+            assert_eq!(PlaneFormats::parse(&raw[..len]), Err(Error::Invalid));
+        }
+    }
+
+    #[test]
+    fn parse_oversized_counts() {
+        let mut raw = blob(&[0x34325258], &[(0b1, 0, 0)]);
+        // A count that would need an enormous allocation if it were
+        // trusted, and offsets that overflow when added to the length.
+        raw[8..12].copy_from_slice(&u32::MAX.to_ne_bytes());
+        assert_eq!(PlaneFormats::parse(&raw), Err(Error::Invalid));
+        raw[8..12].copy_from_slice(&1u32.to_ne_bytes());
+        raw[16..20].copy_from_slice(&u32::MAX.to_ne_bytes());
+        raw[20..24].copy_from_slice(&u32::MAX.to_ne_bytes());
+        assert_eq!(PlaneFormats::parse(&raw), Err(Error::Invalid));
+    }
+}
@@ -324,6 +324,31 @@ pub const DRM_CLIENT_CAP_WRITEBACK_CONNECTORS: DrmClientCap = DrmClientCap(5);
 /// drivers starting from kernel version 6.6.
 pub const DRM_CLIENT_CAP_CURSOR_PLANE_HOTSPOT: DrmClientCap = DrmClientCap(6);
 
+#[repr(C)]
+#[derive(Debug)]
+pub struct DrmPrimeHandle {
+    pub handle: u32,
+    /// Flags, only applicable for [`DRM_IOCTL_PRIME_HANDLE_TO_FD`].
+    pub flags: u32,
+    /// Returned dma-buf file descriptor.
+    pub fd: i32,
+}
+
+impl_zeroed!(DrmPrimeHandle);
+
+/// Export the buffer object with the given handle as a dma-buf file descriptor.
+pub const DRM_IOCTL_PRIME_HANDLE_TO_FD: IoctlReqWriteRead<DrmCardDevice, DrmPrimeHandle, int> =
+    unsafe { ioctl_writeread(_IOWR::<DrmPrimeHandle>(0x2d)) };
+
+/// Import a dma-buf file descriptor as a buffer object handle.
+pub const DRM_IOCTL_PRIME_FD_TO_HANDLE: IoctlReqWriteRead<DrmCardDevice, DrmPrimeHandle, int> =
+    unsafe { ioctl_writeread(_IOWR::<DrmPrimeHandle>(0x2e)) };
+
+/// For [`DrmPrimeHandle::flags`], sets the close-on-exec flag on the new file.
+pub const DRM_CLOEXEC: u32 = linux_unsafe::O_CLOEXEC as u32;
+/// For [`DrmPrimeHandle::flags`], requests a file that allows writing.
+pub const DRM_RDWR: u32 = linux_unsafe::O_RDWR as u32;
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct DrmModeCardRes {
@@ -436,10 +461,45 @@ pub struct DrmModeInfo {
     pub name: [core::ffi::c_char; 32],
 }
 
+pub const DRM_MODE_TYPE_BUILTIN: u32 = 1 << 0;
+pub const DRM_MODE_TYPE_CLOCK_C: u32 = (1 << 1) | DRM_MODE_TYPE_BUILTIN;
+pub const DRM_MODE_TYPE_CRTC_C: u32 = (1 << 2) | DRM_MODE_TYPE_BUILTIN;
 pub const DRM_MODE_TYPE_PREFERRED: u32 = 1 << 3;
+pub const DRM_MODE_TYPE_DEFAULT: u32 = 1 << 4;
 pub const DRM_MODE_TYPE_USERDEF: u32 = 1 << 5;
 pub const DRM_MODE_TYPE_DRIVER: u32 = 1 << 6;
 
+pub const DRM_MODE_FLAG_PHSYNC: u32 = 1 << 0;
+pub const DRM_MODE_FLAG_NHSYNC: u32 = 1 << 1;
+pub const DRM_MODE_FLAG_PVSYNC: u32 = 1 << 2;
+pub const DRM_MODE_FLAG_NVSYNC: u32 = 1 << 3;
+pub const DRM_MODE_FLAG_INTERLACE: u32 = 1 << 4;
+pub const DRM_MODE_FLAG_DBLSCAN: u32 = 1 << 5;
+pub const DRM_MODE_FLAG_CSYNC: u32 = 1 << 6;
+pub const DRM_MODE_FLAG_PCSYNC: u32 = 1 << 7;
+pub const DRM_MODE_FLAG_NCSYNC: u32 = 1 << 8;
+pub const DRM_MODE_FLAG_HSKEW: u32 = 1 << 9;
+pub const DRM_MODE_FLAG_BCAST: u32 = 1 << 10;
+pub const DRM_MODE_FLAG_PIXMUX: u32 = 1 << 11;
+pub const DRM_MODE_FLAG_DBLCLK: u32 = 1 << 12;
+pub const DRM_MODE_FLAG_CLKDIV2: u32 = 1 << 13;
+pub const DRM_MODE_FLAG_3D_MASK: u32 = 0x1f << 14;
+pub const DRM_MODE_FLAG_3D_NONE: u32 = 0 << 14;
+pub const DRM_MODE_FLAG_3D_FRAME_PACKING: u32 = 1 << 14;
+pub const DRM_MODE_FLAG_3D_FIELD_ALTERNATIVE: u32 = 2 << 14;
+pub const DRM_MODE_FLAG_3D_LINE_ALTERNATIVE: u32 = 3 << 14;
+pub const DRM_MODE_FLAG_3D_SIDE_BY_SIDE_FULL: u32 = 4 << 14;
+pub const DRM_MODE_FLAG_3D_L_DEPTH: u32 = 5 << 14;
+pub const DRM_MODE_FLAG_3D_L_DEPTH_GFX_GFX_DEPTH: u32 = 6 << 14;
+pub const DRM_MODE_FLAG_3D_TOP_AND_BOTTOM: u32 = 7 << 14;
+pub const DRM_MODE_FLAG_3D_SIDE_BY_SIDE_HALF: u32 = 8 << 14;
+pub const DRM_MODE_FLAG_PIC_AR_MASK: u32 = 0x0f << 19;
+pub const DRM_MODE_FLAG_PIC_AR_NONE: u32 = 0 << 19;
+pub const DRM_MODE_FLAG_PIC_AR_4_3: u32 = 1 << 19;
+pub const DRM_MODE_FLAG_PIC_AR_16_9: u32 = 2 << 19;
+pub const DRM_MODE_FLAG_PIC_AR_64_27: u32 = 3 << 19;
+pub const DRM_MODE_FLAG_PIC_AR_256_135: u32 = 4 << 19;
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct DrmModeGetConnector {
@@ -635,6 +695,43 @@ pub const DRM_IOCTL_MODE_ADDFB: IoctlReqWriteRead<DrmCardDevice, DrmModeFbCmd, i
 pub const DRM_IOCTL_MODE_RMFB: IoctlReqWriteRead<DrmCardDevice, linux_unsafe::uint, int> =
     unsafe { ioctl_writeread(_IOWR::<linux_unsafe::uint>(0xaf)) };
 
+#[repr(C)]
+#[derive(Debug)]
+pub struct DrmModeFbCmd2 {
+    pub fb_id: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Fourcc code, as in [`crate::format::Fourcc`].
+    pub pixel_format: u32,
+    /// See [`DRM_MODE_FB_INTERLACED`] and [`DRM_MODE_FB_MODIFIERS`].
+    pub flags: u32,
+    pub handles: [u32; 4],
+    /// Pitch for each plane.
+    pub pitches: [u32; 4],
+    /// Offset of each plane.
+    pub offsets: [u32; 4],
+    /// Per-plane format modifier, used only if [`DRM_MODE_FB_MODIFIERS`]
+    /// is set in `flags`.
+    pub modifier: [u64; 4],
+}
+
+impl_zeroed!(DrmModeFbCmd2);
+
+/// Create a framebuffer from one or more buffer objects, using a fourcc
+/// pixel format code to describe the data layout.
+///
+/// Multi-planar formats use a separate entry in the `handles`, `pitches`,
+/// `offsets`, and `modifier` arrays for each plane. Any unused entries
+/// must be zero.
+pub const DRM_IOCTL_MODE_ADDFB2: IoctlReqWriteRead<DrmCardDevice, DrmModeFbCmd2, int> =
+    unsafe { ioctl_writeread(_IOWR::<DrmModeFbCmd2>(0xb8)) };
+
+/// For [`DrmModeFbCmd2::flags`], indicates that the framebuffer is interlaced.
+pub const DRM_MODE_FB_INTERLACED: u32 = 1 << 0;
+/// For [`DrmModeFbCmd2::flags`], indicates that [`DrmModeFbCmd2::modifier`]
+/// is populated.
+pub const DRM_MODE_FB_MODIFIERS: u32 = 1 << 1;
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct DrmModeFbDirtyCmd {
@@ -983,6 +1080,23 @@ impl DrmModeGetPlane {
 pub const DRM_IOCTL_MODE_GETPLANE: IoctlReqWriteRead<DrmCardDevice, DrmModeGetPlane, int> =
     unsafe { ioctl_writeread(_IOWR::<DrmModeGetPlane>(0xb6)) };
 
+/// Value of a plane's `type` property for overlay planes.
+pub const DRM_PLANE_TYPE_OVERLAY: u64 = 0;
+/// Value of a plane's `type` property for primary planes.
+pub const DRM_PLANE_TYPE_PRIMARY: u64 = 1;
+/// Value of a plane's `type` property for cursor planes.
+pub const DRM_PLANE_TYPE_CURSOR: u64 = 2;
+
+pub const DRM_MODE_ROTATE_0: u32 = 1 << 0;
+pub const DRM_MODE_ROTATE_90: u32 = 1 << 1;
+pub const DRM_MODE_ROTATE_180: u32 = 1 << 2;
+pub const DRM_MODE_ROTATE_270: u32 = 1 << 3;
+pub const DRM_MODE_ROTATE_MASK: u32 =
+    DRM_MODE_ROTATE_0 | DRM_MODE_ROTATE_90 | DRM_MODE_ROTATE_180 | DRM_MODE_ROTATE_270;
+pub const DRM_MODE_REFLECT_X: u32 = 1 << 4;
+pub const DRM_MODE_REFLECT_Y: u32 = 1 << 5;
+pub const DRM_MODE_REFLECT_MASK: u32 = DRM_MODE_REFLECT_X | DRM_MODE_REFLECT_Y;
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct DrmModeSetPlane {
@@ -1135,6 +1249,50 @@ pub const fn DRM_MODE_PROP_TYPE(n: u32) -> u32 {
     n << 6
 }
 
+#[repr(C)]
+#[derive(Debug)]
+pub struct DrmModeGetBlob {
+    pub blob_id: u32,
+    length: u32,
+    data: u64,
+}
+
+impl_zeroed!(DrmModeGetBlob);
+
+impl DrmModeGetBlob {
+    /// Set the `data` and `length` fields.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to the start of a writable array of bytes of length
+    /// `len`, and that pointer must remain valid throughout any subsequent
+    /// ioctl calls using this object.
+    #[inline(always)]
+    pub unsafe fn set_data_ptr(&mut self, ptr: *mut u8, len: u32) {
+        self.data = ptr as u64;
+        self.length = len;
+    }
+
+    #[inline(always)]
+    pub fn clear_data_ptr(&mut self) {
+        self.data = 0;
+        self.length = 0;
+    }
+
+    #[inline(always)]
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+}
+
+/// Retrieve the content of a property blob.
+///
+/// Userspace is expected to perform this request at least twice: the first
+/// time with a zero length to retrieve the size of the blob, and then again
+/// with a buffer of that size to retrieve the content.
+pub const DRM_IOCTL_MODE_GETPROPBLOB: IoctlReqWriteRead<DrmCardDevice, DrmModeGetBlob, int> =
+    unsafe { ioctl_writeread(_IOWR::<DrmModeGetBlob>(0xac)) };
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct DrmModeCreateBlob {
@@ -21,15 +21,24 @@
 
 extern crate alloc;
 
+/// Parsing of Extended Display Identification Data, which describes a
+/// display's identity and capabilities.
+pub mod edid;
 /// Types and other symbols used for event handling.
 pub mod event;
+/// Client usage statistics that DRM drivers publish in `/proc/<pid>/fdinfo`.
+pub mod fdinfo;
+/// Pixel format codes and related metadata.
+pub mod format;
 /// Low-level `ioctl`-based access to DRM devices.
 pub mod ioctl;
 /// Types and other symbols used for modesetting.
 pub mod modeset;
 pub mod result;
 
-mod util;
+/// Minimal floating point functions that `core` doesn't provide without
+/// the standard library.
+mod math;
 
 use core::iter::{self, zip};
 use core::ptr::null_mut;
@@ -146,6 +155,39 @@ impl Card {
         Ok(s.value)
     }
 
+    /// Read all of the device capabilities that this library knows about
+    /// at once.
+    ///
+    /// Capabilities that the kernel doesn't recognize, which is typical for
+    /// capabilities added in newer kernel versions, are reported as
+    /// unsupported rather than as an error.
+    pub fn capabilities(&self) -> Result<DeviceCapabilities, Error> {
+        let get = |cap: DeviceCap| match self.get_device_cap(cap) {
+            Ok(v) => Ok(v),
+            Err(Error::Invalid) => Ok(0),
+            Err(e) => Err(e),
+        };
+        let prime = get(DeviceCap::Prime)?;
+        Ok(DeviceCapabilities {
+            dumb_buffer: get(DeviceCap::DumbBuffer)? != 0,
+            vblank_high_crtc: get(DeviceCap::VBlankHighCrtc)? != 0,
+            dumb_preferred_depth: get(DeviceCap::DumbPreferredDepth)?,
+            dumb_prefer_shadow: get(DeviceCap::DumbPreferShadow)? != 0,
+            prime_import: (prime & ioctl::DRM_PRIME_CAP_IMPORT.0) != 0,
+            prime_export: (prime & ioctl::DRM_PRIME_CAP_EXPORT.0) != 0,
+            timestamp_monotonic: get(DeviceCap::TimestampMonotonic)? != 0,
+            async_page_flip: get(DeviceCap::AsyncPageFlip)? != 0,
+            cursor_width: get(DeviceCap::CursorWidth)?,
+            cursor_height: get(DeviceCap::CursorHeight)?,
+            addfb2_modifiers: get(DeviceCap::Addfb2Modifiers)? != 0,
+            page_flip_target: get(DeviceCap::PageFlipTarget)? != 0,
+            crtc_in_vblank_event: get(DeviceCap::CrtcInVblankEvent)? != 0,
+            syncobj: get(DeviceCap::Syncobj)? != 0,
+            syncobj_timeline: get(DeviceCap::SyncobjTimeline)? != 0,
+            atomic_async_page_flip: get(DeviceCap::AtomicAsyncPageFlip)? != 0,
+        })
+    }
+
     /// Read a device capability value using a raw capability number.
     #[inline(always)]
     pub fn set_client_cap(&mut self, capability: ClientCap, value: u64) -> Result<(), Error> {
@@ -165,6 +207,55 @@ impl Card {
         Ok(())
     }
 
+    /// Attempt to enable each of the given client capabilities, returning
+    /// a description of which ones the kernel accepted.
+    ///
+    /// Some client capabilities are only meaningful when another has already
+    /// been enabled, such as [`ClientCap::WritebackConnectors`] requiring
+    /// [`ClientCap::Atomic`]. This function enables the capabilities in an
+    /// order that respects those dependencies, automatically requesting any
+    /// dependencies that were not explicitly requested, and skips any
+    /// capability whose dependency was rejected.
+    ///
+    /// Rejection of an individual capability is not treated as an error,
+    /// because some drivers (particularly those for virtualized hardware)
+    /// reject certain capabilities as a matter of course. Check the
+    /// result to learn which capabilities are active.
+    pub fn negotiate_client_caps(
+        &mut self,
+        caps: &[ClientCap],
+    ) -> Result<ClientCapNegotiation, Error> {
+        let mut ret = ClientCapNegotiation {
+            statuses: [ClientCapStatus::NotRequested; ClientCap::ALL.len()],
+        };
+        let mut requested = [false; ClientCap::ALL.len()];
+        for cap in caps.iter().copied() {
+            let mut next = Some(cap);
+            while let Some(cap) = next {
+                requested[cap.index()] = true;
+                next = cap.dependency();
+            }
+        }
+
+        for cap in ClientCap::ALL {
+            if !requested[cap.index()] {
+                continue;
+            }
+            if let Some(dep) = cap.dependency() {
+                if !ret.is_accepted(dep) {
+                    ret.statuses[cap.index()] = ClientCapStatus::DependencyMissing(dep);
+                    continue;
+                }
+            }
+            ret.statuses[cap.index()] = match self.set_client_cap(cap, 1) {
+                Ok(()) => ClientCapStatus::Accepted,
+                Err(e @ (Error::Invalid | Error::NotSupported)) => ClientCapStatus::Rejected(e),
+                Err(e) => return Err(e),
+            };
+        }
+        Ok(ret)
+    }
+
     /// Attempt to become the "master" of this device, which is required for
     /// modesetting.
     #[inline]
@@ -513,10 +604,10 @@ impl Card {
         self.ioctl(ioctl::DRM_IOCTL_MODE_GETENCODER, &mut tmp)?;
         Ok(EncoderState {
             encoder_id: EncoderId(tmp.encoder_id),
-            encoder_type: tmp.encoder_type,
+            encoder_type: tmp.encoder_type.into(),
             current_crtc_id: CrtcId(tmp.crtc_id),
-            possible_crtcs: tmp.possible_crtcs,
-            possible_clones: tmp.possible_clones,
+            possible_crtcs: modeset::CrtcMask(tmp.possible_crtcs),
+            possible_clones: modeset::EncoderMask(tmp.possible_clones),
         })
     }
 
@@ -529,19 +620,353 @@ impl Card {
     }
 
     /// Read current state information for the plane with the given id.
+    ///
+    /// This includes the list of pixel formats the plane supports and the
+    /// plane's type as given by its `type` property.
     pub fn plane_state(&self, plane_id: PlaneId) -> Result<modeset::PlaneState, Error> {
+        // The format list could potentially change between our calls, so
+        // we'll keep retrying until we get a consistent result.
         let mut tmp = ioctl::DrmModeGetPlane::zeroed();
         tmp.plane_id = plane_id.0;
         self.ioctl(ioctl::DRM_IOCTL_MODE_GETPLANE, &mut tmp)?;
+        let (tmp, formats) = loop {
+            let format_count = tmp.count_format_types();
+            let mut formats = vec_with_capacity::<format::Fourcc>(format_count as usize)?;
+
+            tmp = ioctl::DrmModeGetPlane::zeroed();
+            tmp.plane_id = plane_id.0;
+            unsafe { tmp.set_format_type_ptr(formats.as_mut_ptr() as *mut u32, format_count) };
+            self.ioctl(ioctl::DRM_IOCTL_MODE_GETPLANE, &mut tmp)?;
+            if tmp.count_format_types() != format_count {
+                continue;
+            }
+
+            // Safety: We ensured the slice capacity above, and ensured
+            // that the kernel has populated the number of formats we expected.
+            unsafe { formats.set_len(format_count as usize) };
+            break (tmp, formats);
+        };
+
         Ok(modeset::PlaneState {
             id: PlaneId(tmp.plane_id),
             crtc_id: CrtcId(tmp.crtc_id),
             fb_id: FramebufferId(tmp.fb_id),
-            possible_crtcs: tmp.possible_crtcs,
+            possible_crtcs: modeset::CrtcMask(tmp.possible_crtcs),
             gamma_size: tmp.gamma_size,
+            formats,
+            plane_type: self.plane_type(plane_id)?,
         })
     }
 
+    /// Read the given plane's type from its `type` property.
+    ///
+    /// Returns `Ok(None)` if the plane doesn't have that property or its
+    /// value isn't one of the known plane types.
+    pub fn plane_type(&self, plane_id: PlaneId) -> Result<Option<modeset::PlaneType>, Error> {
+        let mut ret = None;
+        self.each_object_property_meta(plane_id, |meta, value| {
+            if meta.name() == "type" {
+                ret = modeset::PlaneType::from_property_meta(&meta, value);
+            }
+        })?;
+        Ok(ret)
+    }
+
+    /// Read the current value of the given plane's `rotation` property.
+    ///
+    /// Returns [`Error::NotSupported`] if the plane doesn't have that
+    /// property, in which case its content is never rotated.
+    pub fn plane_rotation(&self, plane_id: PlaneId) -> Result<modeset::Rotation, Error> {
+        let value = self
+            .object_property_value(plane_id, "rotation")?
+            .ok_or(Error::NotSupported)?;
+        Ok(modeset::Rotation(value as u32))
+    }
+
+    /// Returns the set of rotation and reflection bits that the given plane
+    /// supports, from the metadata of its `rotation` property.
+    ///
+    /// Returns [`Error::NotSupported`] if the plane doesn't have that
+    /// property.
+    pub fn plane_supported_rotations(&self, plane_id: PlaneId) -> Result<modeset::Rotation, Error> {
+        let mut ret = Err(Error::NotSupported);
+        self.each_object_property_meta(plane_id, |meta, _| {
+            if meta.name() == "rotation" {
+                ret = modeset::Rotation::from_property_meta(&meta);
+            }
+        })?;
+        ret
+    }
+
+    /// Retrieve the format/modifier combinations that the given plane
+    /// supports, from its `IN_FORMATS` property.
+    ///
+    /// Returns [`Error::NotSupported`] if the plane doesn't have that
+    /// property, which is the case for drivers that don't support format
+    /// modifiers. Callers can fall back to [`modeset::PlaneState::formats`]
+    /// with an implicit modifier in that case.
+    pub fn plane_formats(&self, plane_id: PlaneId) -> Result<format::PlaneFormats, Error> {
+        let blob_id = self
+            .object_property_value(plane_id, "IN_FORMATS")?
+            .filter(|v| *v != 0)
+            .ok_or(Error::NotSupported)?;
+        let blob = self.property_blob(BlobId(blob_id as u32))?;
+        format::PlaneFormats::parse(&blob)
+    }
+
+    /// Retrieve and parse the EDID that the given connector's display
+    /// provided, from the connector's `EDID` property.
+    ///
+    /// Returns `Ok(None)` if the connector doesn't currently have an EDID,
+    /// which is typically because no display is connected.
+    pub fn connector_edid(&self, connector_id: ConnectorId) -> Result<Option<edid::Edid>, Error> {
+        let Some(blob_id) = self
+            .object_property_value(connector_id, "EDID")?
+            .filter(|v| *v != 0)
+        else {
+            return Ok(None);
+        };
+        let blob = self.property_blob(BlobId(blob_id as u32))?;
+        edid::Edid::parse(&blob).map(Some)
+    }
+
+    /// Read the current values of the given connector's standard
+    /// properties, such as `DPMS`, `link-status` and `max bpc`.
+    pub fn connector_properties(
+        &self,
+        connector_id: ConnectorId,
+    ) -> Result<modeset::ConnectorProperties, Error> {
+        let mut ret = modeset::ConnectorProperties::default();
+        self.each_object_property_meta(connector_id, |meta, value| {
+            ret.apply(&meta, value);
+        })?;
+        Ok(ret)
+    }
+
+    /// Find the properties needed to control HDCP content protection on
+    /// the given connector.
+    ///
+    /// Returns [`Error::NotSupported`] if the connector doesn't have a
+    /// `Content Protection` property.
+    pub fn connector_content_protection_props(
+        &self,
+        connector_id: ConnectorId,
+    ) -> Result<modeset::ContentProtectionProps, Error> {
+        let mut ret = None;
+        let mut hdcp_content_type = None;
+        self.each_object_property_meta(connector_id, |meta, _| match meta.name() {
+            "Content Protection" => {
+                ret = Some(modeset::ContentProtectionProps::new(connector_id, &meta))
+            }
+            "HDCP Content Type" => {
+                hdcp_content_type = Some(modeset::ContentProtectionProps::hdcp_content_type_values(
+                    &meta,
+                ))
+            }
+            _ => {}
+        })?;
+        let mut ret = ret.ok_or(Error::NotSupported)?;
+        if let Some(values) = hdcp_content_type {
+            ret.set_hdcp_content_type(values);
+        }
+        Ok(ret)
+    }
+
+    /// Read the given connector's current content protection state.
+    ///
+    /// Returns [`Error::NotSupported`] if the connector doesn't have a
+    /// `Content Protection` property.
+    pub fn connector_content_protection(
+        &self,
+        connector_id: ConnectorId,
+    ) -> Result<Option<modeset::ContentProtection>, Error> {
+        let mut ret = None;
+        self.each_object_property_meta(connector_id, |meta, value| {
+            if meta.name() == "Content Protection" {
+                ret = Some(modeset::ContentProtection::from_property_meta(&meta, value));
+            }
+        })?;
+        ret.ok_or(Error::NotSupported)
+    }
+
+    /// Request HDCP protection of the given content type for the signal
+    /// of the given connector.
+    ///
+    /// The kernel establishes protection asynchronously, so the state is
+    /// typically still [`modeset::ContentProtection::Desired`] when this
+    /// returns. Use a [`modeset::ContentProtectionMonitor`] to learn when
+    /// protection is established or lost. Changing the protection state can
+    /// require a full modeset.
+    ///
+    /// This uses an atomic commit, so [`ClientCap::Atomic`] must be enabled.
+    pub fn request_content_protection(
+        &mut self,
+        connector_id: ConnectorId,
+        content_type: modeset::HdcpContentType,
+    ) -> Result<(), Error> {
+        let props = self.connector_content_protection_props(connector_id)?;
+        let mut req = modeset::AtomicRequest::new();
+        props.request(&mut req, content_type)?;
+        self.atomic_commit(&req, modeset::AtomicCommitFlags::ALLOW_MODESET, 0)
+    }
+
+    /// Stop protecting the signal of the given connector.
+    ///
+    /// This uses an atomic commit, so [`ClientCap::Atomic`] must be enabled.
+    pub fn disable_content_protection(&mut self, connector_id: ConnectorId) -> Result<(), Error> {
+        let props = self.connector_content_protection_props(connector_id)?;
+        let mut req = modeset::AtomicRequest::new();
+        props.disable(&mut req)?;
+        self.atomic_commit(&req, modeset::AtomicCommitFlags::ALLOW_MODESET, 0)
+    }
+
+    /// Retrieve and parse the given connector's `PATH` property, which
+    /// describes the position of a DisplayPort MST connector in its
+    /// topology.
+    ///
+    /// Returns `Ok(None)` if the connector isn't an MST connector.
+    pub fn connector_path(
+        &self,
+        connector_id: ConnectorId,
+    ) -> Result<Option<modeset::ConnectorPath>, Error> {
+        let Some(blob_id) = self
+            .object_property_value(connector_id, "PATH")?
+            .filter(|v| *v != 0)
+        else {
+            return Ok(None);
+        };
+        let blob = self.property_blob(BlobId(blob_id as u32))?;
+        match modeset::ConnectorPath::parse(&blob) {
+            Ok(path) => Ok(Some(path)),
+            Err(Error::NotSupported) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Build the DisplayPort MST topology trees for the given connectors,
+    /// with one tree for each physical connector that has MST connectors
+    /// attached.
+    ///
+    /// MST connectors come and go as displays are plugged and unplugged, so
+    /// any connector that no longer exists is skipped.
+    pub fn mst_trees(
+        &self,
+        resources: &modeset::CardResources,
+    ) -> Result<Vec<modeset::MstTree>, Error> {
+        let mut connectors = Vec::new();
+        for connector_id in resources.connector_ids.iter().copied() {
+            match self.connector_path(connector_id) {
+                Ok(Some(path)) => connectors.push((connector_id, path)),
+                Ok(None) | Err(Error::NonExist) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(modeset::MstTree::build(&connectors))
+    }
+
+    /// Retrieve and parse the given connector's `TILE` property, which is
+    /// present when the connector drives one tile of a tiled display.
+    ///
+    /// Returns `Ok(None)` if the connector isn't part of a tiled display.
+    pub fn connector_tile(
+        &self,
+        connector_id: ConnectorId,
+    ) -> Result<Option<modeset::TileInfo>, Error> {
+        let Some(blob_id) = self
+            .object_property_value(connector_id, "TILE")?
+            .filter(|v| *v != 0)
+        else {
+            return Ok(None);
+        };
+        let blob = self.property_blob(BlobId(blob_id as u32))?;
+        modeset::TileInfo::parse(&blob).map(Some)
+    }
+
+    /// Group the given connectors that drive tiles of tiled displays into
+    /// logical monitors, each with the modes and CRTC offsets needed to
+    /// drive the whole display as one surface.
+    ///
+    /// Connectors that aren't part of a tiled display are not included in
+    /// the result, and nor are connectors that were removed after
+    /// `resources` was retrieved.
+    pub fn tiled_monitors(
+        &self,
+        resources: &modeset::CardResources,
+    ) -> Result<Vec<modeset::TiledMonitor>, Error> {
+        let mut connectors = Vec::new();
+        for connector_id in resources.connector_ids.iter().copied() {
+            let state = self
+                .connector_tile(connector_id)
+                .and_then(|tile| match tile {
+                    Some(tile) => Ok(Some((self.connector_state(connector_id)?, tile))),
+                    None => Ok(None),
+                });
+            match state {
+                Ok(Some(connector)) => connectors.push(connector),
+                Ok(None) | Err(Error::NonExist) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(modeset::TiledMonitor::group(&connectors))
+    }
+
+    /// Returns the number of entries the given CRTC expects in a `GAMMA_LUT`
+    /// property blob, from its `GAMMA_LUT_SIZE` property.
+    ///
+    /// Returns [`Error::NotSupported`] if the CRTC doesn't support a gamma
+    /// lookup table.
+    pub fn crtc_gamma_lut_size(&self, crtc_id: CrtcId) -> Result<usize, Error> {
+        self.object_property_value(crtc_id, "GAMMA_LUT_SIZE")?
+            .map(|v| v as usize)
+            .ok_or(Error::NotSupported)
+    }
+
+    /// Returns the number of entries the given CRTC expects in a
+    /// `DEGAMMA_LUT` property blob, from its `DEGAMMA_LUT_SIZE` property.
+    ///
+    /// Returns [`Error::NotSupported`] if the CRTC doesn't support a degamma
+    /// lookup table.
+    pub fn crtc_degamma_lut_size(&self, crtc_id: CrtcId) -> Result<usize, Error> {
+        self.object_property_value(crtc_id, "DEGAMMA_LUT_SIZE")?
+            .map(|v| v as usize)
+            .ok_or(Error::NotSupported)
+    }
+
+    /// Retrieve and parse the static HDR metadata currently set on the given
+    /// connector's `HDR_OUTPUT_METADATA` property.
+    ///
+    /// Returns `Ok(None)` if no metadata is set or if the connector doesn't
+    /// have the property.
+    pub fn connector_hdr_output_metadata(
+        &self,
+        connector_id: ConnectorId,
+    ) -> Result<Option<modeset::HdrOutputMetadata>, Error> {
+        let Some(blob_id) = self
+            .object_property_value(connector_id, "HDR_OUTPUT_METADATA")?
+            .filter(|v| *v != 0)
+        else {
+            return Ok(None);
+        };
+        let blob = self.property_blob(BlobId(blob_id as u32))?;
+        modeset::HdrOutputMetadata::parse(&blob).map(Some)
+    }
+
+    /// Find the current value of the object property with the given name,
+    /// if the object has such a property.
+    fn object_property_value(
+        &self,
+        obj_id: impl Into<modeset::ObjectId>,
+        name: &str,
+    ) -> Result<Option<u64>, Error> {
+        let mut ret = None;
+        self.each_object_property_meta(obj_id, |meta, value| {
+            if meta.name() == name {
+                ret = Some(value);
+            }
+        })?;
+        Ok(ret)
+    }
+
     /// Attempt to commit an atomic modesetting request.
     ///
     /// Callers which intend to perform frequent modesetting, such as modesetting on
@@ -574,6 +999,33 @@ impl Card {
         Ok(())
     }
 
+    /// Retrieve the content of the property blob with the given id.
+    pub fn property_blob(&self, blob_id: BlobId) -> Result<Vec<u8>, Error> {
+        let mut tmp = ioctl::DrmModeGetBlob::zeroed();
+        tmp.blob_id = blob_id.0;
+        self.ioctl(ioctl::DRM_IOCTL_MODE_GETPROPBLOB, &mut tmp)?;
+
+        // Blobs are immutable once created, but we'll still retry if the
+        // length changes just in case the id gets reused between our calls.
+        loop {
+            let len = tmp.length();
+            let mut ret = vec_with_capacity::<u8>(len as usize)?;
+
+            tmp = ioctl::DrmModeGetBlob::zeroed();
+            tmp.blob_id = blob_id.0;
+            unsafe { tmp.set_data_ptr(ret.as_mut_ptr(), len) };
+            self.ioctl(ioctl::DRM_IOCTL_MODE_GETPROPBLOB, &mut tmp)?;
+            if tmp.length() != len {
+                continue;
+            }
+
+            // Safety: We ensured the capacity above, and ensured that the
+            // kernel has populated the number of bytes we expected.
+            unsafe { ret.set_len(len as usize) };
+            return Ok(ret);
+        }
+    }
+
     /// Send the given content to the kernel as a property blob, ready to use
     /// for assignment to a blob-typed object property.
     ///
@@ -621,7 +1073,7 @@ impl Card {
         unsafe {
             tmp.set_set_connectors_ptr(conn_ids.as_ptr() as *const u32, conn_ids.len() as u32)
         };
-        tmp.fb_id = buf.fb_id.0;
+        tmp.fb_id = buf.framebuffer_id().0;
         tmp.mode = mode.into();
         tmp.mode_valid = 1;
 
@@ -639,7 +1091,7 @@ impl Card {
     ) -> Result<(), Error> {
         let mut tmp = ioctl::DrmModeCrtcPageFlip::zeroed();
         tmp.crtc_id = crtd_id.0;
-        tmp.fb_id = buf.fb_id.0;
+        tmp.fb_id = buf.framebuffer_id().0;
         tmp.flags = flags.into();
         self.ioctl(ioctl::DRM_IOCTL_MODE_PAGE_FLIP, &mut tmp)?;
         Ok(())
@@ -647,66 +1099,121 @@ impl Card {
 
     /// Create a new "dumb buffer" that can be used for portable (hardware-agnostic)
     /// software rendering.
+    ///
+    /// This is a convenience wrapper that allocates the buffer with
+    /// [`Self::create_dumb_buffer_object`], attaches it to a framebuffer
+    /// with [`Self::add_dumb_framebuffer`], and maps it into memory with
+    /// [`Self::map_dumb_buffer`]. Use those functions directly to perform
+    /// only some of those steps.
     pub fn create_dumb_buffer(
         &self,
         req: modeset::DumbBufferRequest,
     ) -> Result<modeset::DumbBuffer, Error> {
+        let obj = self.create_dumb_buffer_object(req)?;
+        let fb = self.add_dumb_framebuffer(&obj)?;
+        let mapping = self.map_dumb_buffer(&obj)?;
+        Ok(modeset::DumbBuffer { mapping, fb, obj })
+    }
+
+    /// Allocate a new "dumb buffer" object without mapping it into memory
+    /// or attaching it to a framebuffer.
+    pub fn create_dumb_buffer_object(
+        &self,
+        req: modeset::DumbBufferRequest,
+    ) -> Result<modeset::DumbBufferObject, Error> {
         let mut buf_req = ioctl::DrmModeCreateDumb::zeroed();
         buf_req.width = req.width;
         buf_req.height = req.height;
         buf_req.bpp = req.bpp;
         self.ioctl(ioctl::DRM_IOCTL_MODE_CREATE_DUMB, &mut buf_req)?;
-        let buffer_handle = buf_req.handle;
-        let mut cleanup_db = util::Cleanup::new(|| {
-            let mut msg = crate::ioctl::DrmModeDestroyDumb::zeroed();
-            msg.handle = buffer_handle;
-            let _ = self.ioctl(crate::ioctl::DRM_IOCTL_MODE_DESTROY_DUMB, &mut msg);
-        });
-
-        let mut fb_req = ioctl::DrmModeFbCmd::zeroed();
-        fb_req.width = buf_req.width;
-        fb_req.height = buf_req.height;
-        fb_req.bpp = buf_req.bpp;
-        fb_req.depth = req.depth;
-        fb_req.pitch = buf_req.pitch;
-        fb_req.handle = buf_req.handle;
-        self.ioctl(ioctl::DRM_IOCTL_MODE_ADDFB, &mut fb_req)?;
-        let mut fb_id = fb_req.fb_id;
-        let mut cleanup_fb = util::Cleanup::new(|| {
-            let _ = self.ioctl(crate::ioctl::DRM_IOCTL_MODE_RMFB, &mut fb_id);
-        });
+        Ok(modeset::DumbBufferObject {
+            file: Arc::downgrade(&self.f),
+            width: buf_req.width,
+            height: buf_req.height,
+            depth: req.depth,
+            bpp: buf_req.bpp,
+            pitch: buf_req.pitch,
+            size: buf_req.size,
+            format: req.format,
+            handle: BufferObjectId(buf_req.handle),
+        })
+    }
 
+    /// Map the content of a "dumb buffer" object into memory.
+    pub fn map_dumb_buffer(
+        &self,
+        obj: &modeset::DumbBufferObject,
+    ) -> Result<modeset::DumbBufferMapping, Error> {
         let mut map_req = ioctl::DrmModeMapDumb::zeroed();
-        map_req.handle = buf_req.handle;
+        map_req.handle = obj.handle.0;
         self.ioctl(ioctl::DRM_IOCTL_MODE_MAP_DUMB, &mut map_req)?;
 
+        let len = obj.size as usize;
         let buf_ptr = unsafe {
             self.f.mmap_raw(
                 map_req.offset as _,
-                buf_req.size as usize,
+                len,
                 null_mut(),
                 0b11, // PROT_READ | PROT_WRITE,
                 0x01, // MAP_SHARED,
             )?
         };
-
-        // The DumbBuffer object's Drop is responsible for freeing
-        // the mmap, framebuffer object, and dumb buffer.
-        cleanup_fb.cancel();
-        cleanup_db.cancel();
-        Ok(modeset::DumbBuffer {
-            width: buf_req.width,
-            height: buf_req.height,
-            bpp: buf_req.bpp,
-            pitch: buf_req.pitch,
+        Ok(modeset::DumbBufferMapping {
             ptr: buf_ptr as *mut u8,
-            len: buf_req.size as usize,
-            fb_id: FramebufferId(fb_req.fb_id),
-            buffer_handle: BufferObjectId(buf_req.handle),
+            len,
+        })
+    }
+
+    /// Create a framebuffer that scans out from the given "dumb buffer" object.
+    ///
+    /// If the buffer was requested with a pixel format then the framebuffer
+    /// is created using that format. Otherwise it's created using the legacy
+    /// color depth and bits-per-pixel description.
+    pub fn add_dumb_framebuffer(
+        &self,
+        obj: &modeset::DumbBufferObject,
+    ) -> Result<modeset::FramebufferHandle, Error> {
+        let fb_id = match obj.format {
+            Some(format) => {
+                let mut fb_req = ioctl::DrmModeFbCmd2::zeroed();
+                fb_req.width = obj.width;
+                fb_req.height = obj.height;
+                fb_req.pixel_format = format.to_raw();
+                fb_req.handles[0] = obj.handle.0;
+                fb_req.pitches[0] = obj.pitch;
+                self.ioctl(ioctl::DRM_IOCTL_MODE_ADDFB2, &mut fb_req)?;
+                fb_req.fb_id
+            }
+            None => {
+                let mut fb_req = ioctl::DrmModeFbCmd::zeroed();
+                fb_req.width = obj.width;
+                fb_req.height = obj.height;
+                fb_req.bpp = obj.bpp;
+                fb_req.depth = obj.depth;
+                fb_req.pitch = obj.pitch;
+                fb_req.handle = obj.handle.0;
+                self.ioctl(ioctl::DRM_IOCTL_MODE_ADDFB, &mut fb_req)?;
+                fb_req.fb_id
+            }
+        };
+        Ok(modeset::FramebufferHandle {
+            id: Some(FramebufferId(fb_id)),
             file: Arc::downgrade(&self.f),
         })
     }
 
+    /// Export the buffer object with the given handle as a dma-buf file
+    /// descriptor, so that it can be shared with other devices or processes.
+    pub fn export_buffer_object(&self, handle: BufferObjectId) -> Result<linux_io::File, Error> {
+        let mut tmp = ioctl::DrmPrimeHandle::zeroed();
+        tmp.handle = handle.0;
+        tmp.flags = ioctl::DRM_CLOEXEC | ioctl::DRM_RDWR;
+        self.ioctl(ioctl::DRM_IOCTL_PRIME_HANDLE_TO_FD, &mut tmp)?;
+        // Safety: The kernel just created this file descriptor for us, so
+        // nothing else can possibly own it yet.
+        Ok(unsafe { linux_io::File::from_raw_fd(tmp.fd) })
+    }
+
     /// Read raw events from the card's file descriptor.
     ///
     /// DRM deals with events by having clients read from the card file descriptor,
@@ -756,6 +1263,15 @@ impl Card {
         Ok(raws.map(|raw| event::DrmEvent::from_raw(raw)))
     }
 
+    /// Read the usage statistics that the driver publishes for this card's
+    /// file descriptor, such as engine busy time and memory usage.
+    ///
+    /// Returns [`Error::NotSupported`] if the driver doesn't publish usage
+    /// statistics.
+    pub fn client_usage(&self) -> Result<fdinfo::ClientUsage, Error> {
+        fdinfo::read_fdinfo(&alloc::format!("/proc/self/fdinfo/{}", self.fd()))
+    }
+
     /// Close the filehandle underlying the card object.
     #[inline]
     pub fn close(self) -> linux_io::result::Result<()> {
@@ -831,6 +1347,7 @@ impl core::fmt::Display for ApiVersion {
 }
 
 /// Enumeration of DRM device capabilities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u64)]
 #[non_exhaustive]
 pub enum DeviceCap {
@@ -848,6 +1365,7 @@ pub enum DeviceCap {
     CrtcInVblankEvent = ioctl::DRM_CAP_CRTC_IN_VBLANK_EVENT.0,
     Syncobj = ioctl::DRM_CAP_SYNCOBJ.0,
     SyncobjTimeline = ioctl::DRM_CAP_SYNCOBJ_TIMELINE.0,
+    AtomicAsyncPageFlip = ioctl::DRM_CAP_ATOMIC_ASYNC_PAGE_FLIP.0,
 }
 
 impl From<DeviceCap> for ioctl::DrmCap {
@@ -860,6 +1378,7 @@ impl From<DeviceCap> for ioctl::DrmCap {
 }
 
 /// Enumeration of DRM client capabilities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u64)]
 #[non_exhaustive]
 pub enum ClientCap {
@@ -880,6 +1399,123 @@ impl From<ClientCap> for ioctl::DrmClientCap {
     }
 }
 
+impl ClientCap {
+    /// All of the client capabilities, in an order where each capability
+    /// appears after any capability it depends on.
+    const ALL: [ClientCap; 6] = [
+        Self::Stereo3d,
+        Self::UniversalPlanes,
+        Self::AspectRatio,
+        Self::Atomic,
+        Self::WritebackConnectors,
+        Self::CursorPlaneHotspot,
+    ];
+
+    /// Returns the capability that must be enabled before this one, if any.
+    pub const fn dependency(self) -> Option<ClientCap> {
+        match self {
+            Self::WritebackConnectors | Self::CursorPlaneHotspot => Some(Self::Atomic),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    const fn index(self) -> usize {
+        match self {
+            Self::Stereo3d => 0,
+            Self::UniversalPlanes => 1,
+            Self::AspectRatio => 2,
+            Self::Atomic => 3,
+            Self::WritebackConnectors => 4,
+            Self::CursorPlaneHotspot => 5,
+        }
+    }
+}
+
+/// A snapshot of all of the device capabilities, as returned by
+/// [`Card::capabilities`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DeviceCapabilities {
+    /// The driver supports creating "dumb buffers".
+    pub dumb_buffer: bool,
+    /// The kernel supports specifying a CRTC index in the high bits of
+    /// vblank wait requests.
+    pub vblank_high_crtc: bool,
+    /// The preferred bit depth for dumb buffers.
+    pub dumb_preferred_depth: u64,
+    /// The driver prefers userspace to render to a shadow buffer and then
+    /// copy into a dumb buffer, rather than rendering directly.
+    pub dumb_prefer_shadow: bool,
+    /// The driver supports importing PRIME buffers.
+    pub prime_import: bool,
+    /// The driver supports exporting PRIME buffers.
+    pub prime_export: bool,
+    /// Event timestamps use `CLOCK_MONOTONIC` rather than `CLOCK_REALTIME`.
+    pub timestamp_monotonic: bool,
+    /// The driver supports asynchronous legacy page-flips.
+    pub async_page_flip: bool,
+    /// A plane width that is valid to use for a cursor plane.
+    pub cursor_width: u64,
+    /// A plane height that is valid to use for a cursor plane.
+    pub cursor_height: u64,
+    /// The driver supports framebuffer modifiers.
+    pub addfb2_modifiers: bool,
+    /// The driver supports targeting a specific vblank in page-flip requests.
+    pub page_flip_target: bool,
+    /// Vblank and page-flip events include the CRTC id.
+    pub crtc_in_vblank_event: bool,
+    /// The driver supports sync objects.
+    pub syncobj: bool,
+    /// The driver supports timeline operations on sync objects.
+    pub syncobj_timeline: bool,
+    /// The driver supports asynchronous page-flips in atomic commits.
+    pub atomic_async_page_flip: bool,
+}
+
+/// The outcome of attempting to enable a single client capability using
+/// [`Card::negotiate_client_caps`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientCapStatus {
+    /// The capability was not requested, either directly or as a dependency
+    /// of another requested capability.
+    NotRequested,
+    /// The kernel accepted the capability.
+    Accepted,
+    /// The kernel rejected the capability.
+    Rejected(Error),
+    /// The capability was not attempted because the capability it depends
+    /// on was not accepted.
+    DependencyMissing(ClientCap),
+}
+
+/// The result of [`Card::negotiate_client_caps`].
+#[derive(Debug, Clone, Copy)]
+pub struct ClientCapNegotiation {
+    statuses: [ClientCapStatus; ClientCap::ALL.len()],
+}
+
+impl ClientCapNegotiation {
+    /// Returns the outcome for the given capability.
+    #[inline]
+    pub fn status(&self, cap: ClientCap) -> ClientCapStatus {
+        self.statuses[cap.index()]
+    }
+
+    /// Returns true if the given capability was accepted by the kernel.
+    #[inline]
+    pub fn is_accepted(&self, cap: ClientCap) -> bool {
+        matches!(self.status(cap), ClientCapStatus::Accepted)
+    }
+
+    /// Returns an iterator over all of the capabilities that the kernel accepted.
+    pub fn accepted(&self) -> impl Iterator<Item = ClientCap> + '_ {
+        ClientCap::ALL
+            .into_iter()
+            .filter(|cap| self.is_accepted(*cap))
+    }
+}
+
 // Returns a vector that is guaranteed to have the given capacity exactly, or
 // an error if there isn't enough memory to reserve that capacity.
 //
@@ -0,0 +1,259 @@
+const LN_2: f64 = core::f64::consts::LN_2;
+
+/// Round toward negative infinity.
+pub(crate) fn floor(x: f64) -> f64 {
+    if !x.is_finite() || x.abs() >= (1_u64 << 52) as f64 {
+        return x;
+    }
+    let t = x as i64 as f64;
+    if t > x {
+        t - 1.0
+    } else {
+        t
+    }
+}
+
+/// Compute `2^x`.
+pub(crate) fn exp2(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x >= 1024.0 {
+        return f64::INFINITY;
+    }
+    if x < -1074.0 {
+        return 0.0;
+    }
+    let n = floor(x);
+    let f = (x - n) * LN_2;
+
+    // Taylor series for e^f, where 0 <= f < ln(2).
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    for i in 1..24 {
+        term *= f / i as f64;
+        sum += term;
+    }
+    scale_by_pow2(sum, n as i32)
+}
+
+fn scale_by_pow2(v: f64, n: i32) -> f64 {
+    // Apply the scale in steps so that intermediate values stay in range
+    // for both very large and very small exponents.
+    let mut v = v;
+    let mut n = n;
+    while n > 1000 {
+        v *= f64::from_bits(((1000 + 1023) as u64) << 52);
+        n -= 1000;
+    }
+    while n < -1000 {
+        v *= f64::from_bits(((-1000 + 1023) as u64) << 52);
+        n += 1000;
+    }
+    v * f64::from_bits(((n + 1023) as u64) << 52)
+}
+
+/// Compute `e^x`.
+pub(crate) fn exp(x: f64) -> f64 {
+    exp2(x * core::f64::consts::LOG2_E)
+}
+
+/// Compute the natural logarithm of `x`.
+pub(crate) fn ln(x: f64) -> f64 {
+    if x.is_nan() || x < 0.0 {
+        return f64::NAN;
+    }
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if x.is_infinite() {
+        return x;
+    }
+
+    // Normalize subnormals so that the exponent field is meaningful.
+    let (x, bias) = if x < f64::MIN_POSITIVE {
+        (x * (1_u64 << 54) as f64, -54)
+    } else {
+        (x, 0)
+    };
+
+    // Split x into m * 2^e, where sqrt(1/2) <= m < sqrt(2).
+    let bits = x.to_bits();
+    let mut e = ((bits >> 52) & 0x7ff) as i32 - 1023 + bias;
+    let mut m = f64::from_bits((bits & ((1 << 52) - 1)) | (1023 << 52));
+    if m > core::f64::consts::SQRT_2 {
+        m /= 2.0;
+        e += 1;
+    }
+
+    // ln(m) = 2 * atanh(s), where s = (m - 1) / (m + 1) is small.
+    let s = (m - 1.0) / (m + 1.0);
+    let s2 = s * s;
+    let mut sum = 0.0;
+    let mut term = s;
+    for i in 0..20 {
+        sum += term / (2 * i + 1) as f64;
+        term *= s2;
+    }
+    e as f64 * LN_2 + 2.0 * sum
+}
+
+/// Compute the base-2 logarithm of `x`.
+pub(crate) fn log2(x: f64) -> f64 {
+    ln(x) / LN_2
+}
+
+/// Compute `x^y` for non-negative `x`.
+pub(crate) fn powf(x: f64, y: f64) -> f64 {
+    if y == 0.0 {
+        return 1.0;
+    }
+    if x == 0.0 {
+        return if y > 0.0 { 0.0 } else { f64::INFINITY };
+    }
+    exp2(y * log2(x))
+}
+
+/// Compute the square root of non-negative `x`.
+pub(crate) fn sqrt(x: f64) -> f64 {
+    if x <= 0.0 || !x.is_finite() {
+        return if x == 0.0 || x == f64::INFINITY {
+            x
+        } else {
+            f64::NAN
+        };
+    }
+    if x < f64::MIN_POSITIVE {
+        // The estimate below relies on a normal exponent, so scale
+        // subnormals up by 2^104 first.
+        return sqrt(x * exp2(104.0)) * exp2(-52.0);
+    }
+    // Newton's method, starting from an estimate made by halving the
+    // exponent.
+    let mut r = f64::from_bits((x.to_bits() >> 1) + (511 << 52));
+    for _ in 0..6 {
+        r = 0.5 * (r + x / r);
+    }
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+
+    /// Inputs covering small, large, subnormal and fractional values.
+    const SAMPLES: [f64; 16] = [
+        1e-310,
+        1e-300,
+        1e-10,
+        0.001,
+        0.1,
+        0.25,
+        0.5,
+        0.9999,
+        1.0,
+        1.5,
+        2.0,
+        core::f64::consts::E,
+        10.0,
+        123.456,
+        1e10,
+        1e300,
+    ];
+
+    fn assert_rel(actual: f64, expected: f64, tolerance: f64) {
+        let error = if expected == 0.0 {
+            actual.abs()
+        } else {
+            ((actual - expected) / expected).abs()
+        };
+        assert!(
+            error <= tolerance,
+            "{actual} differs from {expected} by a relative {error}"
+        );
+    }
+
+    #[test]
+    fn test_floor() {
+        for x in [
+            -2.5,
+            -2.0,
+            -0.5,
+            -0.0,
+            0.0,
+            0.5,
+            1.0,
+            2.9999,
+            1e15 + 0.5,
+            -1e15 - 0.5,
+        ] {
+            assert_eq!(floor(x), std::primitive::f64::floor(x), "{x}");
+        }
+        assert_eq!(floor(1e300), 1e300);
+        assert!(floor(f64::NAN).is_nan());
+        assert_eq!(floor(f64::NEG_INFINITY), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_exp2() {
+        for n in -1074..=1023 {
+            assert_eq!(exp2(n as f64), std::primitive::f64::exp2(n as f64), "{n}");
+        }
+        for x in [-1000.5, -3.3, -0.5, 0.1, 0.5, 3.3, 100.75, 1000.5] {
+            assert_rel(exp2(x), std::primitive::f64::exp2(x), 1e-14);
+        }
+        // Results are still finite just below 2^1024.
+        assert_rel(exp2(1023.5), std::primitive::f64::exp2(1023.5), 1e-14);
+        assert!(exp2(1023.999).is_finite());
+        assert_eq!(exp2(1024.0), f64::INFINITY);
+        assert_eq!(exp2(-1100.0), 0.0);
+        assert!(exp2(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn test_exp() {
+        for x in [-700.0, -10.0, -1.0, 0.0, 0.5, 1.0, 10.0, 700.0] {
+            assert_rel(exp(x), std::primitive::f64::exp(x), 1e-13);
+        }
+    }
+
+    #[test]
+    fn test_ln_and_log2() {
+        for x in SAMPLES {
+            assert_rel(ln(x), std::primitive::f64::ln(x), 1e-14);
+            assert_rel(log2(x), std::primitive::f64::log2(x), 1e-14);
+        }
+        assert_eq!(ln(1.0), 0.0);
+        assert_eq!(ln(0.0), f64::NEG_INFINITY);
+        assert_eq!(ln(f64::INFINITY), f64::INFINITY);
+        assert!(ln(-1.0).is_nan());
+    }
+
+    #[test]
+    fn test_powf() {
+        for x in SAMPLES {
+            for y in [-2.4, -1.0, -0.5, 0.0, 1.0 / 2.4, 0.5, 1.0, 2.2, 78.84375] {
+                let expected = std::primitive::f64::powf(x, y);
+                if expected.is_finite() && expected > 1e-300 && expected < 1e300 {
+                    assert_rel(powf(x, y), expected, 1e-12);
+                }
+            }
+        }
+        assert_eq!(powf(0.0, 2.0), 0.0);
+        assert_eq!(powf(0.0, -2.0), f64::INFINITY);
+        assert_eq!(powf(0.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn test_sqrt() {
+        for x in SAMPLES {
+            assert_rel(sqrt(x), std::primitive::f64::sqrt(x), 1e-15);
+        }
+        assert_eq!(sqrt(4.0), 2.0);
+        assert_eq!(sqrt(0.0), 0.0);
+        assert_eq!(sqrt(f64::INFINITY), f64::INFINITY);
+        assert!(sqrt(-1.0).is_nan());
+    }
+}
@@ -2,13 +2,39 @@ use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::ops::{BitAnd, BitOr};
 
+use crate::format::Fourcc;
+
 mod atomic;
 mod buffer;
+mod color;
+mod connector_props;
+mod content_protection;
+mod encoder;
+mod hdr;
+mod mode;
+mod modeline;
+mod mst;
 mod props;
+mod rotation;
+mod select;
+mod tile;
+mod timing;
 
 pub use atomic::*;
 pub use buffer::*;
+pub use color::*;
+pub use connector_props::*;
+pub use content_protection::*;
+pub use encoder::*;
+pub use hdr::*;
+pub use mode::*;
+pub use modeline::*;
+pub use mst::*;
 pub use props::*;
+pub use rotation::*;
+pub use select::*;
+pub use tile::*;
+pub use timing::*;
 
 macro_rules! id_newtype {
     ($name:ident) => {
@@ -163,6 +189,20 @@ impl ConnectorState {
         }
         None
     }
+
+    /// Returns the connector's modes in [`ModeInfo::display_order`], with
+    /// any modes that have the same timings as an earlier mode removed.
+    pub fn sorted_modes(&self) -> Vec<ModeInfo> {
+        let mut sorted: Vec<&ModeInfo> = self.modes.iter().collect();
+        sorted.sort_by(|a, b| a.display_order(b));
+        let mut ret: Vec<ModeInfo> = Vec::with_capacity(sorted.len());
+        for mode in sorted {
+            if !ret.iter().any(|m| m.same_timings(mode)) {
+                ret.push(mode.clone());
+            }
+        }
+        ret
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -226,10 +266,13 @@ impl From<u32> for ConnectorType {
 #[derive(Debug)]
 pub struct EncoderState {
     pub encoder_id: EncoderId,
-    pub encoder_type: u32,
+    pub encoder_type: EncoderType,
     pub current_crtc_id: CrtcId,
-    pub possible_crtcs: u32,
-    pub possible_clones: u32,
+    /// The CRTCs that the encoder can be used with.
+    pub possible_crtcs: CrtcMask,
+    /// The encoders that can be active at the same time as this one while
+    /// sharing the same CRTC.
+    pub possible_clones: EncoderMask,
 }
 
 #[derive(Debug)]
@@ -262,11 +305,81 @@ pub struct PlaneState {
     pub id: PlaneId,
     pub crtc_id: CrtcId,
     pub fb_id: FramebufferId,
-    pub possible_crtcs: u32,
+    /// The CRTCs that the plane can be used with.
+    pub possible_crtcs: CrtcMask,
     pub gamma_size: u32,
+    /// The pixel formats that the plane can scan out from.
+    pub formats: Vec<Fourcc>,
+    /// The plane's type, from its `type` property, or `None` if the plane
+    /// doesn't have that property.
+    pub plane_type: Option<PlaneType>,
 }
 
-#[derive(Debug)]
+impl PlaneState {
+    /// Returns the ids of the CRTCs that this plane can be used with.
+    ///
+    /// Each bit of [`Self::possible_crtcs`] is an index into
+    /// [`CardResources::crtc_ids`], so the given resources must have been
+    /// retrieved from the same card as the plane.
+    #[inline]
+    pub fn possible_crtc_ids(&self, resources: &CardResources) -> Vec<CrtcId> {
+        self.possible_crtcs.ids(resources)
+    }
+
+    /// Returns true if the plane can scan out from the given pixel format.
+    pub fn supports_format(&self, format: Fourcc) -> bool {
+        self.formats.contains(&format)
+    }
+}
+
+/// The type of a plane, which decides which roles it can take.
+///
+/// A plane's `type` property is immutable, so there's no conversion to a
+/// raw property value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub enum PlaneType {
+    Overlay = crate::ioctl::DRM_PLANE_TYPE_OVERLAY,
+    Primary = crate::ioctl::DRM_PLANE_TYPE_PRIMARY,
+    Cursor = crate::ioctl::DRM_PLANE_TYPE_CURSOR,
+}
+
+impl PlaneType {
+    /// Returns the plane type for the given name, as used in the enum
+    /// members of the `type` property.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Overlay" => Some(Self::Overlay),
+            "Primary" => Some(Self::Primary),
+            "Cursor" => Some(Self::Cursor),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the plane type, as used in the enum members of
+    /// the `type` property.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Overlay => "Overlay",
+            Self::Primary => "Primary",
+            Self::Cursor => "Cursor",
+        }
+    }
+
+    /// Returns the plane type for the given value of a plane's `type`
+    /// property, using the property's metadata.
+    pub fn from_property_meta(meta: &ObjectPropMeta, value: u64) -> Option<Self> {
+        // We match on the enum member names rather than the raw values
+        // because the names are the documented part of the API.
+        let members = meta.enum_members().ok()?;
+        members
+            .iter()
+            .find(|member| member.value() == value)
+            .and_then(|member| Self::from_name(member.name()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ModeInfo {
     pub name: Vec<u8>,
     pub clock: u32,
@@ -336,6 +449,33 @@ impl From<&ModeInfo> for crate::ioctl::DrmModeInfo {
     }
 }
 
+impl ModeInfo {
+    /// Fill in `vrefresh` and `name` from the other fields, in the same way
+    /// as the kernel does for modes that it generates itself.
+    pub(crate) fn fill_vrefresh_and_name(&mut self) {
+        let interlace = (self.flags & crate::ioctl::DRM_MODE_FLAG_INTERLACE) != 0;
+        let mut num = self.clock as u64 * 1000;
+        let mut den = self.htotal as u64 * self.vtotal as u64;
+        if interlace {
+            num *= 2;
+        }
+        if (self.flags & crate::ioctl::DRM_MODE_FLAG_DBLSCAN) != 0 {
+            den *= 2;
+        }
+        if self.vscan > 1 {
+            den *= self.vscan as u64;
+        }
+        self.vrefresh = (num + den / 2).checked_div(den).unwrap_or(0) as u32;
+        self.name = alloc::format!(
+            "{}x{}{}",
+            self.hdisplay,
+            self.vdisplay,
+            if interlace { "i" } else { "" }
+        )
+        .into_bytes();
+    }
+}
+
 #[derive(Debug)]
 #[repr(u32)]
 pub enum SubpixelType {
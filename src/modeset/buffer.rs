@@ -2,37 +2,72 @@ use alloc::sync::Weak;
 use core::slice;
 
 use super::{BufferObjectId, FramebufferId};
+use crate::format::Fourcc;
 
-#[derive(Debug)]
+/// Describes a "dumb buffer" to create using [`crate::Card::create_dumb_buffer`]
+/// or [`crate::Card::create_dumb_buffer_object`].
+#[derive(Debug, Clone, Copy)]
 pub struct DumbBufferRequest {
     pub width: u32,
     pub height: u32,
+    /// The color depth to use when attaching a framebuffer with the legacy
+    /// `DRM_IOCTL_MODE_ADDFB` request. Ignored if `format` is set.
     pub depth: u32,
     pub bpp: u32,
+    /// The pixel format to use when attaching a framebuffer.
+    ///
+    /// If this is set then framebuffers are created with
+    /// `DRM_IOCTL_MODE_ADDFB2`, which supports a much wider variety of
+    /// formats than the legacy request that uses `depth` and `bpp`.
+    pub format: Option<Fourcc>,
+}
+
+impl DumbBufferRequest {
+    /// Describe a dumb buffer that will hold pixels in the given format,
+    /// deriving the number of bits per pixel from the format.
+    ///
+    /// Returns [`crate::result::Error::NotSupported`] if the format is not
+    /// a single-plane format whose bits-per-pixel this library knows.
+    pub fn with_format(
+        width: u32,
+        height: u32,
+        format: Fourcc,
+    ) -> Result<Self, crate::result::Error> {
+        let bpp = format
+            .bits_per_pixel()
+            .ok_or(crate::result::Error::NotSupported)?;
+        Ok(Self {
+            width,
+            height,
+            depth: format.legacy_depth().unwrap_or(0),
+            bpp,
+            format: Some(format),
+        })
+    }
 }
 
+/// A "dumb buffer" object allocated by the kernel, which is not necessarily
+/// mapped into memory or attached to a framebuffer.
+///
+/// Use [`crate::Card::map_dumb_buffer`] to access the buffer's content and
+/// [`crate::Card::add_dumb_framebuffer`] to create a framebuffer that uses it.
+/// The buffer object is destroyed when this object is dropped, but the kernel
+/// retains the underlying memory for as long as any mapping or framebuffer
+/// still refers to it.
 #[derive(Debug)]
-pub struct DumbBuffer {
-    pub(crate) ptr: *mut u8,
-    pub(crate) len: usize,
+pub struct DumbBufferObject {
     pub(crate) file: Weak<linux_io::File<crate::ioctl::DrmCardDevice>>,
     pub(crate) width: u32,
     pub(crate) height: u32,
+    pub(crate) depth: u32,
     pub(crate) bpp: u32,
     pub(crate) pitch: u32,
-    pub(crate) fb_id: FramebufferId,
-    pub(crate) buffer_handle: BufferObjectId,
+    pub(crate) size: u64,
+    pub(crate) format: Option<Fourcc>,
+    pub(crate) handle: BufferObjectId,
 }
 
-impl DumbBuffer {
-    pub fn buffer(&self) -> &[u8] {
-        unsafe { slice::from_raw_parts(self.ptr, self.len) }
-    }
-
-    pub fn buffer_mut(&mut self) -> &mut [u8] {
-        unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
-    }
-
+impl DumbBufferObject {
     pub fn width(&self) -> u32 {
         self.width
     }
@@ -49,15 +84,59 @@ impl DumbBuffer {
         self.bpp
     }
 
-    pub fn framebuffer_id(&self) -> FramebufferId {
-        self.fb_id
+    /// Returns the size of the buffer in bytes, as decided by the kernel.
+    pub fn size(&self) -> u64 {
+        self.size
     }
 
-    pub fn pixel_idx(&self, x: u32, y: u32) -> Option<usize> {
-        if x >= self.width || y >= self.height {
-            return None;
-        }
-        Some((y as usize * self.pitch as usize) + (x as usize * (self.bpp / 8) as usize))
+    /// Returns the pixel format given when the buffer was requested, if any.
+    pub fn format(&self) -> Option<Fourcc> {
+        self.format
+    }
+
+    /// Returns the handle of the buffer object, which can be used with
+    /// [`crate::Card::export_buffer_object`] or other buffer-related requests.
+    pub fn handle(&self) -> BufferObjectId {
+        self.handle
+    }
+}
+
+impl Drop for DumbBufferObject {
+    fn drop(&mut self) {
+        // If the associated file is still open then we'll free the dumb buffer.
+        // Otherwise we'll just hope that the file descriptor associated with
+        // self.file got properly closed so that the kernel could free it
+        // automatically.
+        let Some(f) = self.file.upgrade() else {
+            return;
+        };
+        let mut msg = crate::ioctl::DrmModeDestroyDumb::zeroed();
+        msg.handle = self.handle.0;
+        let _ = crate::drm_ioctl(
+            f.as_ref(),
+            crate::ioctl::DRM_IOCTL_MODE_DESTROY_DUMB,
+            &mut msg,
+        );
+    }
+}
+
+/// A memory mapping of the content of a [`DumbBufferObject`], created using
+/// [`crate::Card::map_dumb_buffer`].
+///
+/// The memory is unmapped when this object is dropped.
+#[derive(Debug)]
+pub struct DumbBufferMapping {
+    pub(crate) ptr: *mut u8,
+    pub(crate) len: usize,
+}
+
+impl DumbBufferMapping {
+    pub fn buffer(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    pub fn buffer_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
     }
 
     pub fn clear_to_zero(&mut self) {
@@ -65,28 +144,113 @@ impl DumbBuffer {
     }
 }
 
-impl Drop for DumbBuffer {
+impl Drop for DumbBufferMapping {
     fn drop(&mut self) {
         let _ = unsafe { linux_unsafe::munmap(self.ptr as *mut _, self.len) };
+    }
+}
 
-        // If the associated file is still open then we'll also free the framebuffer and
-        // dumb buffer. Otherwise we'll just hope that the file descriptor associated with
-        // self.file got properly closed so that the kernel could free these automatically.
-        let Some(f) = self.file.upgrade() else {
-            return;
+/// A handle for a live framebuffer object.
+///
+/// The [`Drop`] implementation for this type removes the framebuffer.
+#[derive(Debug)]
+pub struct FramebufferHandle {
+    pub(crate) id: Option<FramebufferId>,
+    pub(crate) file: Weak<linux_io::File<crate::ioctl::DrmCardDevice>>,
+}
+
+impl FramebufferHandle {
+    #[inline(always)]
+    pub const fn id(&self) -> FramebufferId {
+        let Some(ret) = self.id else {
+            unreachable!();
         };
-        {
-            let mut fb_id = self.fb_id.0;
-            let _ = crate::drm_ioctl(f.as_ref(), crate::ioctl::DRM_IOCTL_MODE_RMFB, &mut fb_id);
+        ret
+    }
+
+    /// Consume the handle and remove the underlying framebuffer in the kernel.
+    #[inline(always)]
+    pub fn destroy(mut self) -> Result<(), crate::result::Error> {
+        self.destroy_internal()
+    }
+
+    #[inline]
+    fn destroy_internal(&mut self) -> Result<(), crate::result::Error> {
+        if let Some(f) = self.file.upgrade() {
+            if let Some(fb_id) = self.id.take() {
+                let mut fb_id = fb_id.0;
+                crate::drm_ioctl(&f, crate::ioctl::DRM_IOCTL_MODE_RMFB, &mut fb_id)?;
+            }
         }
-        {
-            let mut msg = crate::ioctl::DrmModeDestroyDumb::zeroed();
-            msg.handle = self.buffer_handle.0;
-            let _ = crate::drm_ioctl(
-                f.as_ref(),
-                crate::ioctl::DRM_IOCTL_MODE_DESTROY_DUMB,
-                &mut msg,
-            );
+        Ok(())
+    }
+}
+
+impl Drop for FramebufferHandle {
+    #[inline(always)]
+    fn drop(&mut self) {
+        let _ = self.destroy_internal();
+    }
+}
+
+/// A "dumb buffer" that is both mapped into memory and attached to a
+/// framebuffer, as returned by [`crate::Card::create_dumb_buffer`].
+#[derive(Debug)]
+pub struct DumbBuffer {
+    // The field order here is significant: the mapping and framebuffer must
+    // be dropped before the buffer object they refer to.
+    pub(crate) mapping: DumbBufferMapping,
+    pub(crate) fb: FramebufferHandle,
+    pub(crate) obj: DumbBufferObject,
+}
+
+impl DumbBuffer {
+    pub fn buffer(&self) -> &[u8] {
+        self.mapping.buffer()
+    }
+
+    pub fn buffer_mut(&mut self) -> &mut [u8] {
+        self.mapping.buffer_mut()
+    }
+
+    pub fn width(&self) -> u32 {
+        self.obj.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.obj.height
+    }
+
+    pub fn pitch(&self) -> u32 {
+        self.obj.pitch
+    }
+
+    pub fn bpp(&self) -> u32 {
+        self.obj.bpp
+    }
+
+    pub fn format(&self) -> Option<Fourcc> {
+        self.obj.format
+    }
+
+    pub fn framebuffer_id(&self) -> FramebufferId {
+        self.fb.id()
+    }
+
+    pub fn pixel_idx(&self, x: u32, y: u32) -> Option<usize> {
+        if x >= self.obj.width || y >= self.obj.height {
+            return None;
         }
+        Some((y as usize * self.obj.pitch as usize) + (x as usize * (self.obj.bpp / 8) as usize))
+    }
+
+    pub fn clear_to_zero(&mut self) {
+        self.mapping.clear_to_zero()
+    }
+
+    /// Separate the buffer into its buffer object, mapping, and framebuffer
+    /// so that they can be managed independently.
+    pub fn into_parts(self) -> (DumbBufferObject, DumbBufferMapping, FramebufferHandle) {
+        (self.obj, self.mapping, self.fb)
     }
 }
@@ -0,0 +1,792 @@
+use alloc::vec::Vec;
+use core::iter::zip;
+
+use crate::math;
+use crate::result::Error;
+
+/// One entry of a color lookup table, matching the kernel's
+/// `struct drm_color_lut`.
+///
+/// Each channel uses the full `u16` range, regardless of the precision
+/// of the hardware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ColorLutEntry {
+    pub red: u16,
+    pub green: u16,
+    pub blue: u16,
+}
+
+impl ColorLutEntry {
+    /// The size of `struct drm_color_lut`, including its reserved field.
+    pub const SIZE: usize = 8;
+
+    #[inline]
+    pub const fn new(red: u16, green: u16, blue: u16) -> Self {
+        Self { red, green, blue }
+    }
+
+    /// Returns an entry with the same value in all three channels.
+    #[inline]
+    pub const fn gray(v: u16) -> Self {
+        Self::new(v, v, v)
+    }
+}
+
+/// A color lookup table for the `GAMMA_LUT` or `DEGAMMA_LUT` CRTC
+/// properties.
+///
+/// Use [`Self::to_bytes`] to produce the content of a property blob, using
+/// [`crate::Card::new_property_blob`], whose id can then be set as the
+/// property's value. The number of entries must match the corresponding
+/// `GAMMA_LUT_SIZE` or `DEGAMMA_LUT_SIZE` property, which
+/// [`Self::resample`] can help with.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ColorLut {
+    pub entries: Vec<ColorLutEntry>,
+}
+
+impl ColorLut {
+    #[inline]
+    pub const fn new(entries: Vec<ColorLutEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// Returns the number of entries in the table.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Parse the content of a `GAMMA_LUT` or `DEGAMMA_LUT` property blob.
+    ///
+    /// Returns [`Error::Invalid`] if the blob size is not a multiple of
+    /// the size of `struct drm_color_lut`.
+    pub fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
+        if !raw.len().is_multiple_of(ColorLutEntry::SIZE) {
+            return Err(Error::Invalid);
+        }
+        let entries = raw
+            .chunks_exact(ColorLutEntry::SIZE)
+            .map(|raw| {
+                let word = |i: usize| u16::from_ne_bytes([raw[i * 2], raw[i * 2 + 1]]);
+                ColorLutEntry::new(word(0), word(1), word(2))
+            })
+            .collect();
+        Ok(Self { entries })
+    }
+
+    /// Encode the table as an array of `struct drm_color_lut`, ready to use
+    /// as a property blob.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut ret = Vec::with_capacity(self.entries.len() * ColorLutEntry::SIZE);
+        for entry in &self.entries {
+            ret.extend_from_slice(&entry.red.to_ne_bytes());
+            ret.extend_from_slice(&entry.green.to_ne_bytes());
+            ret.extend_from_slice(&entry.blue.to_ne_bytes());
+            ret.extend_from_slice(&0_u16.to_ne_bytes());
+        }
+        ret
+    }
+
+    /// Returns a table with the given number of entries that approximates
+    /// the same curve, using linear interpolation between the existing
+    /// entries.
+    ///
+    /// The first and last entries are preserved exactly. Resampling an
+    /// empty table produces another empty table.
+    pub fn resample(&self, size: usize) -> Self {
+        let channel = |f: fn(&ColorLutEntry) -> u16| {
+            let src: Vec<u16> = self.entries.iter().map(f).collect();
+            resample_channel(&src, size)
+        };
+        Self::from_channels(
+            &channel(|e| e.red),
+            &channel(|e| e.green),
+            &channel(|e| e.blue),
+        )
+    }
+
+    /// Returns a table built from separate tables for each channel, which
+    /// must all have the same length.
+    ///
+    /// Returns `None` if the lengths differ.
+    pub fn from_channel_tables(red: &[u16], green: &[u16], blue: &[u16]) -> Option<Self> {
+        if red.len() != green.len() || red.len() != blue.len() {
+            return None;
+        }
+        Some(Self::from_channels(red, green, blue))
+    }
+
+    fn from_channels(red: &[u16], green: &[u16], blue: &[u16]) -> Self {
+        let entries = zip(red, zip(green, blue))
+            .map(|(r, (g, b))| ColorLutEntry::new(*r, *g, *b))
+            .collect();
+        Self { entries }
+    }
+
+    /// Returns a table with the given number of entries that maps each
+    /// input value to itself.
+    pub fn identity(size: usize) -> Self {
+        Self::from_fn(size, |x| x)
+    }
+
+    /// Returns a table with the given number of entries that encodes
+    /// linear light values using the given transfer function.
+    pub fn encoding(size: usize, tf: TransferFunction) -> Self {
+        Self::from_fn(size, |x| tf.encode(x))
+    }
+
+    /// Returns a table with the given number of entries that decodes values
+    /// encoded with the given transfer function into linear light values.
+    pub fn decoding(size: usize, tf: TransferFunction) -> Self {
+        Self::from_fn(size, |x| tf.decode(x))
+    }
+
+    /// Returns a table with the given number of entries by evaluating the
+    /// given function for each entry, using the same curve for all channels.
+    ///
+    /// The function's input and output values are in the range 0.0 to 1.0,
+    /// and outputs outside of that range are clamped.
+    pub fn from_fn(size: usize, f: impl Fn(f64) -> f64) -> Self {
+        let entries = (0..size)
+            .map(|i| ColorLutEntry::gray(encode_unorm16(f(lut_input(i, size)))))
+            .collect();
+        Self { entries }
+    }
+
+    /// Like [`Self::from_fn`], but with a separate function for each channel.
+    pub fn from_channel_fns(
+        size: usize,
+        red: impl Fn(f64) -> f64,
+        green: impl Fn(f64) -> f64,
+        blue: impl Fn(f64) -> f64,
+    ) -> Self {
+        let entries = (0..size)
+            .map(|i| {
+                let x = lut_input(i, size);
+                ColorLutEntry::new(
+                    encode_unorm16(red(x)),
+                    encode_unorm16(green(x)),
+                    encode_unorm16(blue(x)),
+                )
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Returns a table with the given number of entries from the content
+    /// of an ICC profile's `vcgt` (video card gamma table) tag, including
+    /// its type signature.
+    ///
+    /// Both the table and formula forms of the tag are supported. Tables
+    /// are resampled to the requested size. Returns [`Error::Invalid`] if
+    /// the tag is malformed or [`Error::NotSupported`] if it uses an
+    /// unknown form.
+    pub fn from_icc_vcgt(raw: &[u8], size: usize) -> Result<Self, Error> {
+        if raw.len() < 12 || &raw[0..4] != b"vcgt" {
+            return Err(Error::Invalid);
+        }
+        let be16 = |i: usize| u16::from_be_bytes([raw[i], raw[i + 1]]);
+        match u32::from_be_bytes([raw[8], raw[9], raw[10], raw[11]]) {
+            0 => {
+                if raw.len() < 18 {
+                    return Err(Error::Invalid);
+                }
+                let channels = be16(12) as usize;
+                let count = be16(14) as usize;
+                let entry_size = be16(16) as usize;
+                if !(channels == 1 || channels == 3) || !(entry_size == 1 || entry_size == 2) {
+                    return Err(Error::NotSupported);
+                }
+                let data = raw
+                    .get(18..18 + channels * count * entry_size)
+                    .ok_or(Error::Invalid)?;
+                let channel = |c: usize| -> Vec<u16> {
+                    let data = &data[c * count * entry_size..(c + 1) * count * entry_size];
+                    let src: Vec<u16> = match entry_size {
+                        1 => data.iter().map(|v| *v as u16 * 257).collect(),
+                        _ => data
+                            .chunks_exact(2)
+                            .map(|v| u16::from_be_bytes([v[0], v[1]]))
+                            .collect(),
+                    };
+                    resample_channel(&src, size)
+                };
+                let red = channel(0);
+                if channels == 1 {
+                    return Ok(Self::from_channels(&red, &red, &red));
+                }
+                Ok(Self::from_channels(&red, &channel(1), &channel(2)))
+            }
+            1 => {
+                if raw.len() < 48 {
+                    return Err(Error::Invalid);
+                }
+                let s15f16 = |i: usize| {
+                    i32::from_be_bytes([raw[i], raw[i + 1], raw[i + 2], raw[i + 3]]) as f64
+                        / 65536.0
+                };
+                let formula = |c: usize| {
+                    let base = 12 + c * 12;
+                    let (gamma, min, max) = (s15f16(base), s15f16(base + 4), s15f16(base + 8));
+                    move |x: f64| min + (max - min) * math::powf(x, gamma)
+                };
+                Ok(Self::from_channel_fns(
+                    size,
+                    formula(0),
+                    formula(1),
+                    formula(2),
+                ))
+            }
+            _ => Err(Error::NotSupported),
+        }
+    }
+
+    /// Returns a copy of the table with each channel multiplied by the
+    /// corresponding factor in `[red, green, blue]`.
+    ///
+    /// This can be combined with [`blackbody_white_point`] to adjust the
+    /// color temperature of an existing curve.
+    pub fn scale(&self, factors: [f64; 3]) -> Self {
+        let scale = |v: u16, factor: f64| encode_unorm16(v as f64 / 65535.0 * factor);
+        let entries = self
+            .entries
+            .iter()
+            .map(|e| {
+                ColorLutEntry::new(
+                    scale(e.red, factors[0]),
+                    scale(e.green, factors[1]),
+                    scale(e.blue, factors[2]),
+                )
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Returns a table with the given number of entries that shifts the
+    /// white point to that of a black body at the given temperature in
+    /// kelvin, as is typically used for "night light" features.
+    ///
+    /// 6500K is neutral, leaving the table unchanged apart from rounding.
+    pub fn color_temperature(size: usize, kelvin: f64) -> Self {
+        Self::identity(size).scale(blackbody_white_point(kelvin))
+    }
+}
+
+/// A transfer function that maps between linear light values and the
+/// non-linear values used to encode them, with both in the range 0.0 to 1.0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransferFunction {
+    /// Values are encoded linearly.
+    Identity,
+    /// The piecewise sRGB transfer function from IEC 61966-2-1.
+    Srgb,
+    /// A pure power curve with the given gamma, where decoding raises the
+    /// encoded value to the power of the gamma.
+    Power(f64),
+    /// SMPTE ST 2084 "perceptual quantizer", where a linear value of 1.0
+    /// represents 10000 cd/m².
+    Pq,
+    /// The Hybrid Log-Gamma OETF from ITU-R BT.2100, where a linear value
+    /// of 1.0 represents the nominal peak scene light.
+    Hlg,
+}
+
+const PQ_M1: f64 = 2610.0 / 16384.0;
+const PQ_M2: f64 = 2523.0 / 4096.0 * 128.0;
+const PQ_C1: f64 = 3424.0 / 4096.0;
+const PQ_C2: f64 = 2413.0 / 4096.0 * 32.0;
+const PQ_C3: f64 = 2392.0 / 4096.0 * 32.0;
+
+const HLG_A: f64 = 0.17883277;
+const HLG_B: f64 = 0.28466892;
+const HLG_C: f64 = 0.55991073;
+
+impl TransferFunction {
+    /// Convert a linear light value into an encoded value.
+    pub fn encode(&self, linear: f64) -> f64 {
+        let x = linear.clamp(0.0, 1.0);
+        match *self {
+            Self::Identity => x,
+            Self::Srgb => {
+                if x <= 0.0031308 {
+                    x * 12.92
+                } else {
+                    1.055 * math::powf(x, 1.0 / 2.4) - 0.055
+                }
+            }
+            Self::Power(gamma) => math::powf(x, 1.0 / gamma),
+            Self::Pq => {
+                let y = math::powf(x, PQ_M1);
+                math::powf((PQ_C1 + PQ_C2 * y) / (1.0 + PQ_C3 * y), PQ_M2)
+            }
+            Self::Hlg => {
+                if x <= 1.0 / 12.0 {
+                    math::sqrt(3.0 * x)
+                } else {
+                    HLG_A * math::ln(12.0 * x - HLG_B) + HLG_C
+                }
+            }
+        }
+    }
+
+    /// Convert an encoded value into a linear light value.
+    pub fn decode(&self, encoded: f64) -> f64 {
+        let x = encoded.clamp(0.0, 1.0);
+        match *self {
+            Self::Identity => x,
+            Self::Srgb => {
+                if x <= 0.04045 {
+                    x / 12.92
+                } else {
+                    math::powf((x + 0.055) / 1.055, 2.4)
+                }
+            }
+            Self::Power(gamma) => math::powf(x, gamma),
+            Self::Pq => {
+                let y = math::powf(x, 1.0 / PQ_M2);
+                math::powf((y - PQ_C1).max(0.0) / (PQ_C2 - PQ_C3 * y), 1.0 / PQ_M1)
+            }
+            Self::Hlg => {
+                if x <= 0.5 {
+                    x * x / 3.0
+                } else {
+                    (math::exp((x - HLG_C) / HLG_A) + HLG_B) / 12.0
+                }
+            }
+        }
+    }
+}
+
+/// Returns the `[red, green, blue]` scale factors that shift a white point
+/// of 6500K to that of a black body at the given temperature in kelvin.
+///
+/// The factors are normalized so that 6500K gives `[1.0, 1.0, 1.0]` and so
+/// that the largest factor is always 1.0.
+///
+/// This uses Tanner Helland's curve fitted to the blackbody locus, which is
+/// accurate enough for display color temperature adjustments between 1000K
+/// and 40000K. Temperatures outside of that range are clamped.
+pub fn blackbody_white_point(kelvin: f64) -> [f64; 3] {
+    let white = blackbody_rgb(6500.0);
+    let rgb = blackbody_rgb(kelvin.clamp(1000.0, 40000.0));
+    let ratios = [0, 1, 2].map(|i| rgb[i] / white[i]);
+    let max = ratios[0].max(ratios[1]).max(ratios[2]);
+    ratios.map(|v| (v / max).clamp(0.0, 1.0))
+}
+
+/// Returns the color of a black body at the given temperature in kelvin,
+/// as `[red, green, blue]` in the range 0.0 to 255.0, without any
+/// adjustment for the white point.
+fn blackbody_rgb(kelvin: f64) -> [f64; 3] {
+    let t = kelvin / 100.0;
+    let red = if t <= 66.0 {
+        255.0
+    } else {
+        329.698727446 * math::powf(t - 60.0, -0.1332047592)
+    };
+    let green = if t <= 66.0 {
+        99.4708025861 * math::ln(t) - 161.1195681661
+    } else {
+        288.1221695283 * math::powf(t - 60.0, -0.0755148492)
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.5177312231 * math::ln(t - 10.0) - 305.0447927307
+    };
+    [red, green, blue].map(|v| v.clamp(0.0, 255.0))
+}
+
+/// Returns the normalized input value for entry `i` of a table with `size`
+/// entries.
+fn lut_input(i: usize, size: usize) -> f64 {
+    if size <= 1 {
+        0.0
+    } else {
+        i as f64 / (size - 1) as f64
+    }
+}
+
+/// Convert a value in the range 0.0 to 1.0 into the full `u16` range,
+/// clamping values outside of that range.
+fn encode_unorm16(v: f64) -> u16 {
+    // Float-to-int casts saturate and map NaN to zero.
+    (v * 65535.0 + 0.5) as u16
+}
+
+/// Resample a single channel of a lookup table using linear interpolation.
+fn resample_channel(src: &[u16], size: usize) -> Vec<u16> {
+    if src.is_empty() || size == 0 {
+        return Vec::new();
+    }
+    if size == 1 || src.len() == 1 {
+        return alloc::vec![src[0]; size];
+    }
+
+    let num_steps = (size - 1) as u64;
+    let src_steps = (src.len() - 1) as u64;
+    (0..size as u64)
+        .map(|i| {
+            // The position in the source table is idx + frac/num_steps.
+            let pos = i * src_steps;
+            let idx = (pos / num_steps) as usize;
+            let frac = (pos % num_steps) as i64;
+            let a = src[idx] as i64;
+            let Some(b) = src.get(idx + 1) else {
+                return src[idx];
+            };
+            let delta = (*b as i64 - a) * frac;
+            let step = (delta + (num_steps as i64 / 2) * delta.signum()) / num_steps as i64;
+            (a + step) as u16
+        })
+        .collect()
+}
+
+/// A color transformation matrix for the `CTM` CRTC property, matching the
+/// kernel's `struct drm_color_ctm`.
+///
+/// The matrix is in row-major order and is applied to column vectors of
+/// `[red, green, blue]`. Each entry is a fixed-point number in S31.32
+/// sign-magnitude format; use [`Self::from_f64`] and [`Self::to_f64`] to
+/// convert from and to floating point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColorCtm {
+    pub matrix: [u64; 9],
+}
+
+impl ColorCtm {
+    /// The size of `struct drm_color_ctm`.
+    pub const BLOB_SIZE: usize = 72;
+
+    /// The matrix that leaves colors unchanged.
+    pub const IDENTITY: Self = Self {
+        matrix: [1 << 32, 0, 0, 0, 1 << 32, 0, 0, 0, 1 << 32],
+    };
+
+    /// Returns a matrix with the given entries, with each row given as an
+    /// inner array.
+    pub fn from_f64(rows: [[f64; 3]; 3]) -> Self {
+        let mut matrix = [0_u64; 9];
+        for (i, v) in rows.iter().flatten().enumerate() {
+            matrix[i] = Self::encode_entry(*v);
+        }
+        Self { matrix }
+    }
+
+    /// Returns the entries of the matrix, with each row given as an inner
+    /// array.
+    pub fn to_f64(&self) -> [[f64; 3]; 3] {
+        let mut ret = [[0.0; 3]; 3];
+        for (i, v) in self.matrix.iter().enumerate() {
+            ret[i / 3][i % 3] = Self::decode_entry(*v);
+        }
+        ret
+    }
+
+    /// Encode a value as S31.32 sign-magnitude fixed point, saturating
+    /// values that are out of range.
+    pub fn encode_entry(v: f64) -> u64 {
+        const MAX_MAGNITUDE: u64 = (1 << 63) - 1;
+        let sign = if v.is_sign_negative() { 1 << 63 } else { 0 };
+        // Float-to-int casts saturate and map NaN to zero, so the only
+        // additional clamp needed is to keep the sign bit clear.
+        let magnitude = ((v.abs() * (1_u64 << 32) as f64) + 0.5) as u64;
+        let magnitude = magnitude.min(MAX_MAGNITUDE);
+        if magnitude == 0 {
+            0
+        } else {
+            sign | magnitude
+        }
+    }
+
+    /// Decode a value from S31.32 sign-magnitude fixed point.
+    pub fn decode_entry(v: u64) -> f64 {
+        let magnitude = (v & !(1 << 63)) as f64 / (1_u64 << 32) as f64;
+        if (v & (1 << 63)) != 0 {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Parse the content of a `CTM` property blob.
+    ///
+    /// Returns [`Error::Invalid`] if the blob is not the size of
+    /// `struct drm_color_ctm`.
+    pub fn from_bytes(raw: &[u8]) -> Result<Self, Error> {
+        if raw.len() != Self::BLOB_SIZE {
+            return Err(Error::Invalid);
+        }
+        let mut matrix = [0_u64; 9];
+        for (v, raw) in matrix.iter_mut().zip(raw.chunks_exact(8)) {
+            *v = u64::from_ne_bytes(raw.try_into().unwrap());
+        }
+        Ok(Self { matrix })
+    }
+
+    /// Encode the matrix as a `struct drm_color_ctm`, ready to use as a
+    /// property blob.
+    pub fn to_bytes(&self) -> [u8; Self::BLOB_SIZE] {
+        let mut ret = [0_u8; Self::BLOB_SIZE];
+        for (v, raw) in self.matrix.iter().zip(ret.chunks_exact_mut(8)) {
+            raw.copy_from_slice(&v.to_ne_bytes());
+        }
+        ret
+    }
+}
+
+impl Default for ColorCtm {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "{actual} is not within {tolerance} of {expected}"
+        );
+    }
+
+    #[test]
+    fn transfer_function_round_trip() {
+        let tfs = [
+            TransferFunction::Identity,
+            TransferFunction::Srgb,
+            TransferFunction::Power(2.2),
+            TransferFunction::Pq,
+            TransferFunction::Hlg,
+        ];
+        // BT.2100 only gives the HLG constants to eight digits, which limits
+        // the precision of its round trip.
+        for tf in tfs {
+            for i in 0..=1000 {
+                let x = i as f64 / 1000.0;
+                assert_close(tf.decode(tf.encode(x)), x, 1e-8);
+            }
+            // PQ encodes zero as a tiny positive signal, so the round trip in
+            // this direction starts just above zero.
+            for i in 1..=1000 {
+                let x = i as f64 / 1000.0;
+                assert_close(tf.encode(tf.decode(x)), x, 1e-8);
+            }
+        }
+    }
+
+    #[test]
+    fn transfer_function_known_values() {
+        let srgb = TransferFunction::Srgb;
+        assert_close(srgb.encode(0.0), 0.0, 0.0);
+        assert_close(srgb.encode(1.0), 1.0, 1e-12);
+        assert_close(srgb.encode(0.5), 0.735356983, 1e-9);
+        assert_close(srgb.decode(0.5), 0.214041140, 1e-9);
+
+        let pq = TransferFunction::Pq;
+        assert_close(pq.encode(1.0), 1.0, 1e-12);
+        // 100 cd/m² and 1000 cd/m², from ITU-R BT.2100's reference tables.
+        assert_close(pq.encode(0.01), 0.508078, 1e-6);
+        assert_close(pq.encode(0.1), 0.751827, 1e-6);
+
+        let hlg = TransferFunction::Hlg;
+        assert_close(hlg.encode(1.0 / 12.0), 0.5, 1e-12);
+        assert_close(hlg.encode(1.0), 1.0, 1e-6);
+        assert_close(hlg.decode(0.75), 0.26496256, 1e-7);
+    }
+
+    #[test]
+    fn blackbody() {
+        let white = blackbody_white_point(6500.0);
+        for v in white {
+            assert_close(v, 1.0, 1e-12);
+        }
+        let [r, g, b] = blackbody_white_point(3000.0);
+        assert_close(r, 1.0, 0.0);
+        assert!(g < 0.8 && g > b && b > 0.0, "{g} {b}");
+        let [r, g, b] = blackbody_white_point(10000.0);
+        assert_close(b, 1.0, 0.0);
+        assert!(r < g && g < 1.0, "{r} {g}");
+        assert_eq!(blackbody_white_point(100.0), blackbody_white_point(1000.0));
+    }
+
+    #[test]
+    fn color_temperature_neutral() {
+        let identity = ColorLut::identity(256);
+        let lut = ColorLut::color_temperature(256, 6500.0);
+        for (a, b) in identity.entries.iter().zip(lut.entries.iter()) {
+            assert!(a.red.abs_diff(b.red) <= 1);
+            assert!(a.green.abs_diff(b.green) <= 1);
+            assert!(a.blue.abs_diff(b.blue) <= 1);
+        }
+    }
+
+    #[test]
+    fn icc_vcgt_table() {
+        // A two-entry, 8-bit table for each channel.
+        let mut raw = b"vcgt\0\0\0\0\0\0\0\0".to_vec();
+        raw.extend_from_slice(&[0, 3, 0, 2, 0, 1]);
+        raw.extend_from_slice(&[0, 255, 0, 128, 255, 0]);
+        let lut = ColorLut::from_icc_vcgt(&raw, 2).unwrap();
+        let red: Vec<u16> = lut.entries.iter().map(|e| e.red).collect();
+        let green: Vec<u16> = lut.entries.iter().map(|e| e.green).collect();
+        let blue: Vec<u16> = lut.entries.iter().map(|e| e.blue).collect();
+        assert_eq!(red, [0, 65535]);
+        assert_eq!(green, [0, 128 * 257]);
+        assert_eq!(blue, [65535, 0]);
+
+        assert_eq!(ColorLut::from_icc_vcgt(&raw[..20], 2), Err(Error::Invalid));
+    }
+
+    #[test]
+    fn icc_vcgt_formula() {
+        let mut raw = b"vcgt\0\0\0\0\0\0\0\x01".to_vec();
+        let s15f16 = |v: f64| ((v * 65536.0) as i32).to_be_bytes();
+        for _ in 0..3 {
+            raw.extend_from_slice(&s15f16(2.0));
+            raw.extend_from_slice(&s15f16(0.0));
+            raw.extend_from_slice(&s15f16(1.0));
+        }
+        let lut = ColorLut::from_icc_vcgt(&raw, 3).unwrap();
+        let red: Vec<u16> = lut.entries.iter().map(|e| e.red).collect();
+        assert_eq!(red, [0, 16384, 65535]);
+
+        raw[11] = 2;
+        assert_eq!(ColorLut::from_icc_vcgt(&raw, 3), Err(Error::NotSupported));
+    }
+
+    #[test]
+    fn ctm_encode_entry() {
+        const SIGN: u64 = 1 << 63;
+        const ONE: u64 = 1 << 32;
+        assert_eq!(ColorCtm::encode_entry(1.0), ONE);
+        assert_eq!(ColorCtm::encode_entry(-1.0), SIGN | ONE);
+        assert_eq!(ColorCtm::encode_entry(0.0), 0);
+        // Negative zero has no magnitude, so it's encoded without the sign.
+        assert_eq!(ColorCtm::encode_entry(-0.0), 0);
+        assert_eq!(ColorCtm::encode_entry(0.5), ONE / 2);
+        assert_eq!(ColorCtm::encode_entry(-0.25), SIGN | (ONE / 4));
+        assert_eq!(ColorCtm::encode_entry(2.75), ONE * 2 + ONE * 3 / 4);
+        // Fractions are rounded to the nearest representable value.
+        assert_eq!(ColorCtm::encode_entry(0.1), 429496730);
+        assert_eq!(ColorCtm::encode_entry(1.0 / ONE as f64), 1);
+        assert_eq!(ColorCtm::encode_entry(0.4 / ONE as f64), 0);
+
+        // Out of range values saturate without touching the sign bit.
+        assert_eq!(ColorCtm::encode_entry(1e30), !SIGN);
+        assert_eq!(ColorCtm::encode_entry(-1e30), u64::MAX);
+        assert_eq!(ColorCtm::encode_entry(f64::INFINITY), !SIGN);
+        assert_eq!(ColorCtm::encode_entry(f64::NEG_INFINITY), u64::MAX);
+        assert_eq!(ColorCtm::encode_entry(f64::NAN), 0);
+        assert_eq!(ColorCtm::encode_entry(-f64::NAN), 0);
+    }
+
+    #[test]
+    fn ctm_decode_entry() {
+        const SIGN: u64 = 1 << 63;
+        const ONE: u64 = 1 << 32;
+        assert_eq!(ColorCtm::decode_entry(ONE), 1.0);
+        assert_eq!(ColorCtm::decode_entry(SIGN | ONE), -1.0);
+        assert_eq!(ColorCtm::decode_entry(ONE / 2), 0.5);
+        assert_eq!(ColorCtm::decode_entry(SIGN | (ONE / 4)), -0.25);
+        assert_eq!(ColorCtm::decode_entry(0), 0.0);
+        let neg_zero = ColorCtm::decode_entry(SIGN);
+        assert!(neg_zero == 0.0 && neg_zero.is_sign_negative());
+        for v in [0.0, 1.0, -1.0, 0.1, -0.7152, 1234.5678, -0.0722] {
+            assert_close(
+                ColorCtm::decode_entry(ColorCtm::encode_entry(v)),
+                v,
+                1.0 / (1_u64 << 33) as f64,
+            );
+        }
+    }
+
+    #[test]
+    fn ctm_blob_round_trip() {
+        let rows = [[0.5, -0.25, 0.0], [1.0, -1.0, 2.75], [-0.0, 0.125, -3.5]];
+        let ctm = ColorCtm::from_f64(rows);
+        let raw = ctm.to_bytes();
+        assert_eq!(raw.len(), ColorCtm::BLOB_SIZE);
+        assert_eq!(raw[8..16], ColorCtm::encode_entry(-0.25).to_ne_bytes());
+        let parsed = ColorCtm::from_bytes(&raw).unwrap();
+        assert_eq!(parsed, ctm);
+        assert_eq!(parsed.to_f64(), rows);
+
+        let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        assert_eq!(ColorCtm::from_f64(identity), ColorCtm::IDENTITY);
+        assert_eq!(ColorCtm::default().to_f64(), identity);
+
+        assert_eq!(ColorCtm::from_bytes(&raw[..71]), Err(Error::Invalid));
+        assert_eq!(ColorCtm::from_bytes(&[0; 80]), Err(Error::Invalid));
+    }
+
+    #[test]
+    fn lut_blob_round_trip() {
+        let lut = ColorLut::new(alloc::vec![
+            ColorLutEntry::new(0, 1, 2),
+            ColorLutEntry::new(0x1234, 0x8000, 0xffff),
+        ]);
+        let raw = lut.to_bytes();
+        assert_eq!(raw.len(), 2 * ColorLutEntry::SIZE);
+        // The reserved field is zero.
+        assert_eq!(raw[6..8], [0, 0]);
+        assert_eq!(ColorLut::from_bytes(&raw).unwrap(), lut);
+        assert_eq!(ColorLut::from_bytes(&raw[..12]), Err(Error::Invalid));
+    }
+
+    #[test]
+    fn resample_up() {
+        let lut = ColorLut::new(alloc::vec![
+            ColorLutEntry::new(0, 65535, 1000),
+            ColorLutEntry::new(65535, 0, 1000),
+        ]);
+        let resampled = lut.resample(5);
+        let red: Vec<u16> = resampled.entries.iter().map(|e| e.red).collect();
+        let green: Vec<u16> = resampled.entries.iter().map(|e| e.green).collect();
+        let blue: Vec<u16> = resampled.entries.iter().map(|e| e.blue).collect();
+        assert_eq!(red, [0, 16384, 32768, 49151, 65535]);
+        // Rounding is symmetric, so a falling channel mirrors a rising one.
+        assert_eq!(green, [65535, 49151, 32767, 16384, 0]);
+        assert_eq!(blue, [1000; 5]);
+    }
+
+    #[test]
+    fn resample_down() {
+        // Every 17th entry of a 256-entry identity table is exactly on the
+        // 16-entry identity table.
+        assert_eq!(ColorLut::identity(256).resample(16), ColorLut::identity(16));
+
+        let lut = ColorLut::encoding(1024, TransferFunction::Srgb);
+        let resampled = lut.resample(33);
+        assert_eq!(resampled.len(), 33);
+        assert_eq!(resampled.entries[0], lut.entries[0]);
+        assert_eq!(resampled.entries[32], lut.entries[1023]);
+        assert!(resampled
+            .entries
+            .windows(2)
+            .all(|pair| pair[0].red <= pair[1].red));
+    }
+
+    #[test]
+    fn resample_edge_cases() {
+        assert!(ColorLut::default().resample(256).is_empty());
+        assert!(ColorLut::identity(256).resample(0).is_empty());
+        let single = ColorLut::new(alloc::vec![ColorLutEntry::gray(1234)]);
+        assert_eq!(single.resample(3).entries, [ColorLutEntry::gray(1234); 3]);
+        assert_eq!(
+            ColorLut::identity(256).resample(1).entries,
+            [ColorLutEntry::gray(0)]
+        );
+    }
+}
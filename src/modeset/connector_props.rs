@@ -0,0 +1,289 @@
+use super::{ObjectPropMeta, Rotation};
+
+/// Declares an enum whose variants correspond to the named members of an
+/// enum-typed property, converting to and from raw values using the
+/// property's metadata.
+///
+/// The kernel documents the member names of the standard properties as
+/// part of its API but not their raw values, so the conversions always
+/// go through the names.
+macro_rules! named_prop_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$vmeta:meta])* $variant:ident => $member:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$vmeta])* $variant,)*
+        }
+
+        impl $name {
+            /// Returns the value for the given property enum member name.
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $($member => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+
+            /// Returns the property enum member name for the value.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $member,)*
+                }
+            }
+
+            /// Returns the value for the given raw property value, using the
+            /// property's metadata.
+            pub fn from_property_meta(meta: &ObjectPropMeta, value: u64) -> Option<Self> {
+                let members = meta.enum_members().ok()?;
+                members
+                    .iter()
+                    .find(|member| member.value() == value)
+                    .and_then(|member| Self::from_name(member.name()))
+            }
+
+            /// Returns the raw property value to use for this value, using the
+            /// property's metadata, or `None` if the property doesn't support
+            /// this value.
+            pub fn raw_value(self, meta: &ObjectPropMeta) -> Option<u64> {
+                let members = meta.enum_members().ok()?;
+                members
+                    .iter()
+                    .find(|member| member.name() == self.name())
+                    .map(|member| member.value())
+            }
+        }
+    };
+}
+
+named_prop_enum! {
+    /// The value of a connector's `DPMS` property, which is the legacy
+    /// power saving state of the connected display.
+    pub enum Dpms {
+        On => "On",
+        Standby => "Standby",
+        Suspend => "Suspend",
+        Off => "Off",
+    }
+}
+
+named_prop_enum! {
+    /// The value of a connector's `link-status` property.
+    ///
+    /// The kernel changes this to [`Self::Bad`] when link training fails
+    /// after a modeset, in which case userspace should perform a new modeset,
+    /// possibly with a lower mode.
+    pub enum LinkStatus {
+        Good => "Good",
+        Bad => "Bad",
+    }
+}
+
+named_prop_enum! {
+    /// The value of a connector's `content type` property, which tells
+    /// the display what kind of content is being shown so that it can
+    /// adjust its processing.
+    pub enum ContentType {
+        NoData => "No Data",
+        Graphics => "Graphics",
+        Photo => "Photo",
+        Cinema => "Cinema",
+        Game => "Game",
+    }
+}
+
+named_prop_enum! {
+    /// The value of a connector's `Colorspace` property, which tells the
+    /// display what colorimetry the signal uses.
+    pub enum Colorspace {
+        Default => "Default",
+        Smpte170mYcc => "SMPTE_170M_YCC",
+        Bt709Ycc => "BT709_YCC",
+        Xvycc601 => "XVYCC_601",
+        Xvycc709 => "XVYCC_709",
+        Sycc601 => "SYCC_601",
+        OpYcc601 => "opYCC_601",
+        OpRgb => "opRGB",
+        Bt2020Cycc => "BT2020_CYCC",
+        Bt2020Rgb => "BT2020_RGB",
+        Bt2020Ycc => "BT2020_YCC",
+        DciP3RgbD65 => "DCI-P3_RGB_D65",
+        DciP3RgbTheater => "DCI-P3_RGB_Theater",
+        RgbWideFixed => "RGB_WIDE_FIXED",
+        RgbWideFloat => "RGB_WIDE_FLOAT",
+        Bt601Ycc => "BT601_YCC",
+    }
+}
+
+named_prop_enum! {
+    /// The value of a connector's `Broadcast RGB` property, which selects
+    /// the quantization range of RGB output.
+    pub enum BroadcastRgb {
+        /// Choose the range based on the mode, as recommended by the
+        /// relevant specifications.
+        Automatic => "Automatic",
+        Full => "Full",
+        Limited => "Limited 16:235",
+    }
+}
+
+named_prop_enum! {
+    /// The value of a connector's `scaling mode` property, which decides
+    /// how a mode smaller than the panel's native mode is shown.
+    pub enum ScalingMode {
+        None => "None",
+        /// Stretch to fill the panel, ignoring the aspect ratio.
+        Full => "Full",
+        /// Show unscaled in the middle of the panel.
+        Center => "Center",
+        /// Stretch as far as possible while keeping the aspect ratio.
+        FullAspect => "Full aspect",
+    }
+}
+
+named_prop_enum! {
+    /// The value of a connector's `underscan` property, which shrinks the
+    /// image to compensate for displays that overscan.
+    pub enum Underscan {
+        Off => "off",
+        On => "on",
+        Auto => "auto",
+    }
+}
+
+named_prop_enum! {
+    /// The value of a connector's `panel orientation` property, which
+    /// describes how a built-in panel is mounted relative to the device.
+    pub enum PanelOrientation {
+        Normal => "Normal",
+        UpsideDown => "Upside Down",
+        LeftSideUp => "Left Side Up",
+        RightSideUp => "Right Side Up",
+    }
+}
+
+impl PanelOrientation {
+    /// Returns the plane rotation that makes content appear upright on a
+    /// panel mounted with this orientation.
+    pub const fn correction(self) -> Rotation {
+        match self {
+            Self::Normal => Rotation::ROTATE_0,
+            Self::UpsideDown => Rotation::ROTATE_180,
+            Self::LeftSideUp => Rotation::ROTATE_90,
+            Self::RightSideUp => Rotation::ROTATE_270,
+        }
+    }
+}
+
+named_prop_enum! {
+    /// The value of a connector's `privacy-screen sw-state` or
+    /// `privacy-screen hw-state` property.
+    ///
+    /// The locked states mean that the privacy screen is controlled by
+    /// hardware, such as a physical switch, and can't be changed through the
+    /// `sw-state` property.
+    pub enum PrivacyScreenState {
+        Disabled => "Disabled",
+        Enabled => "Enabled",
+        DisabledLocked => "Disabled, locked",
+        EnabledLocked => "Enabled, locked",
+    }
+}
+
+named_prop_enum! {
+    /// The value of a connector's `Content Protection` property, which
+    /// controls HDCP encryption of the signal.
+    ///
+    /// Userspace can only set [`Self::Undesired`] or [`Self::Desired`]. The
+    /// kernel changes [`Self::Desired`] to [`Self::Enabled`] once protection
+    /// is established, and changes it back to [`Self::Desired`] if the
+    /// protection is lost, such as after a link failure.
+    pub enum ContentProtection {
+        Undesired => "Undesired",
+        Desired => "Desired",
+        Enabled => "Enabled",
+    }
+}
+
+named_prop_enum! {
+    /// The value of a connector's `HDCP Content Type` property, which
+    /// selects which HDCP versions are acceptable for protecting the signal.
+    pub enum HdcpContentType {
+        /// Content that may be protected by any HDCP version.
+        Type0 => "HDCP Type0",
+        /// Content that must be protected by HDCP 2.2 or later.
+        Type1 => "HDCP Type1",
+    }
+}
+
+/// The current values of a connector's standard properties, as returned by
+/// [`crate::Card::connector_properties`].
+///
+/// Each field is `None` if the connector doesn't have the corresponding
+/// property, or if the property's current value is one that this library
+/// doesn't know.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConnectorProperties {
+    pub dpms: Option<Dpms>,
+    pub link_status: Option<LinkStatus>,
+    /// True if the connected display isn't intended for use as part of
+    /// a desktop, such as a head-mounted display.
+    pub non_desktop: Option<bool>,
+    pub content_type: Option<ContentType>,
+    pub colorspace: Option<Colorspace>,
+    /// The maximum number of bits per color channel that the driver may
+    /// use for the signal.
+    pub max_bpc: Option<u32>,
+    pub broadcast_rgb: Option<BroadcastRgb>,
+    pub scaling_mode: Option<ScalingMode>,
+    pub underscan: Option<Underscan>,
+    /// The width of the horizontal underscan border, in pixels.
+    pub underscan_hborder: Option<u32>,
+    /// The height of the vertical underscan border, in pixels.
+    pub underscan_vborder: Option<u32>,
+    pub panel_orientation: Option<PanelOrientation>,
+    pub privacy_screen_sw_state: Option<PrivacyScreenState>,
+    pub privacy_screen_hw_state: Option<PrivacyScreenState>,
+    pub content_protection: Option<ContentProtection>,
+    pub hdcp_content_type: Option<HdcpContentType>,
+}
+
+impl ConnectorProperties {
+    /// Record the value of the given property if it's one of the standard
+    /// connector properties, ignoring it otherwise.
+    pub fn apply(&mut self, meta: &ObjectPropMeta, value: u64) {
+        match meta.name() {
+            "DPMS" => self.dpms = Dpms::from_property_meta(meta, value),
+            "link-status" => self.link_status = LinkStatus::from_property_meta(meta, value),
+            "non-desktop" => self.non_desktop = Some(value != 0),
+            "content type" => self.content_type = ContentType::from_property_meta(meta, value),
+            "Colorspace" => self.colorspace = Colorspace::from_property_meta(meta, value),
+            "max bpc" => self.max_bpc = u32::try_from(value).ok(),
+            "Broadcast RGB" => self.broadcast_rgb = BroadcastRgb::from_property_meta(meta, value),
+            "scaling mode" => self.scaling_mode = ScalingMode::from_property_meta(meta, value),
+            "underscan" => self.underscan = Underscan::from_property_meta(meta, value),
+            "underscan hborder" => self.underscan_hborder = u32::try_from(value).ok(),
+            "underscan vborder" => self.underscan_vborder = u32::try_from(value).ok(),
+            "panel orientation" => {
+                self.panel_orientation = PanelOrientation::from_property_meta(meta, value)
+            }
+            "privacy-screen sw-state" => {
+                self.privacy_screen_sw_state = PrivacyScreenState::from_property_meta(meta, value)
+            }
+            "privacy-screen hw-state" => {
+                self.privacy_screen_hw_state = PrivacyScreenState::from_property_meta(meta, value)
+            }
+            "Content Protection" => {
+                self.content_protection = ContentProtection::from_property_meta(meta, value)
+            }
+            "HDCP Content Type" => {
+                self.hdcp_content_type = HdcpContentType::from_property_meta(meta, value)
+            }
+            _ => {}
+        }
+    }
+}
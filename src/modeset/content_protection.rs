@@ -0,0 +1,269 @@
+use super::{
+    AtomicRequest, ConnectorId, ContentProtection, HdcpContentType, ObjectPropMeta, PropertyId,
+};
+use crate::event::HotplugEvent;
+use crate::result::Error;
+
+/// The property ids and raw values needed to control HDCP content
+/// protection on a connector, as returned by
+/// [`crate::Card::connector_content_protection_props`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentProtectionProps {
+    pub connector_id: ConnectorId,
+    /// The id of the connector's `Content Protection` property.
+    pub content_protection: PropertyId,
+    /// The id of the connector's `HDCP Content Type` property, if it has
+    /// one. Connectors without this property only support type 0.
+    pub hdcp_content_type: Option<PropertyId>,
+    protection_values: [Option<u64>; 3],
+    type_values: [Option<u64>; 2],
+}
+
+impl ContentProtectionProps {
+    pub(crate) fn new(connector_id: ConnectorId, content_protection: &ObjectPropMeta) -> Self {
+        let value = |v: ContentProtection| v.raw_value(content_protection);
+        Self {
+            connector_id,
+            content_protection: content_protection.property_id(),
+            hdcp_content_type: None,
+            protection_values: [
+                value(ContentProtection::Undesired),
+                value(ContentProtection::Desired),
+                value(ContentProtection::Enabled),
+            ],
+            type_values: [None, None],
+        }
+    }
+
+    /// Returns the property id and raw values of an `HDCP Content Type`
+    /// property, for use with [`Self::set_hdcp_content_type`].
+    pub(crate) fn hdcp_content_type_values(
+        meta: &ObjectPropMeta,
+    ) -> (PropertyId, [Option<u64>; 2]) {
+        let values = [
+            HdcpContentType::Type0.raw_value(meta),
+            HdcpContentType::Type1.raw_value(meta),
+        ];
+        (meta.property_id(), values)
+    }
+
+    pub(crate) fn set_hdcp_content_type(
+        &mut self,
+        (prop_id, values): (PropertyId, [Option<u64>; 2]),
+    ) {
+        self.hdcp_content_type = Some(prop_id);
+        self.type_values = values;
+    }
+
+    /// Returns the content protection state for the given raw value of the
+    /// `Content Protection` property.
+    pub fn state_from_raw(&self, value: u64) -> Option<ContentProtection> {
+        [
+            ContentProtection::Undesired,
+            ContentProtection::Desired,
+            ContentProtection::Enabled,
+        ]
+        .into_iter()
+        .zip(self.protection_values)
+        .find(|(_, raw)| *raw == Some(value))
+        .map(|(state, _)| state)
+    }
+
+    /// Add the property changes needed to request protection of the given
+    /// content type to an atomic request.
+    ///
+    /// Returns [`Error::NotSupported`] if the connector can't protect that
+    /// content type.
+    pub fn request(
+        &self,
+        req: &mut AtomicRequest,
+        content_type: HdcpContentType,
+    ) -> Result<(), Error> {
+        let desired = self.protection_values[1].ok_or(Error::NotSupported)?;
+        match (self.hdcp_content_type, content_type) {
+            (Some(prop_id), _) => {
+                let raw = self.type_values[content_type as usize].ok_or(Error::NotSupported)?;
+                req.set_property(self.connector_id, prop_id, raw);
+            }
+            (None, HdcpContentType::Type0) => {}
+            (None, HdcpContentType::Type1) => return Err(Error::NotSupported),
+        }
+        req.set_property(self.connector_id, self.content_protection, desired);
+        Ok(())
+    }
+
+    /// Add the property change needed to stop protecting the connector's
+    /// signal to an atomic request.
+    pub fn disable(&self, req: &mut AtomicRequest) -> Result<(), Error> {
+        let undesired = self.protection_values[0].ok_or(Error::NotSupported)?;
+        req.set_property(self.connector_id, self.content_protection, undesired);
+        Ok(())
+    }
+}
+
+/// Tracks the content protection state of a connector so that callers can
+/// react when the kernel establishes or loses protection.
+///
+/// The kernel reports changes to the `Content Protection` property through
+/// hotplug uevents, so callers should pass each [`HotplugEvent`] they
+/// receive for the card to [`Self::handle_hotplug`].
+#[derive(Debug, Clone)]
+pub struct ContentProtectionMonitor {
+    props: ContentProtectionProps,
+    state: Option<ContentProtection>,
+}
+
+/// A change of a connector's content protection state, as reported by
+/// [`ContentProtectionMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentProtectionChange {
+    pub connector_id: ConnectorId,
+    pub old: Option<ContentProtection>,
+    pub new: Option<ContentProtection>,
+}
+
+impl ContentProtectionChange {
+    /// Returns true if the connector's signal was protected before the
+    /// change but isn't anymore, in which case protected content must no
+    /// longer be shown.
+    #[inline]
+    pub fn is_lost(&self) -> bool {
+        self.old == Some(ContentProtection::Enabled) && self.new != Some(ContentProtection::Enabled)
+    }
+
+    /// Returns true if the connector's signal is newly protected.
+    #[inline]
+    pub fn is_established(&self) -> bool {
+        self.old != Some(ContentProtection::Enabled) && self.new == Some(ContentProtection::Enabled)
+    }
+}
+
+impl ContentProtectionMonitor {
+    /// Start tracking the connector described by the given properties,
+    /// reading its current state from the card.
+    pub fn new(card: &crate::Card, props: ContentProtectionProps) -> Result<Self, Error> {
+        let mut ret = Self { props, state: None };
+        ret.state = ret.read_state(card)?;
+        Ok(ret)
+    }
+
+    #[inline]
+    pub fn props(&self) -> &ContentProtectionProps {
+        &self.props
+    }
+
+    /// Returns the most recently observed state, or `None` if the property
+    /// had a value that this library doesn't know.
+    #[inline]
+    pub fn state(&self) -> Option<ContentProtection> {
+        self.state
+    }
+
+    /// Returns true if the connector's signal was protected when the state
+    /// was last observed.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.state == Some(ContentProtection::Enabled)
+    }
+
+    /// Re-read the state if the given hotplug event might relate to it,
+    /// returning the change if the state has changed.
+    pub fn handle_hotplug(
+        &mut self,
+        card: &crate::Card,
+        event: &HotplugEvent,
+    ) -> Result<Option<ContentProtectionChange>, Error> {
+        if !event.may_affect_connector(self.props.connector_id) {
+            return Ok(None);
+        }
+        if event
+            .property_id
+            .is_some_and(|id| id != self.props.content_protection)
+        {
+            return Ok(None);
+        }
+        self.refresh(card)
+    }
+
+    /// Re-read the state, returning the change if the state has changed.
+    pub fn refresh(
+        &mut self,
+        card: &crate::Card,
+    ) -> Result<Option<ContentProtectionChange>, Error> {
+        let new = self.read_state(card)?;
+        let old = core::mem::replace(&mut self.state, new);
+        if old == new {
+            return Ok(None);
+        }
+        Ok(Some(ContentProtectionChange {
+            connector_id: self.props.connector_id,
+            old,
+            new,
+        }))
+    }
+
+    fn read_state(&self, card: &crate::Card) -> Result<Option<ContentProtection>, Error> {
+        let props = card.object_properties(self.props.connector_id)?;
+        let value = props
+            .iter()
+            .find(|p| p.prop_id == self.props.content_protection)
+            .ok_or(Error::NotSupported)?
+            .value;
+        Ok(self.props.state_from_raw(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Properties whose raw values differ from the usual enum values, to
+    /// check that they're resolved through the property metadata.
+    fn props() -> ContentProtectionProps {
+        ContentProtectionProps {
+            connector_id: ConnectorId(95),
+            content_protection: PropertyId(21),
+            hdcp_content_type: None,
+            protection_values: [Some(10), Some(11), Some(12)],
+            type_values: [None, None],
+        }
+    }
+
+    #[test]
+    fn state_from_raw() {
+        let props = props();
+        assert_eq!(props.state_from_raw(10), Some(ContentProtection::Undesired));
+        assert_eq!(props.state_from_raw(11), Some(ContentProtection::Desired));
+        assert_eq!(props.state_from_raw(12), Some(ContentProtection::Enabled));
+        assert_eq!(props.state_from_raw(0), None);
+        assert_eq!(props.state_from_raw(2), None);
+    }
+
+    #[test]
+    fn change_lost_and_established() {
+        let change = |old, new| ContentProtectionChange {
+            connector_id: ConnectorId(95),
+            old,
+            new,
+        };
+        let undesired = Some(ContentProtection::Undesired);
+        let desired = Some(ContentProtection::Desired);
+        let enabled = Some(ContentProtection::Enabled);
+
+        // The kernel moves from Enabled back to Desired when the link
+        // loses protection, and then tries to re-establish it.
+        let lost = change(enabled, desired);
+        assert!(lost.is_lost());
+        assert!(!lost.is_established());
+        assert!(change(enabled, undesired).is_lost());
+        assert!(change(enabled, None).is_lost());
+
+        let established = change(desired, enabled);
+        assert!(established.is_established());
+        assert!(!established.is_lost());
+
+        assert!(!change(undesired, desired).is_lost());
+        assert!(!change(undesired, desired).is_established());
+        assert!(!change(enabled, enabled).is_lost());
+    }
+}
@@ -0,0 +1,169 @@
+use alloc::vec::Vec;
+use core::ops::{BitAnd, BitOr};
+
+use super::{CardResources, CrtcId, EncoderId, EncoderState};
+
+/// The type of an encoder, which describes the kind of signal it produces.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[repr(u32)]
+pub enum EncoderType {
+    None = 0,
+    Dac = 1,
+    Tmds = 2,
+    Lvds = 3,
+    TvDac = 4,
+    Virtual = 5,
+    Dsi = 6,
+    /// A fake encoder for one stream of a DisplayPort Multi-Stream
+    /// Transport connector.
+    DpMst = 7,
+    Dpi = 8,
+    Other = !0, // Not used by kernel, but used by us if kernel returns something we don't know
+}
+
+impl From<u32> for EncoderType {
+    #[inline]
+    fn from(value: u32) -> Self {
+        if value < 9 {
+            // Safety: all values in this range are valid representations
+            // of this enum, as described above.
+            unsafe { core::mem::transmute::<u32, Self>(value) }
+        } else {
+            Self::Other
+        }
+    }
+}
+
+macro_rules! index_mask {
+    ($name:ident, $id:ident, $ids_field:ident, $index_fn:ident, $desc:literal) => {
+        #[doc = concat!("A set of ", $desc, "s, represented as a bitmask of indices into")]
+        #[doc = concat!("[`CardResources::", stringify!($ids_field), "`].")]
+        ///
+        /// The resources used to interpret the mask must have been retrieved
+        /// from the same card as the object the mask came from.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+        pub struct $name(pub u32);
+
+        impl $name {
+            pub const NONE: Self = Self(0);
+
+            /// Returns true if no bits are set.
+            #[inline(always)]
+            pub const fn is_empty(self) -> bool {
+                self.0 == 0
+            }
+
+            /// Returns true if the bit for the given index is set.
+            #[inline(always)]
+            pub const fn contains_index(self, index: usize) -> bool {
+                index < 32 && (self.0 & (1 << index)) != 0
+            }
+
+            #[doc = concat!("Returns true if the given ", $desc, " is in the set.")]
+            pub fn contains(self, id: $id, resources: &CardResources) -> bool {
+                resources
+                    .$index_fn(id)
+                    .is_some_and(|index| self.contains_index(index))
+            }
+
+            /// Returns the indices of the bits that are set, in increasing
+            /// order.
+            pub fn indices(self) -> impl Iterator<Item = usize> {
+                (0..32).filter(move |i| self.contains_index(*i))
+            }
+
+            #[doc = concat!("Returns the ids of the ", $desc, "s in the set.")]
+            ///
+            /// Bits that don't correspond to an object in the given resources
+            /// are ignored.
+            pub fn ids(self, resources: &CardResources) -> Vec<$id> {
+                self.indices()
+                    .filter_map(|i| resources.$ids_field.get(i).copied())
+                    .collect()
+            }
+
+            #[doc = concat!("Returns the set containing the given ", $desc, "s.")]
+            ///
+            /// Returns `None` if any of the ids aren't in the given resources,
+            /// or if their index is too large to represent in the mask.
+            pub fn from_ids(ids: &[$id], resources: &CardResources) -> Option<Self> {
+                let mut ret = 0;
+                for id in ids {
+                    let index = resources.$index_fn(*id).filter(|i| *i < 32)?;
+                    ret |= 1 << index;
+                }
+                Some(Self(ret))
+            }
+        }
+
+        impl BitOr for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitor(self, rhs: Self) -> Self::Output {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl BitAnd for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn bitand(self, rhs: Self) -> Self::Output {
+                Self(self.0 & rhs.0)
+            }
+        }
+    };
+}
+
+index_mask!(CrtcMask, CrtcId, crtc_ids, crtc_index, "CRTC");
+index_mask!(
+    EncoderMask,
+    EncoderId,
+    encoder_ids,
+    encoder_index,
+    "encoder"
+);
+
+impl CardResources {
+    /// Returns the index of the given CRTC in [`Self::crtc_ids`], which is
+    /// the bit that represents it in a [`CrtcMask`].
+    pub fn crtc_index(&self, id: CrtcId) -> Option<usize> {
+        self.crtc_ids.iter().position(|v| *v == id)
+    }
+
+    /// Returns the index of the given encoder in [`Self::encoder_ids`],
+    /// which is the bit that represents it in an [`EncoderMask`].
+    pub fn encoder_index(&self, id: EncoderId) -> Option<usize> {
+        self.encoder_ids.iter().position(|v| *v == id)
+    }
+
+    /// Returns the id of the CRTC at the given index in
+    /// [`Self::crtc_ids`], if any.
+    #[inline]
+    pub fn crtc_id_at(&self, index: usize) -> Option<CrtcId> {
+        self.crtc_ids.get(index).copied()
+    }
+
+    /// Returns the id of the encoder at the given index in
+    /// [`Self::encoder_ids`], if any.
+    #[inline]
+    pub fn encoder_id_at(&self, index: usize) -> Option<EncoderId> {
+        self.encoder_ids.get(index).copied()
+    }
+}
+
+impl EncoderState {
+    /// Returns the ids of the CRTCs that this encoder can be used with.
+    #[inline]
+    pub fn possible_crtc_ids(&self, resources: &CardResources) -> Vec<CrtcId> {
+        self.possible_crtcs.ids(resources)
+    }
+
+    /// Returns the ids of the encoders that can be active at the same time
+    /// as this one while sharing the same CRTC.
+    #[inline]
+    pub fn possible_clone_ids(&self, resources: &CardResources) -> Vec<EncoderId> {
+        self.possible_clones.ids(resources)
+    }
+}
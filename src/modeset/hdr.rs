@@ -0,0 +1,348 @@
+use crate::edid::ColorCharacteristics;
+use crate::result::Error;
+
+/// The value of `metadata_type` for CTA-861 Static Metadata Type 1, which is
+/// the only type the kernel currently supports.
+const HDMI_STATIC_METADATA_TYPE1: u8 = 0;
+
+/// An electro-optical transfer function that can be signalled to a sink
+/// in [`HdrOutputMetadata`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum HdrEotf {
+    /// Traditional gamma with standard dynamic range.
+    TraditionalSdr = 0,
+    /// Traditional gamma with high dynamic range.
+    TraditionalHdr = 1,
+    /// SMPTE ST 2084, also known as "PQ", as used by HDR10.
+    Pq = 2,
+    /// Hybrid Log-Gamma.
+    Hlg = 3,
+}
+
+impl HdrEotf {
+    /// Returns the EOTF for the given raw CTA-861 code, if it's known.
+    pub fn from_raw(raw: u8) -> Option<Self> {
+        match raw {
+            0 => Some(Self::TraditionalSdr),
+            1 => Some(Self::TraditionalHdr),
+            2 => Some(Self::Pq),
+            3 => Some(Self::Hlg),
+            _ => None,
+        }
+    }
+
+    /// Returns the raw CTA-861 code for the EOTF.
+    #[inline(always)]
+    pub const fn to_raw(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Static HDR metadata for the `HDR_OUTPUT_METADATA` connector property.
+///
+/// Use [`Self::to_bytes`] to produce the content of a property blob, using
+/// [`crate::Card::new_property_blob`], whose id can then be set as the
+/// property's value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HdrOutputMetadata {
+    pub eotf: HdrEotf,
+    /// The color primaries and white point of the mastering display.
+    pub mastering_primaries: ColorCharacteristics,
+    /// The maximum luminance of the mastering display in cd/m².
+    pub max_mastering_luminance: f64,
+    /// The minimum luminance of the mastering display in cd/m².
+    pub min_mastering_luminance: f64,
+    /// The maximum content light level in cd/m², or zero if unknown.
+    pub max_cll: u16,
+    /// The maximum frame-average light level in cd/m², or zero if unknown.
+    pub max_fall: u16,
+}
+
+impl HdrOutputMetadata {
+    /// The size of the kernel's `struct hdr_output_metadata`, which is the
+    /// size that property blobs must have.
+    pub const BLOB_SIZE: usize = 32;
+
+    /// Returns metadata for the given EOTF with all of the other fields
+    /// set to zero, meaning that they are unknown.
+    pub const fn new(eotf: HdrEotf) -> Self {
+        Self {
+            eotf,
+            mastering_primaries: ColorCharacteristics {
+                red: (0.0, 0.0),
+                green: (0.0, 0.0),
+                blue: (0.0, 0.0),
+                white: (0.0, 0.0),
+            },
+            max_mastering_luminance: 0.0,
+            min_mastering_luminance: 0.0,
+            max_cll: 0,
+            max_fall: 0,
+        }
+    }
+
+    /// Returns metadata for HDR10 content, which uses the PQ transfer
+    /// function, mastered on a display with BT.2020 primaries and the given
+    /// luminance range in cd/m².
+    pub const fn hdr10(min_mastering_luminance: f64, max_mastering_luminance: f64) -> Self {
+        Self::new(HdrEotf::Pq)
+            .with_mastering_primaries(ColorCharacteristics::BT2020)
+            .with_mastering_luminance(min_mastering_luminance, max_mastering_luminance)
+    }
+
+    /// Returns the metadata with the given mastering display primaries and
+    /// white point.
+    pub const fn with_mastering_primaries(mut self, primaries: ColorCharacteristics) -> Self {
+        self.mastering_primaries = primaries;
+        self
+    }
+
+    /// Returns the metadata with the given mastering display luminance range
+    /// in cd/m².
+    pub const fn with_mastering_luminance(mut self, min: f64, max: f64) -> Self {
+        self.min_mastering_luminance = min;
+        self.max_mastering_luminance = max;
+        self
+    }
+
+    /// Returns the metadata with the given maximum content light level and
+    /// maximum frame-average light level, in cd/m².
+    pub const fn with_content_light_level(mut self, max_cll: u16, max_fall: u16) -> Self {
+        self.max_cll = max_cll;
+        self.max_fall = max_fall;
+        self
+    }
+
+    /// Encode the metadata in the layout of the kernel's
+    /// `struct hdr_output_metadata`, ready to use as a property blob.
+    ///
+    /// The primaries are written in red, green, blue order. Values that
+    /// are out of range for their fields are clamped.
+    pub fn to_bytes(&self) -> [u8; Self::BLOB_SIZE] {
+        let mut ret = [0_u8; Self::BLOB_SIZE];
+        // struct hdr_output_metadata starts with a __u32 metadata_type,
+        // followed by the struct hdr_metadata_infoframe at offset 4.
+        ret[0..4].copy_from_slice(&(HDMI_STATIC_METADATA_TYPE1 as u32).to_ne_bytes());
+        let frame = &mut ret[4..];
+        frame[0] = self.eotf.to_raw();
+        frame[1] = HDMI_STATIC_METADATA_TYPE1;
+
+        let p = &self.mastering_primaries;
+        let mut words = [0_u16; 12];
+        for (i, (x, y)) in [p.red, p.green, p.blue, p.white].into_iter().enumerate() {
+            words[i * 2] = encode_chromaticity(x);
+            words[i * 2 + 1] = encode_chromaticity(y);
+        }
+        words[8] = round_to_u16(self.max_mastering_luminance);
+        words[9] = round_to_u16(self.min_mastering_luminance * 10000.0);
+        words[10] = self.max_cll;
+        words[11] = self.max_fall;
+        for (i, word) in words.into_iter().enumerate() {
+            frame[2 + i * 2..4 + i * 2].copy_from_slice(&word.to_ne_bytes());
+        }
+        ret
+    }
+
+    /// Parse the content of an `HDR_OUTPUT_METADATA` property blob.
+    ///
+    /// Returns [`Error::Invalid`] if the blob is not the size of the
+    /// kernel's `struct hdr_output_metadata`, or [`Error::NotSupported`]
+    /// if it uses a metadata type or EOTF that this library doesn't know.
+    pub fn parse(raw: &[u8]) -> Result<Self, Error> {
+        if raw.len() != Self::BLOB_SIZE {
+            return Err(Error::Invalid);
+        }
+        let metadata_type = u32::from_ne_bytes([raw[0], raw[1], raw[2], raw[3]]);
+        let frame = &raw[4..];
+        if metadata_type != HDMI_STATIC_METADATA_TYPE1 as u32
+            || frame[1] != HDMI_STATIC_METADATA_TYPE1
+        {
+            return Err(Error::NotSupported);
+        }
+        let eotf = HdrEotf::from_raw(frame[0]).ok_or(Error::NotSupported)?;
+
+        let word = |i: usize| u16::from_ne_bytes([frame[2 + i * 2], frame[3 + i * 2]]);
+        let coords = |i: usize| {
+            (
+                word(i * 2) as f64 / 50000.0,
+                word(i * 2 + 1) as f64 / 50000.0,
+            )
+        };
+        Ok(Self {
+            eotf,
+            mastering_primaries: ColorCharacteristics {
+                red: coords(0),
+                green: coords(1),
+                blue: coords(2),
+                white: coords(3),
+            },
+            max_mastering_luminance: word(8) as f64,
+            min_mastering_luminance: word(9) as f64 / 10000.0,
+            max_cll: word(10),
+            max_fall: word(11),
+        })
+    }
+}
+
+/// Encode a chromaticity coordinate in units of 0.00002, as CTA-861 uses.
+fn encode_chromaticity(v: f64) -> u16 {
+    round_to_u16(v * 50000.0).min(50000)
+}
+
+fn round_to_u16(v: f64) -> u16 {
+    // Float-to-int casts saturate, so this also clamps to the valid range.
+    (v + 0.5) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::mem::{align_of, offset_of, size_of};
+
+    /// Mirrors of the kernel's `struct hdr_metadata_infoframe` and
+    /// `struct hdr_output_metadata` from `drm_mode.h`.
+    #[repr(C)]
+    struct HdrMetadataInfoframe {
+        eotf: u8,
+        metadata_type: u8,
+        display_primaries: [[u16; 2]; 3],
+        white_point: [u16; 2],
+        max_display_mastering_luminance: u16,
+        min_display_mastering_luminance: u16,
+        max_cll: u16,
+        max_fall: u16,
+    }
+
+    #[repr(C)]
+    struct KernelHdrOutputMetadata {
+        metadata_type: u32,
+        hdmi_metadata_type1: HdrMetadataInfoframe,
+    }
+
+    fn word_at(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_ne_bytes([bytes[offset], bytes[offset + 1]])
+    }
+
+    #[test]
+    fn kernel_layout() {
+        assert_eq!(size_of::<HdrMetadataInfoframe>(), 26);
+        assert_eq!(align_of::<HdrMetadataInfoframe>(), 2);
+        // 30 bytes of fields padded to the alignment of metadata_type.
+        assert_eq!(
+            size_of::<KernelHdrOutputMetadata>(),
+            HdrOutputMetadata::BLOB_SIZE
+        );
+
+        let metadata = HdrOutputMetadata::hdr10(0.005, 1000.0).with_content_light_level(800, 400);
+        let bytes = metadata.to_bytes();
+        let frame = offset_of!(KernelHdrOutputMetadata, hdmi_metadata_type1);
+        let field = |offset: usize| frame + offset;
+        assert_eq!(
+            bytes[offset_of!(KernelHdrOutputMetadata, metadata_type)..][..4],
+            0_u32.to_ne_bytes()
+        );
+        assert_eq!(bytes[field(offset_of!(HdrMetadataInfoframe, eotf))], 2);
+        assert_eq!(
+            bytes[field(offset_of!(HdrMetadataInfoframe, metadata_type))],
+            0
+        );
+
+        let primaries = field(offset_of!(HdrMetadataInfoframe, display_primaries));
+        let expected = [[35400, 14600], [8500, 39850], [6550, 2300]];
+        for (i, [x, y]) in expected.into_iter().enumerate() {
+            assert_eq!(word_at(&bytes, primaries + i * 4), x);
+            assert_eq!(word_at(&bytes, primaries + i * 4 + 2), y);
+        }
+        let white = field(offset_of!(HdrMetadataInfoframe, white_point));
+        assert_eq!(word_at(&bytes, white), 15635);
+        assert_eq!(word_at(&bytes, white + 2), 16450);
+        assert_eq!(
+            word_at(
+                &bytes,
+                field(offset_of!(
+                    HdrMetadataInfoframe,
+                    max_display_mastering_luminance
+                ))
+            ),
+            1000
+        );
+        assert_eq!(
+            word_at(
+                &bytes,
+                field(offset_of!(
+                    HdrMetadataInfoframe,
+                    min_display_mastering_luminance
+                ))
+            ),
+            50
+        );
+        assert_eq!(
+            word_at(&bytes, field(offset_of!(HdrMetadataInfoframe, max_cll))),
+            800
+        );
+        assert_eq!(
+            word_at(&bytes, field(offset_of!(HdrMetadataInfoframe, max_fall))),
+            400
+        );
+        // Tail padding.
+        assert_eq!(bytes[30..], [0, 0]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let metadata = HdrOutputMetadata::new(HdrEotf::Hlg)
+            .with_mastering_primaries(ColorCharacteristics {
+                red: (0.68, 0.32),
+                green: (0.265, 0.69),
+                blue: (0.15, 0.06),
+                white: (0.3127, 0.329),
+            })
+            .with_mastering_luminance(0.0001, 4000.0)
+            .with_content_light_level(1000, 250);
+        let parsed = HdrOutputMetadata::parse(&metadata.to_bytes()).unwrap();
+        assert_eq!(parsed, metadata);
+        assert_eq!(
+            HdrOutputMetadata::parse(&parsed.to_bytes()).unwrap(),
+            parsed
+        );
+
+        let sdr = HdrOutputMetadata::new(HdrEotf::TraditionalSdr);
+        assert_eq!(HdrOutputMetadata::parse(&sdr.to_bytes()).unwrap(), sdr);
+    }
+
+    #[test]
+    fn parse_rejects() {
+        let bytes = HdrOutputMetadata::new(HdrEotf::Pq).to_bytes();
+        assert_eq!(HdrOutputMetadata::parse(&bytes[..31]), Err(Error::Invalid));
+
+        let mut unknown_eotf = bytes;
+        unknown_eotf[4] = 4;
+        assert_eq!(
+            HdrOutputMetadata::parse(&unknown_eotf),
+            Err(Error::NotSupported)
+        );
+
+        let mut unknown_type = bytes;
+        unknown_type[0..4].copy_from_slice(&1_u32.to_ne_bytes());
+        assert_eq!(
+            HdrOutputMetadata::parse(&unknown_type),
+            Err(Error::NotSupported)
+        );
+    }
+
+    #[test]
+    fn clamping() {
+        let bytes = HdrOutputMetadata::new(HdrEotf::Pq)
+            .with_mastering_primaries(ColorCharacteristics {
+                red: (1.5, -0.1),
+                ..ColorCharacteristics::BT709
+            })
+            .with_mastering_luminance(10.0, 70000.0)
+            .to_bytes();
+        assert_eq!(word_at(&bytes, 6), 50000);
+        assert_eq!(word_at(&bytes, 8), 0);
+        assert_eq!(word_at(&bytes, 22), u16::MAX);
+        assert_eq!(word_at(&bytes, 24), u16::MAX);
+    }
+}
@@ -0,0 +1,406 @@
+use core::cmp::Ordering;
+use core::ops::BitOr;
+
+use super::ModeInfo;
+use crate::ioctl;
+
+/// The polarity of a sync signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyncPolarity {
+    Positive,
+    Negative,
+}
+
+/// The 3D stereo layout of a mode, from the mode's flags.
+///
+/// The kernel only reports stereo modes to clients that have enabled
+/// [`crate::ClientCap::Stereo3d`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum Stereo3d {
+    None = 0,
+    FramePacking = 1,
+    FieldAlternative = 2,
+    LineAlternative = 3,
+    SideBySideFull = 4,
+    LDepth = 5,
+    LDepthGfxGfxDepth = 6,
+    TopAndBottom = 7,
+    SideBySideHalf = 8,
+    Other = !0, // Not used by kernel, but used by us if kernel returns something we don't know
+}
+
+impl From<u32> for Stereo3d {
+    /// Convert from the value of the 3D field, after shifting it down to
+    /// start at bit zero.
+    #[inline]
+    fn from(value: u32) -> Self {
+        if value < 9 {
+            // Safety: all values in this range are valid representations
+            // of this enum, as described above.
+            unsafe { core::mem::transmute::<u32, Self>(value) }
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// The picture aspect ratio of a mode, from the mode's flags.
+///
+/// The kernel only reports aspect ratios to clients that have enabled
+/// [`crate::ClientCap::AspectRatio`], and otherwise always uses
+/// [`PictureAspectRatio::None`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum PictureAspectRatio {
+    None = 0,
+    Ratio4x3 = 1,
+    Ratio16x9 = 2,
+    Ratio64x27 = 3,
+    Ratio256x135 = 4,
+    Other = !0, // Not used by kernel, but used by us if kernel returns something we don't know
+}
+
+impl PictureAspectRatio {
+    /// Returns the ratio as `(width, height)`, if it's known.
+    pub const fn ratio(self) -> Option<(u16, u16)> {
+        match self {
+            Self::Ratio4x3 => Some((4, 3)),
+            Self::Ratio16x9 => Some((16, 9)),
+            Self::Ratio64x27 => Some((64, 27)),
+            Self::Ratio256x135 => Some((256, 135)),
+            Self::None | Self::Other => None,
+        }
+    }
+}
+
+impl From<u32> for PictureAspectRatio {
+    /// Convert from the value of the aspect ratio field, after shifting it
+    /// down to start at bit zero.
+    #[inline]
+    fn from(value: u32) -> Self {
+        if value < 5 {
+            // Safety: all values in this range are valid representations
+            // of this enum, as described above.
+            unsafe { core::mem::transmute::<u32, Self>(value) }
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// The type bits of a mode, from [`ModeInfo::typ`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ModeType(pub u32);
+
+impl ModeType {
+    pub const NONE: Self = Self(0);
+    /// The mode is the display's preferred mode.
+    pub const PREFERRED: Self = Self(ioctl::DRM_MODE_TYPE_PREFERRED);
+    /// The mode was defined by userspace, such as from the kernel command
+    /// line.
+    pub const USERDEF: Self = Self(ioctl::DRM_MODE_TYPE_USERDEF);
+    /// The mode was provided by the driver, usually from the display's EDID.
+    pub const DRIVER: Self = Self(ioctl::DRM_MODE_TYPE_DRIVER);
+    /// Obsolete; no longer set by the kernel.
+    pub const BUILTIN: Self = Self(ioctl::DRM_MODE_TYPE_BUILTIN);
+    /// Obsolete; no longer set by the kernel.
+    pub const CLOCK_C: Self = Self(ioctl::DRM_MODE_TYPE_CLOCK_C);
+    /// Obsolete; no longer set by the kernel.
+    pub const CRTC_C: Self = Self(ioctl::DRM_MODE_TYPE_CRTC_C);
+    /// Obsolete; no longer set by the kernel.
+    pub const DEFAULT: Self = Self(ioctl::DRM_MODE_TYPE_DEFAULT);
+
+    /// Returns true if all of the bits in `other` are also set in `self`.
+    #[inline(always)]
+    pub const fn contains(self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+}
+
+impl BitOr for ModeType {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl ModeInfo {
+    /// Returns the mode's type bits.
+    #[inline(always)]
+    pub const fn mode_type(&self) -> ModeType {
+        ModeType(self.typ)
+    }
+
+    /// Returns true if this is the display's preferred mode.
+    #[inline(always)]
+    pub const fn is_preferred(&self) -> bool {
+        self.mode_type().contains(ModeType::PREFERRED)
+    }
+
+    /// Returns the polarity of the horizontal sync signal, if the mode
+    /// specifies one.
+    #[inline]
+    pub const fn hsync_polarity(&self) -> Option<SyncPolarity> {
+        self.polarity(ioctl::DRM_MODE_FLAG_PHSYNC, ioctl::DRM_MODE_FLAG_NHSYNC)
+    }
+
+    /// Returns the polarity of the vertical sync signal, if the mode
+    /// specifies one.
+    #[inline]
+    pub const fn vsync_polarity(&self) -> Option<SyncPolarity> {
+        self.polarity(ioctl::DRM_MODE_FLAG_PVSYNC, ioctl::DRM_MODE_FLAG_NVSYNC)
+    }
+
+    /// Returns the polarity of the composite sync signal, if the mode
+    /// specifies one.
+    #[inline]
+    pub const fn csync_polarity(&self) -> Option<SyncPolarity> {
+        self.polarity(ioctl::DRM_MODE_FLAG_PCSYNC, ioctl::DRM_MODE_FLAG_NCSYNC)
+    }
+
+    const fn polarity(&self, positive: u32, negative: u32) -> Option<SyncPolarity> {
+        if (self.flags & positive) != 0 {
+            Some(SyncPolarity::Positive)
+        } else if (self.flags & negative) != 0 {
+            Some(SyncPolarity::Negative)
+        } else {
+            None
+        }
+    }
+
+    /// Set the polarity of the horizontal sync signal.
+    #[inline]
+    pub fn set_hsync_polarity(&mut self, polarity: Option<SyncPolarity>) {
+        self.set_polarity(
+            polarity,
+            ioctl::DRM_MODE_FLAG_PHSYNC,
+            ioctl::DRM_MODE_FLAG_NHSYNC,
+        );
+    }
+
+    /// Set the polarity of the vertical sync signal.
+    #[inline]
+    pub fn set_vsync_polarity(&mut self, polarity: Option<SyncPolarity>) {
+        self.set_polarity(
+            polarity,
+            ioctl::DRM_MODE_FLAG_PVSYNC,
+            ioctl::DRM_MODE_FLAG_NVSYNC,
+        );
+    }
+
+    fn set_polarity(&mut self, polarity: Option<SyncPolarity>, positive: u32, negative: u32) {
+        self.flags &= !(positive | negative);
+        self.flags |= match polarity {
+            Some(SyncPolarity::Positive) => positive,
+            Some(SyncPolarity::Negative) => negative,
+            None => 0,
+        };
+    }
+
+    /// Returns true if the mode is interlaced.
+    #[inline(always)]
+    pub const fn is_interlaced(&self) -> bool {
+        (self.flags & ioctl::DRM_MODE_FLAG_INTERLACE) != 0
+    }
+
+    /// Returns true if each line of the mode is scanned out twice.
+    #[inline(always)]
+    pub const fn is_doublescan(&self) -> bool {
+        (self.flags & ioctl::DRM_MODE_FLAG_DBLSCAN) != 0
+    }
+
+    /// Returns true if the mode uses a composite sync signal.
+    #[inline(always)]
+    pub const fn has_csync(&self) -> bool {
+        (self.flags & ioctl::DRM_MODE_FLAG_CSYNC) != 0
+    }
+
+    /// Returns true if the mode uses pixel multiplexing.
+    #[inline(always)]
+    pub const fn is_pixmux(&self) -> bool {
+        (self.flags & ioctl::DRM_MODE_FLAG_PIXMUX) != 0
+    }
+
+    /// Returns true if each pixel of the mode is sent twice, as is used for
+    /// some low-resolution TV modes.
+    #[inline(always)]
+    pub const fn is_dblclk(&self) -> bool {
+        (self.flags & ioctl::DRM_MODE_FLAG_DBLCLK) != 0
+    }
+
+    /// Returns the mode's 3D stereo layout.
+    #[inline]
+    pub fn stereo_3d(&self) -> Stereo3d {
+        Stereo3d::from((self.flags & ioctl::DRM_MODE_FLAG_3D_MASK) >> 14)
+    }
+
+    /// Set the mode's 3D stereo layout.
+    ///
+    /// [`Stereo3d::Other`] is treated as [`Stereo3d::None`].
+    #[inline]
+    pub fn set_stereo_3d(&mut self, layout: Stereo3d) {
+        let raw = match layout {
+            Stereo3d::Other => 0,
+            layout => layout as u32,
+        };
+        self.flags = (self.flags & !ioctl::DRM_MODE_FLAG_3D_MASK) | (raw << 14);
+    }
+
+    /// Returns the mode's picture aspect ratio.
+    #[inline]
+    pub fn picture_aspect_ratio(&self) -> PictureAspectRatio {
+        PictureAspectRatio::from((self.flags & ioctl::DRM_MODE_FLAG_PIC_AR_MASK) >> 19)
+    }
+
+    /// Set the mode's picture aspect ratio.
+    ///
+    /// The kernel rejects modes with an aspect ratio unless the client has
+    /// enabled [`crate::ClientCap::AspectRatio`]. [`PictureAspectRatio::Other`]
+    /// is treated as [`PictureAspectRatio::None`].
+    #[inline]
+    pub fn set_picture_aspect_ratio(&mut self, ratio: PictureAspectRatio) {
+        let raw = match ratio {
+            PictureAspectRatio::Other => 0,
+            ratio => ratio as u32,
+        };
+        self.flags = (self.flags & !ioctl::DRM_MODE_FLAG_PIC_AR_MASK) | (raw << 19);
+    }
+}
+
+impl ModeInfo {
+    /// Returns the exact refresh rate in millihertz, accounting for
+    /// interlacing, doublescan and `vscan`.
+    ///
+    /// Unlike [`Self::vrefresh`], this distinguishes rates such as 59.94Hz
+    /// from 60Hz. Returns zero if the mode has no total size, and saturates
+    /// at [`u32::MAX`] for nonsensical timings whose rate doesn't fit.
+    pub fn refresh_mhz(&self) -> u32 {
+        u32::try_from(self.exact_refresh_mhz()).unwrap_or(u32::MAX)
+    }
+
+    fn exact_refresh_mhz(&self) -> u64 {
+        // clock is in kHz, so multiplying by 10^6 gives millihertz.
+        let mut num = self.clock as u64 * 1_000_000;
+        let mut den = self.htotal as u64 * self.vtotal as u64;
+        if self.is_interlaced() {
+            num *= 2;
+        }
+        if self.is_doublescan() {
+            den *= 2;
+        }
+        if self.vscan > 1 {
+            den *= self.vscan as u64;
+        }
+        (num + den / 2).checked_div(den).unwrap_or(0)
+    }
+
+    /// Returns the pixel clock in hertz.
+    #[inline(always)]
+    pub const fn pixel_clock_hz(&self) -> u64 {
+        self.clock as u64 * 1000
+    }
+
+    /// Returns the horizontal line rate in hertz, rounded to the nearest
+    /// hertz, or zero if the mode has no horizontal total. Saturates at
+    /// [`u32::MAX`] if the rate doesn't fit.
+    pub fn line_rate_hz(&self) -> u32 {
+        let den = self.htotal as u64;
+        let rate = (self.pixel_clock_hz() + den / 2)
+            .checked_div(den)
+            .unwrap_or(0);
+        u32::try_from(rate).unwrap_or(u32::MAX)
+    }
+
+    /// Returns the duration of each refresh cycle in nanoseconds, or zero if
+    /// the mode has no refresh rate.
+    pub fn frame_period_ns(&self) -> u64 {
+        let mhz = self.exact_refresh_mhz();
+        (1_000_000_000_000 + mhz / 2).checked_div(mhz).unwrap_or(0)
+    }
+
+    /// Returns true if the two modes have the same timings and flags,
+    /// ignoring their names, types, and `vrefresh` fields.
+    pub fn same_timings(&self, other: &Self) -> bool {
+        self.timings_key() == other.timings_key()
+    }
+
+    fn timings_key(&self) -> (u32, [u16; 10], u32) {
+        (
+            self.clock,
+            [
+                self.hdisplay,
+                self.hsync_start,
+                self.hsync_end,
+                self.htotal,
+                self.hskew,
+                self.vdisplay,
+                self.vsync_start,
+                self.vsync_end,
+                self.vtotal,
+                self.vscan,
+            ],
+            self.flags,
+        )
+    }
+
+    /// Compare two modes in the order they would typically be presented to
+    /// a user: larger resolutions first, then higher refresh rates, then
+    /// preferred modes before others, then progressive modes before
+    /// interlaced ones.
+    ///
+    /// Remaining ties are broken by the other timings, so that only modes
+    /// with [the same timings](Self::same_timings) can compare as equal.
+    /// [`super::ConnectorState::sorted_modes`] uses this order.
+    pub fn display_order(&self, other: &Self) -> Ordering {
+        let area = |m: &Self| m.hdisplay as u32 * m.vdisplay as u32;
+        area(other)
+            .cmp(&area(self))
+            .then_with(|| other.hdisplay.cmp(&self.hdisplay))
+            .then_with(|| other.refresh_mhz().cmp(&self.refresh_mhz()))
+            .then_with(|| other.is_preferred().cmp(&self.is_preferred()))
+            .then_with(|| self.is_interlaced().cmp(&other.is_interlaced()))
+            .then_with(|| self.timings_key().cmp(&other.timings_key()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates() {
+        let mode =
+            ModeInfo::parse_modeline("x 148.5 1920 2008 2052 2200 1080 1084 1089 1125").unwrap();
+        assert_eq!(mode.refresh_mhz(), 60000);
+        assert_eq!(mode.line_rate_hz(), 67500);
+        assert_eq!(mode.frame_period_ns(), 16_666_667);
+
+        let mode = ModeInfo::parse_modeline("x 1 1 2 3 0 1 2 3 4").unwrap();
+        assert_eq!(mode.refresh_mhz(), 0);
+        assert_eq!(mode.line_rate_hz(), 0);
+        assert_eq!(mode.frame_period_ns(), 0);
+    }
+
+    #[test]
+    fn rates_saturate() {
+        // A maximal clock over a 1x1 total is far too fast for a u32.
+        let mut mode = ModeInfo::parse_modeline("x 1 1 1 1 1 1 1 1 1").unwrap();
+        mode.clock = u32::MAX;
+        assert_eq!(mode.refresh_mhz(), u32::MAX);
+        assert_eq!(mode.line_rate_hz(), u32::MAX);
+        // The frame period still uses the exact rate.
+        assert_eq!(mode.frame_period_ns(), 0);
+        mode.clock = 1_000_000;
+        mode.htotal = 1000;
+        mode.vtotal = 1000;
+        assert_eq!(mode.refresh_mhz(), 1_000_000);
+        mode.htotal = 1;
+        mode.vtotal = 1;
+        assert_eq!(mode.refresh_mhz(), u32::MAX);
+        assert_eq!(mode.frame_period_ns(), 1);
+    }
+}
@@ -0,0 +1,518 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use super::{CvtBlanking, ModeInfo};
+use crate::ioctl;
+use crate::result::Error;
+
+/// The modeline flag keywords and the mode flags they represent, in the
+/// order that [`ModeInfo::to_modeline`] writes them.
+const MODELINE_FLAGS: [(&str, u32); 9] = [
+    ("+hsync", ioctl::DRM_MODE_FLAG_PHSYNC),
+    ("-hsync", ioctl::DRM_MODE_FLAG_NHSYNC),
+    ("+vsync", ioctl::DRM_MODE_FLAG_PVSYNC),
+    ("-vsync", ioctl::DRM_MODE_FLAG_NVSYNC),
+    ("interlace", ioctl::DRM_MODE_FLAG_INTERLACE),
+    ("doublescan", ioctl::DRM_MODE_FLAG_DBLSCAN),
+    ("composite", ioctl::DRM_MODE_FLAG_CSYNC),
+    ("+csync", ioctl::DRM_MODE_FLAG_PCSYNC),
+    ("-csync", ioctl::DRM_MODE_FLAG_NCSYNC),
+];
+
+impl ModeInfo {
+    /// Parse a mode from an Xorg-style modeline, such as
+    /// `"1920x1080_60" 148.50 1920 2008 2052 2200 1080 1084 1089 1125 +hsync +vsync`.
+    ///
+    /// A leading `Modeline` keyword is allowed, and the name may be unquoted
+    /// if it contains no spaces. The flags `+hsync`, `-hsync`, `+vsync`,
+    /// `-vsync`, `interlace`, `doublescan`, `composite`, `+csync` and
+    /// `-csync` are supported, along with `hskew` and `vscan` followed by a
+    /// number. The resulting mode has the type `DRM_MODE_TYPE_USERDEF`.
+    ///
+    /// Returns [`Error::Invalid`] if the modeline is malformed.
+    pub fn parse_modeline(s: &str) -> Result<Self, Error> {
+        let mut s = s.trim();
+        if let Some(keyword) = s.get(..8) {
+            if keyword.eq_ignore_ascii_case("modeline") {
+                s = s[8..].trim_start();
+            }
+        }
+        let (name, rest) = if let Some(quoted) = s.strip_prefix('"') {
+            quoted.split_once('"').ok_or(Error::Invalid)?
+        } else {
+            s.split_once(char::is_whitespace).ok_or(Error::Invalid)?
+        };
+
+        let mut tokens = rest.split_whitespace();
+        let clock = parse_mhz(tokens.next().ok_or(Error::Invalid)?).ok_or(Error::Invalid)?;
+        let mut timings = [0_u16; 8];
+        for v in timings.iter_mut() {
+            *v = tokens
+                .next()
+                .and_then(|t| t.parse().ok())
+                .ok_or(Error::Invalid)?;
+        }
+
+        let mut mode = ModeInfo {
+            name: Vec::new(),
+            clock,
+            hdisplay: timings[0],
+            hsync_start: timings[1],
+            hsync_end: timings[2],
+            htotal: timings[3],
+            hskew: 0,
+            vdisplay: timings[4],
+            vsync_start: timings[5],
+            vsync_end: timings[6],
+            vtotal: timings[7],
+            vscan: 0,
+            vrefresh: 0,
+            flags: 0,
+            typ: ioctl::DRM_MODE_TYPE_USERDEF,
+        };
+        while let Some(token) = tokens.next() {
+            let mut number = || -> Result<u16, Error> {
+                tokens
+                    .next()
+                    .and_then(|t| t.parse().ok())
+                    .ok_or(Error::Invalid)
+            };
+            if token.eq_ignore_ascii_case("hskew") {
+                mode.hskew = number()?;
+                mode.flags |= ioctl::DRM_MODE_FLAG_HSKEW;
+            } else if token.eq_ignore_ascii_case("vscan") {
+                mode.vscan = number()?;
+            } else {
+                let (_, flag) = MODELINE_FLAGS
+                    .iter()
+                    .find(|(keyword, _)| token.eq_ignore_ascii_case(keyword))
+                    .ok_or(Error::Invalid)?;
+                mode.flags |= flag;
+            }
+        }
+
+        mode.fill_vrefresh_and_name();
+        if !name.is_empty() {
+            mode.name = name.as_bytes().to_vec();
+        }
+        Ok(mode)
+    }
+
+    /// Format the mode as an Xorg-style modeline, which
+    /// [`Self::parse_modeline`] can parse back into an equivalent mode.
+    ///
+    /// The result doesn't include a leading `Modeline` keyword.
+    pub fn to_modeline(&self) -> String {
+        let mut ret = String::new();
+        let name = String::from_utf8_lossy(&self.name);
+        let mhz = alloc::format!("{}.{:03}", self.clock / 1000, self.clock % 1000);
+        let mhz = mhz.strip_suffix('0').unwrap_or(&mhz);
+        // write! to a String can't fail.
+        let _ = write!(
+            ret,
+            "\"{}\" {} {} {} {} {} {} {} {} {}",
+            name.replace('"', ""),
+            mhz,
+            self.hdisplay,
+            self.hsync_start,
+            self.hsync_end,
+            self.htotal,
+            self.vdisplay,
+            self.vsync_start,
+            self.vsync_end,
+            self.vtotal,
+        );
+        for (keyword, flag) in MODELINE_FLAGS {
+            if (self.flags & flag) != 0 {
+                ret.push(' ');
+                ret.push_str(keyword);
+            }
+        }
+        if (self.flags & ioctl::DRM_MODE_FLAG_HSKEW) != 0 {
+            let _ = write!(ret, " hskew {}", self.hskew);
+        }
+        if self.vscan != 0 {
+            let _ = write!(ret, " vscan {}", self.vscan);
+        }
+        ret
+    }
+}
+
+/// Parse a decimal number of megahertz into kilohertz, rounding to the
+/// nearest kilohertz.
+fn parse_mhz(s: &str) -> Option<u32> {
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    if int.is_empty() && frac.is_empty() {
+        return None;
+    }
+    if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let int: u32 = if int.is_empty() { 0 } else { int.parse().ok()? };
+    let mut khz = int.checked_mul(1000)?;
+    let mut digits = frac.bytes().map(|b| (b - b'0') as u32);
+    for scale in [100, 10, 1] {
+        khz = khz.checked_add(digits.next().unwrap_or(0) * scale)?;
+    }
+    if digits.next().unwrap_or(0) >= 5 {
+        khz = khz.checked_add(1)?;
+    }
+    Some(khz)
+}
+
+/// A mode specification in the syntax of the kernel's `video=` command
+/// line parameter, such as `HDMI-A-1:1920x1080@60e` or `1920x1080MR`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CmdlineMode {
+    /// The name of the connector the specification applies to, if given.
+    pub connector: Option<String>,
+    /// The requested resolution, if given as `<width>x<height>`.
+    pub resolution: Option<(u16, u16)>,
+    /// The name of a named mode such as `NTSC` or `PAL`, if given instead
+    /// of a resolution.
+    pub name: Option<String>,
+    /// Calculate the mode using CVT, rather than GTF. (`M`)
+    pub cvt: bool,
+    /// Use CVT reduced blanking. (`R`)
+    pub reduced_blanking: bool,
+    pub bpp: Option<u32>,
+    pub refresh_hz: Option<u32>,
+    /// Use an interlaced mode. (`i`)
+    pub interlace: bool,
+    /// Add margins to the mode. (`m`)
+    pub margins: bool,
+    pub force: Option<CmdlineForce>,
+    /// Any comma-separated options that follow the mode, such as
+    /// `rotate=90` or `margin_left=20`, without interpretation.
+    pub options: Vec<String>,
+}
+
+/// How a `video=` mode specification overrides the connector's detected
+/// state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CmdlineForce {
+    /// Force the connector on. (`e`)
+    On,
+    /// Force the connector on, using its digital output. (`D`)
+    OnDigital,
+    /// Force the connector off. (`d`)
+    Off,
+}
+
+impl CmdlineMode {
+    /// Parse a mode specification in the syntax of the kernel's `video=`
+    /// parameter, with or without the `video=` prefix.
+    ///
+    /// The single-letter modifiers `M`, `R`, `i`, `m`, `e`, `D` and `d` can
+    /// appear in any order after the resolution, including after the bpp
+    /// and refresh rate.
+    ///
+    /// Returns [`Error::Invalid`] if the specification is malformed.
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let s = s.trim();
+        let s = s.strip_prefix("video=").unwrap_or(s);
+        let mut ret = Self::default();
+
+        let (spec, options) = s.split_once(',').unwrap_or((s, ""));
+        ret.options = options
+            .split(',')
+            .filter(|o| !o.is_empty())
+            .map(String::from)
+            .collect();
+        let spec = match spec.split_once(':') {
+            Some((connector, spec)) => {
+                ret.connector = Some(String::from(connector));
+                spec
+            }
+            None => spec,
+        };
+        if spec.is_empty() {
+            return Ok(ret);
+        }
+
+        if !spec.starts_with(|c: char| c.is_ascii_digit()) {
+            // A named mode, or just a forced connector state, which are the
+            // only modifiers a named mode can have.
+            let (name, extras) = split_extras(spec);
+            if !name.is_empty() {
+                ret.name = Some(String::from(name));
+            }
+            ret.parse_extras(extras)?;
+            return Ok(ret);
+        }
+
+        let (width, rest) = take_number(spec).ok_or(Error::Invalid)?;
+        let rest = rest.strip_prefix('x').ok_or(Error::Invalid)?;
+        let (height, mut rest) = take_number(rest).ok_or(Error::Invalid)?;
+        ret.resolution = Some((
+            u16::try_from(width).map_err(|_| Error::Invalid)?,
+            u16::try_from(height).map_err(|_| Error::Invalid)?,
+        ));
+
+        // Like the kernel, we accept the single-letter modifiers in any
+        // order after the resolution. We also accept `M` and `R` after the
+        // bpp and refresh rate, as in `1920x1080@60eR`, which the kernel
+        // would reject.
+        while let Some(c) = rest.chars().next() {
+            let after = &rest[c.len_utf8()..];
+            rest = match c {
+                '-' if ret.bpp.is_none() && ret.refresh_hz.is_none() => {
+                    let (bpp, r) = take_number(after).ok_or(Error::Invalid)?;
+                    ret.bpp = Some(bpp);
+                    r
+                }
+                '@' if ret.refresh_hz.is_none() => {
+                    let (refresh, r) = take_number(after).ok_or(Error::Invalid)?;
+                    ret.refresh_hz = Some(refresh);
+                    r
+                }
+                'M' => {
+                    ret.cvt = true;
+                    after
+                }
+                'R' => {
+                    ret.reduced_blanking = true;
+                    after
+                }
+                _ => {
+                    ret.parse_extras(&rest[..c.len_utf8()])?;
+                    after
+                }
+            };
+        }
+        Ok(ret)
+    }
+
+    fn parse_extras(&mut self, extras: &str) -> Result<(), Error> {
+        for c in extras.chars() {
+            match c {
+                'i' if self.resolution.is_some() => self.interlace = true,
+                'm' if self.resolution.is_some() => self.margins = true,
+                'e' => self.force = Some(CmdlineForce::On),
+                'D' => self.force = Some(CmdlineForce::OnDigital),
+                'd' => self.force = Some(CmdlineForce::Off),
+                _ => return Err(Error::Invalid),
+            }
+        }
+        Ok(())
+    }
+
+    /// Generate a mode for the specification in the same way as the kernel
+    /// does when the connector doesn't already have a matching mode: using
+    /// CVT if requested, or GTF otherwise, at 60Hz unless a refresh rate
+    /// was given.
+    ///
+    /// Returns `None` if the specification has no resolution, or if it
+    /// requests interlacing or margins, which this library's timing
+    /// generators don't support.
+    pub fn to_mode_info(&self) -> Option<ModeInfo> {
+        let (width, height) = self.resolution?;
+        if self.interlace || self.margins {
+            return None;
+        }
+        let refresh = self.refresh_hz.unwrap_or(60);
+        if self.cvt {
+            let blanking = if self.reduced_blanking {
+                CvtBlanking::ReducedV1
+            } else {
+                CvtBlanking::Standard
+            };
+            ModeInfo::cvt(width, height, refresh, blanking)
+        } else {
+            ModeInfo::gtf(width, height, refresh)
+        }
+    }
+
+    /// Returns true if the given mode matches the resolution, refresh rate
+    /// and interlacing in this specification.
+    ///
+    /// This can be used to choose from a connector's existing modes, as the
+    /// kernel prefers to do before generating a new mode.
+    pub fn matches(&self, mode: &ModeInfo) -> bool {
+        let Some((width, height)) = self.resolution else {
+            return false;
+        };
+        mode.hdisplay == width
+            && mode.vdisplay == height
+            && mode.is_interlaced() == self.interlace
+            && self.refresh_hz.is_none_or(|r| r == mode.vrefresh)
+    }
+}
+
+/// Split a trailing force modifier from a named mode.
+fn split_extras(s: &str) -> (&str, &str) {
+    match s.char_indices().last() {
+        Some((i, 'e' | 'D' | 'd')) => s.split_at(i),
+        _ => (s, ""),
+    }
+}
+
+fn take_number(s: &str) -> Option<(u32, &str)> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let n = s[..end].parse().ok()?;
+    Some((n, &s[end..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modeline_round_trip() {
+        let line = "Modeline \"1920x1080_60.00\"  173.00  1920 2048 2248 2576  1080 1083 1088 1120 -hsync +vsync";
+        let mode = ModeInfo::parse_modeline(line).unwrap();
+        assert_eq!(mode.clock, 173000);
+        assert_eq!(
+            (mode.hdisplay, mode.hsync_start, mode.hsync_end, mode.htotal),
+            (1920, 2048, 2248, 2576)
+        );
+        assert_eq!(
+            (mode.vdisplay, mode.vsync_start, mode.vsync_end, mode.vtotal),
+            (1080, 1083, 1088, 1120)
+        );
+        assert_eq!(
+            mode.flags,
+            ioctl::DRM_MODE_FLAG_NHSYNC | ioctl::DRM_MODE_FLAG_PVSYNC
+        );
+        assert_eq!(mode.name, b"1920x1080_60.00");
+        assert_eq!(mode.vrefresh, 60);
+        assert_eq!(ModeInfo::parse_modeline(&mode.to_modeline()).unwrap(), mode);
+    }
+
+    #[test]
+    fn modeline_round_trip_extras() {
+        let line = "\"odd mode\" 25.175 640 656 752 800 480 490 492 525 interlace doublescan +csync hskew 4 vscan 2";
+        let mode = ModeInfo::parse_modeline(line).unwrap();
+        assert_eq!(mode.clock, 25175);
+        assert_eq!(mode.hskew, 4);
+        assert_eq!(mode.vscan, 2);
+        assert!(mode.is_interlaced());
+        assert!(mode.is_doublescan());
+        assert_eq!(ModeInfo::parse_modeline(&mode.to_modeline()).unwrap(), mode);
+    }
+
+    #[test]
+    fn modeline_round_trip_vscan_1() {
+        // The kernel treats a vscan of 1 like 0, but the value should still
+        // survive a round trip.
+        let mode =
+            ModeInfo::parse_modeline("x 25.175 640 656 752 800 480 490 492 525 vscan 1").unwrap();
+        assert_eq!(mode.vscan, 1);
+        assert!(mode.to_modeline().ends_with(" vscan 1"));
+        assert_eq!(ModeInfo::parse_modeline(&mode.to_modeline()).unwrap(), mode);
+
+        let mode = ModeInfo::parse_modeline("x 25.175 640 656 752 800 480 490 492 525").unwrap();
+        assert!(!mode.to_modeline().contains("vscan"));
+    }
+
+    #[test]
+    fn modeline_clock_rounding() {
+        let mode = ModeInfo::parse_modeline("x 148.5004 1 2 3 4 1 2 3 4").unwrap();
+        assert_eq!(mode.clock, 148500);
+        let mode = ModeInfo::parse_modeline("x .0005 1 2 3 4 1 2 3 4").unwrap();
+        assert_eq!(mode.clock, 1);
+    }
+
+    #[test]
+    fn modeline_clock_overflow() {
+        for line in [
+            "x 4294967.999 1 2 3 4 1 2 3 4",
+            "x 4294967.2955 1 2 3 4 1 2 3 4",
+            "x 4294968 1 2 3 4 1 2 3 4",
+        ] {
+            assert_eq!(
+                ModeInfo::parse_modeline(line),
+                Err(Error::Invalid),
+                "{line}"
+            );
+        }
+        let mode = ModeInfo::parse_modeline("x 4294967.295 1 2 3 4 1 2 3 4").unwrap();
+        assert_eq!(mode.clock, u32::MAX);
+    }
+
+    #[test]
+    fn modeline_malformed() {
+        for line in [
+            "",
+            "\"unterminated 1 2 3 4 5 6 7 8 9",
+            "x 148.5 1920 2008 2052 2200 1080 1084 1089",
+            "x 148.5 1920 2008 2052 2200 1080 1084 1089 1125 +bogus",
+            "x 148.5 1920 2008 2052 2200 1080 1084 1089 1125 hskew",
+            "x 1.2.3 1 2 3 4 1 2 3 4",
+        ] {
+            assert_eq!(
+                ModeInfo::parse_modeline(line),
+                Err(Error::Invalid),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn cmdline_full() {
+        let mode = CmdlineMode::parse("video=HDMI-A-1:1920x1080MR-24@60,rotate=90").unwrap();
+        assert_eq!(mode.connector.as_deref(), Some("HDMI-A-1"));
+        assert_eq!(mode.resolution, Some((1920, 1080)));
+        assert!(mode.cvt);
+        assert!(mode.reduced_blanking);
+        assert_eq!(mode.bpp, Some(24));
+        assert_eq!(mode.refresh_hz, Some(60));
+        assert_eq!(mode.options, ["rotate=90"]);
+    }
+
+    #[test]
+    fn cmdline_modifiers_in_any_order() {
+        let mode = CmdlineMode::parse("1920x1080@60eR").unwrap();
+        assert_eq!(mode.refresh_hz, Some(60));
+        assert_eq!(mode.force, Some(CmdlineForce::On));
+        assert!(mode.reduced_blanking);
+
+        let mode = CmdlineMode::parse("1920x1080RM").unwrap();
+        assert!(mode.cvt && mode.reduced_blanking);
+
+        let mode = CmdlineMode::parse("1920x1080i@60").unwrap();
+        assert!(mode.interlace);
+        assert_eq!(mode.refresh_hz, Some(60));
+    }
+
+    #[test]
+    fn cmdline_margins_and_interlace() {
+        let mode = CmdlineMode::parse("1024x768@75im").unwrap();
+        assert!(mode.interlace);
+        assert!(mode.margins);
+        assert_eq!(mode.to_mode_info(), None);
+
+        let mode = CmdlineMode::parse("1024x768M@75").unwrap();
+        let generated = mode.to_mode_info().unwrap();
+        assert_eq!((generated.hdisplay, generated.vdisplay), (1024, 768));
+        assert!(mode.matches(&generated));
+    }
+
+    #[test]
+    fn cmdline_named_and_force() {
+        let mode = CmdlineMode::parse("DP-1:d").unwrap();
+        assert_eq!(mode.connector.as_deref(), Some("DP-1"));
+        assert_eq!(mode.name, None);
+        assert_eq!(mode.force, Some(CmdlineForce::Off));
+
+        let mode = CmdlineMode::parse("Composite-1:PALe").unwrap();
+        assert_eq!(mode.name.as_deref(), Some("PAL"));
+        assert_eq!(mode.force, Some(CmdlineForce::On));
+    }
+
+    #[test]
+    fn cmdline_malformed() {
+        for spec in [
+            "1920",
+            "1920x",
+            "1920x1080@",
+            "1920x1080-",
+            "1920x1080@60@50",
+            "1920x1080q",
+            "99999x1080",
+        ] {
+            assert_eq!(CmdlineMode::parse(spec), Err(Error::Invalid), "{spec}");
+        }
+    }
+}
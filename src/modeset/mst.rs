@@ -0,0 +1,307 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::ConnectorId;
+use crate::result::Error;
+
+/// The position of a DisplayPort Multi-Stream Transport connector in its
+/// topology, from the connector's `PATH` property.
+///
+/// The path stays the same when the same hub or daisy-chained display is
+/// plugged into the same port again, even though the connector ids change,
+/// so it's suitable for identifying a position in saved configurations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectorPath {
+    /// The physical connector that the topology is attached to.
+    pub parent: ConnectorId,
+    /// The port numbers to follow through each branch device from the
+    /// parent connector to this connector.
+    pub ports: Vec<u8>,
+}
+
+impl ConnectorPath {
+    /// Parse the content of a `PATH` property blob, such as `mst:57-1-8`.
+    ///
+    /// Returns [`Error::NotSupported`] if the path isn't an MST path, or
+    /// [`Error::Invalid`] if it's malformed.
+    pub fn parse(raw: &[u8]) -> Result<Self, Error> {
+        let raw = raw.split(|b| *b == 0).next().unwrap_or(raw);
+        let s = core::str::from_utf8(raw).map_err(|_| Error::Invalid)?;
+        let s = s.strip_prefix("mst:").ok_or(Error::NotSupported)?;
+        let mut parts = s.split('-');
+        let parent = parts
+            .next()
+            .and_then(|p| p.parse().ok())
+            .ok_or(Error::Invalid)?;
+        let ports = parts
+            .map(|p| p.parse().map_err(|_| Error::Invalid))
+            .collect::<Result<Vec<u8>, Error>>()?;
+        if ports.is_empty() {
+            return Err(Error::Invalid);
+        }
+        Ok(Self {
+            parent: ConnectorId(parent),
+            ports,
+        })
+    }
+
+    /// Returns the number of branch devices between the parent connector and
+    /// this connector, which is the number of ports in the path. For
+    /// example, the depth of `mst:57-1` is one.
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.ports.len()
+    }
+
+    /// Returns true if `self` is further down the same branch as `other`.
+    pub fn is_descendant_of(&self, other: &Self) -> bool {
+        self.parent == other.parent
+            && self.ports.len() > other.ports.len()
+            && self.ports.starts_with(&other.ports)
+    }
+}
+
+impl fmt::Display for ConnectorPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "mst:{}", self.parent.0)?;
+        for port in &self.ports {
+            write!(f, "-{}", port)?;
+        }
+        Ok(())
+    }
+}
+
+/// The MST topology attached to one physical connector.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MstTree {
+    /// The physical connector that the topology is attached to.
+    pub root: ConnectorId,
+    /// The ports of the first branch device, ordered by port number.
+    pub ports: Vec<MstNode>,
+}
+
+/// A port of a branch device in an MST topology.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MstNode {
+    pub port: u8,
+    /// The connector for the display attached to this port, if there is
+    /// one. Ports that only lead to another branch device have no connector.
+    pub connector_id: Option<ConnectorId>,
+    /// The ports of the branch device attached to this port, if any,
+    /// ordered by port number.
+    pub ports: Vec<MstNode>,
+}
+
+impl MstTree {
+    /// Build the MST trees for the given connectors and their paths, with
+    /// one tree for each distinct parent connector.
+    pub fn build(connectors: &[(ConnectorId, ConnectorPath)]) -> Vec<Self> {
+        let mut ret: Vec<Self> = Vec::new();
+        for (connector_id, path) in connectors {
+            let tree = match ret.iter_mut().find(|t| t.root == path.parent) {
+                Some(tree) => tree,
+                None => {
+                    ret.push(Self {
+                        root: path.parent,
+                        ports: Vec::new(),
+                    });
+                    ret.last_mut().unwrap()
+                }
+            };
+            if let Some(node) = node_entry(&mut tree.ports, &path.ports) {
+                node.connector_id = Some(*connector_id);
+            }
+        }
+        ret.sort_by_key(|t| t.root.0);
+        ret
+    }
+
+    /// Returns the node at the given sequence of port numbers, if present.
+    pub fn node(&self, ports: &[u8]) -> Option<&MstNode> {
+        let (first, rest) = ports.split_first()?;
+        let mut node = self.ports.iter().find(|n| n.port == *first)?;
+        for port in rest {
+            node = node.ports.iter().find(|n| n.port == *port)?;
+        }
+        Some(node)
+    }
+
+    /// Returns the connector currently at the given path, if any.
+    ///
+    /// The path's parent must match this tree's root.
+    pub fn connector_at(&self, path: &ConnectorPath) -> Option<ConnectorId> {
+        if path.parent != self.root {
+            return None;
+        }
+        self.node(&path.ports)?.connector_id
+    }
+
+    /// Returns all of the connectors in the tree along with their paths, in
+    /// depth-first order.
+    pub fn connectors(&self) -> Vec<(ConnectorId, ConnectorPath)> {
+        let mut ret = Vec::new();
+        let mut ports = Vec::new();
+        collect_connectors(self.root, &self.ports, &mut ports, &mut ret);
+        ret
+    }
+}
+
+/// Find the node at the given sequence of port numbers, creating it and any
+/// missing nodes on the way to it.
+fn node_entry<'a>(nodes: &'a mut Vec<MstNode>, ports: &[u8]) -> Option<&'a mut MstNode> {
+    let (first, rest) = ports.split_first()?;
+    let idx = match nodes.binary_search_by_key(first, |n| n.port) {
+        Ok(idx) => idx,
+        Err(idx) => {
+            let node = MstNode {
+                port: *first,
+                connector_id: None,
+                ports: Vec::new(),
+            };
+            nodes.insert(idx, node);
+            idx
+        }
+    };
+    let node = &mut nodes[idx];
+    if rest.is_empty() {
+        Some(node)
+    } else {
+        node_entry(&mut node.ports, rest)
+    }
+}
+
+fn collect_connectors(
+    root: ConnectorId,
+    nodes: &[MstNode],
+    ports: &mut Vec<u8>,
+    into: &mut Vec<(ConnectorId, ConnectorPath)>,
+) {
+    for node in nodes {
+        ports.push(node.port);
+        if let Some(connector_id) = node.connector_id {
+            into.push((
+                connector_id,
+                ConnectorPath {
+                    parent: root,
+                    ports: ports.clone(),
+                },
+            ));
+        }
+        collect_connectors(root, &node.ports, ports, into);
+        ports.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(s: &str) -> ConnectorPath {
+        ConnectorPath::parse(s.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let p = path("mst:57-1-8");
+        assert_eq!(p.parent, ConnectorId(57));
+        assert_eq!(p.ports, [1, 8]);
+        assert_eq!(p.depth(), 2);
+        assert_eq!(path("mst:57-1").depth(), 1);
+        // The kernel includes the string's NUL terminator in the blob.
+        assert_eq!(ConnectorPath::parse(b"mst:57-1-8\0"), Ok(p));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(ConnectorPath::parse(b"tbt:1-2"), Err(Error::NotSupported));
+        assert_eq!(ConnectorPath::parse(b""), Err(Error::NotSupported));
+        for raw in [
+            &b"mst:57"[..],
+            b"mst:",
+            b"mst:57-",
+            b"mst:57-256",
+            b"mst:57-1--2",
+            b"mst:x-1",
+            b"mst:57-\xff",
+        ] {
+            assert_eq!(ConnectorPath::parse(raw), Err(Error::Invalid), "{raw:?}");
+        }
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for s in ["mst:57-1", "mst:57-1-8", "mst:4294967295-255-0-3"] {
+            let p = path(s);
+            assert_eq!(alloc::format!("{p}"), s);
+            assert_eq!(path(&alloc::format!("{p}")), p);
+        }
+    }
+
+    #[test]
+    fn is_descendant_of() {
+        let hub = path("mst:57-1");
+        assert!(path("mst:57-1-8").is_descendant_of(&hub));
+        assert!(!hub.is_descendant_of(&hub));
+        assert!(!path("mst:57-2-8").is_descendant_of(&hub));
+        assert!(!path("mst:58-1-8").is_descendant_of(&hub));
+    }
+
+    #[test]
+    fn build_tree() {
+        // Two displays share the hub on port 1, and a daisy chain hangs off
+        // one of them. Port 2 has a display attached directly.
+        let connectors = [
+            (ConnectorId(103), path("mst:57-1-8-1")),
+            (ConnectorId(100), path("mst:57-2")),
+            (ConnectorId(102), path("mst:57-1-9")),
+            (ConnectorId(101), path("mst:57-1-8")),
+            (ConnectorId(200), path("mst:60-1")),
+        ];
+        let trees = MstTree::build(&connectors);
+        assert_eq!(trees.len(), 2);
+        assert_eq!(trees[0].root, ConnectorId(57));
+        assert_eq!(trees[1].root, ConnectorId(60));
+
+        let tree = &trees[0];
+        let ports: Vec<u8> = tree.ports.iter().map(|n| n.port).collect();
+        assert_eq!(ports, [1, 2]);
+        let hub = tree.node(&[1]).unwrap();
+        assert_eq!(hub.connector_id, None);
+        let hub_ports: Vec<u8> = hub.ports.iter().map(|n| n.port).collect();
+        assert_eq!(hub_ports, [8, 9]);
+        assert_eq!(
+            tree.node(&[1, 8, 1]).unwrap().connector_id,
+            Some(ConnectorId(103))
+        );
+        assert_eq!(tree.node(&[1, 7]), None);
+        assert_eq!(tree.node(&[]), None);
+
+        assert_eq!(
+            tree.connector_at(&path("mst:57-1-8")),
+            Some(ConnectorId(101))
+        );
+        assert_eq!(tree.connector_at(&path("mst:57-2")), Some(ConnectorId(100)));
+        assert_eq!(tree.connector_at(&path("mst:57-1")), None);
+        assert_eq!(tree.connector_at(&path("mst:60-1")), None);
+        assert_eq!(
+            trees[1].connector_at(&path("mst:60-1")),
+            Some(ConnectorId(200))
+        );
+
+        // Connectors are listed depth-first in port order.
+        let listed: Vec<_> = tree
+            .connectors()
+            .into_iter()
+            .map(|(id, p)| (id.0, alloc::format!("{p}")))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                (101, "mst:57-1-8".into()),
+                (103, "mst:57-1-8-1".into()),
+                (102, "mst:57-1-9".into()),
+                (100, "mst:57-2".into()),
+            ]
+        );
+    }
+}
@@ -218,6 +218,72 @@ impl<'card> ObjectPropMeta<'card> {
             return Ok(members);
         }
     }
+
+    /// Get the ids of the blobs associated with a blob-typed property.
+    ///
+    /// Only older kernels populate this list; current kernels always
+    /// report no blob ids here, because the blob for each object is
+    /// available as the property's value on that object instead. Use
+    /// [`Self::blob_value`] to retrieve the content of such a value.
+    pub fn blob_ids(&self) -> Result<Vec<BlobId>, crate::Error> {
+        if !matches!(self.property_type(), PropertyType::Blob) {
+            return Err(crate::Error::NotSupported);
+        }
+
+        let mut count = self.raw.count_enum_blobs() as usize;
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        loop {
+            // For blob properties the kernel uses the "enum blob" array to
+            // return the blob ids and the values array to return the
+            // corresponding blob lengths, both as u32.
+            let mut ids = crate::vec_with_capacity::<BlobId>(count)?;
+            let mut lengths = crate::vec_with_capacity::<u32>(count)?;
+
+            let mut tmp = crate::ioctl::DrmModeGetProperty::zeroed();
+            tmp.prop_id = self.raw.prop_id;
+            unsafe {
+                tmp.set_enum_blob_ptr(
+                    ids.as_mut_ptr() as *mut crate::ioctl::DrmModePropertyEnum,
+                    count as u32,
+                );
+                tmp.set_values_ptr(lengths.as_mut_ptr() as *mut u64, count as u32);
+            };
+
+            self.card
+                .ioctl(crate::ioctl::DRM_IOCTL_MODE_GETPROPERTY, &mut tmp)?;
+
+            let new_count = tmp.count_enum_blobs() as usize;
+            if new_count != count {
+                count = new_count;
+                continue;
+            }
+
+            // Safety: We confirmed above that the kernel generated the number
+            // of values we were expecting.
+            unsafe {
+                ids.set_len(count);
+            }
+            return Ok(ids);
+        }
+    }
+
+    /// Retrieve the content of the blob that a blob-typed property refers
+    /// to, given the property's value on some object.
+    ///
+    /// Returns `Ok(None)` if the value is zero, which represents the absence
+    /// of a blob.
+    pub fn blob_value(&self, value: u64) -> Result<Option<Vec<u8>>, crate::Error> {
+        if !matches!(self.property_type(), PropertyType::Blob) {
+            return Err(crate::Error::NotSupported);
+        }
+        if value == 0 {
+            return Ok(None);
+        }
+        let blob_id = BlobId(u32::try_from(value).map_err(|_| crate::Error::Invalid)?);
+        self.card.property_blob(blob_id).map(Some)
+    }
 }
 
 #[derive(Debug)]
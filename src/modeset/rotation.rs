@@ -0,0 +1,253 @@
+use alloc::boxed::Box;
+use core::ops::BitOr;
+
+use super::{AsRawPropertyValue, IntoRawPropertyValue, ObjectPropMeta, PropertyType};
+use crate::ioctl;
+use crate::result::Error;
+
+/// The value of a plane's `rotation` property, which rotates and reflects
+/// the plane's content as it's scanned out.
+///
+/// A valid value has exactly one of the `ROTATE_*` bits set, and can
+/// additionally have either or both of the `REFLECT_*` bits set. The
+/// rotation is counter-clockwise and is applied after any reflection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rotation(pub u32);
+
+impl Rotation {
+    pub const ROTATE_0: Self = Self(ioctl::DRM_MODE_ROTATE_0);
+    pub const ROTATE_90: Self = Self(ioctl::DRM_MODE_ROTATE_90);
+    pub const ROTATE_180: Self = Self(ioctl::DRM_MODE_ROTATE_180);
+    pub const ROTATE_270: Self = Self(ioctl::DRM_MODE_ROTATE_270);
+    /// Reflect the content along the x axis, so that it's mirrored
+    /// horizontally.
+    pub const REFLECT_X: Self = Self(ioctl::DRM_MODE_REFLECT_X);
+    /// Reflect the content along the y axis, so that it's mirrored
+    /// vertically.
+    pub const REFLECT_Y: Self = Self(ioctl::DRM_MODE_REFLECT_Y);
+    /// All of the `ROTATE_*` bits.
+    pub const ROTATE_MASK: Self = Self(ioctl::DRM_MODE_ROTATE_MASK);
+    /// All of the `REFLECT_*` bits.
+    pub const REFLECT_MASK: Self = Self(ioctl::DRM_MODE_REFLECT_MASK);
+
+    /// Returns the rotation for the given number of degrees
+    /// counter-clockwise, which must be a multiple of 90 less than 360.
+    pub const fn from_degrees(degrees: u32) -> Option<Self> {
+        match degrees {
+            0 => Some(Self::ROTATE_0),
+            90 => Some(Self::ROTATE_90),
+            180 => Some(Self::ROTATE_180),
+            270 => Some(Self::ROTATE_270),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of degrees of counter-clockwise rotation, or
+    /// `None` if the value doesn't have exactly one `ROTATE_*` bit set.
+    pub const fn degrees(self) -> Option<u32> {
+        match self.0 & ioctl::DRM_MODE_ROTATE_MASK {
+            ioctl::DRM_MODE_ROTATE_0 => Some(0),
+            ioctl::DRM_MODE_ROTATE_90 => Some(90),
+            ioctl::DRM_MODE_ROTATE_180 => Some(180),
+            ioctl::DRM_MODE_ROTATE_270 => Some(270),
+            _ => None,
+        }
+    }
+
+    /// Returns true if all of the bits in `other` are also set in `self`.
+    #[inline(always)]
+    pub const fn contains(self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+
+    /// Returns true if the value has exactly one `ROTATE_*` bit set and no
+    /// bits other than the `ROTATE_*` and `REFLECT_*` bits.
+    pub const fn is_valid(self) -> bool {
+        let known = ioctl::DRM_MODE_ROTATE_MASK | ioctl::DRM_MODE_REFLECT_MASK;
+        (self.0 & !known) == 0 && (self.0 & ioctl::DRM_MODE_ROTATE_MASK).is_power_of_two()
+    }
+
+    /// Returns the set of bits that a plane supports, given the values
+    /// of its `rotation` property's bitmask members.
+    ///
+    /// The kernel describes each member of a bitmask property by its bit
+    /// index rather than by its mask.
+    pub fn from_bit_indices(values: &[u64]) -> Self {
+        let bits = values
+            .iter()
+            .filter(|v| **v < 32)
+            .fold(0, |acc, v| acc | (1 << *v));
+        Self(bits)
+    }
+
+    /// Returns the set of bits that a plane supports, using the metadata
+    /// of its `rotation` property.
+    ///
+    /// Returns [`Error::NotSupported`] if the property isn't a bitmask.
+    pub fn from_property_meta(meta: &ObjectPropMeta) -> Result<Self, Error> {
+        if !matches!(meta.property_type(), PropertyType::Bitmask) {
+            return Err(Error::NotSupported);
+        }
+        Ok(Self::from_bit_indices(&meta.values()?))
+    }
+
+    /// Check the value against the set of bits that a plane supports,
+    /// as returned by [`Self::from_property_meta`].
+    ///
+    /// Returns [`Error::Invalid`] if the value isn't valid for any plane,
+    /// or [`Error::NotSupported`] if the plane doesn't support it.
+    pub fn validate(self, supported: Self) -> Result<Self, Error> {
+        if !self.is_valid() {
+            return Err(Error::Invalid);
+        }
+        if !supported.contains(self) {
+            return Err(Error::NotSupported);
+        }
+        Ok(self)
+    }
+
+    /// Returns an equivalent value that uses only supported bits, if the
+    /// value itself isn't supported.
+    ///
+    /// Rotating by 180 degrees is the same as reflecting along both axes,
+    /// so this can allow 180 degree rotation on planes that only support
+    /// reflection, and vice-versa. The result might still not be supported,
+    /// so use [`Self::validate`] to check it.
+    pub const fn simplify(self, supported: Self) -> Self {
+        if supported.contains(self) {
+            return self;
+        }
+        let flipped = self.0 ^ ioctl::DRM_MODE_REFLECT_MASK;
+        let rotate = match self.0 & ioctl::DRM_MODE_ROTATE_MASK {
+            ioctl::DRM_MODE_ROTATE_0 => ioctl::DRM_MODE_ROTATE_180,
+            ioctl::DRM_MODE_ROTATE_90 => ioctl::DRM_MODE_ROTATE_270,
+            ioctl::DRM_MODE_ROTATE_180 => ioctl::DRM_MODE_ROTATE_0,
+            ioctl::DRM_MODE_ROTATE_270 => ioctl::DRM_MODE_ROTATE_90,
+            _ => return self,
+        };
+        Self((flipped & !ioctl::DRM_MODE_ROTATE_MASK) | rotate)
+    }
+}
+
+impl Default for Rotation {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::ROTATE_0
+    }
+}
+
+impl BitOr for Rotation {
+    type Output = Self;
+
+    #[inline(always)]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl AsRawPropertyValue for Rotation {
+    #[inline(always)]
+    fn as_raw_property_value(&self) -> u64 {
+        self.0 as u64
+    }
+}
+
+impl IntoRawPropertyValue for Rotation {
+    #[inline(always)]
+    fn into_raw_property_value(self) -> (u64, Option<Box<dyn core::any::Any>>) {
+        (self.0 as u64, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_bit_indices() {
+        // As reported by a plane supporting all rotations and reflections.
+        assert_eq!(
+            Rotation::from_bit_indices(&[0, 1, 2, 3, 4, 5]),
+            Rotation::ROTATE_MASK | Rotation::REFLECT_MASK
+        );
+        assert_eq!(
+            Rotation::from_bit_indices(&[0, 2]),
+            Rotation::ROTATE_0 | Rotation::ROTATE_180
+        );
+        assert_eq!(Rotation::from_bit_indices(&[]), Rotation(0));
+        // Indices that don't fit are ignored.
+        assert_eq!(Rotation::from_bit_indices(&[0, 32, 64]), Rotation::ROTATE_0);
+    }
+
+    #[test]
+    fn degrees_and_validity() {
+        for degrees in [0, 90, 180, 270] {
+            let rotation = Rotation::from_degrees(degrees).unwrap();
+            assert_eq!(rotation.degrees(), Some(degrees));
+            assert!(rotation.is_valid());
+            assert!((rotation | Rotation::REFLECT_MASK).is_valid());
+        }
+        assert_eq!(Rotation::from_degrees(45), None);
+        assert!(!Rotation(0).is_valid());
+        assert!(!Rotation::REFLECT_X.is_valid());
+        assert!(!(Rotation::ROTATE_0 | Rotation::ROTATE_90).is_valid());
+        assert!(!Rotation(ioctl::DRM_MODE_ROTATE_0 | 1 << 6).is_valid());
+        assert_eq!((Rotation::ROTATE_0 | Rotation::ROTATE_90).degrees(), None);
+    }
+
+    #[test]
+    fn validate() {
+        let supported = Rotation::from_bit_indices(&[0, 2, 4]);
+        assert_eq!(
+            Rotation::ROTATE_180.validate(supported),
+            Ok(Rotation::ROTATE_180)
+        );
+        assert_eq!(
+            (Rotation::ROTATE_0 | Rotation::REFLECT_X).validate(supported),
+            Ok(Rotation::ROTATE_0 | Rotation::REFLECT_X)
+        );
+        // REFLECT_Y's bit isn't among the plane's values.
+        assert_eq!(
+            (Rotation::ROTATE_0 | Rotation::REFLECT_Y).validate(supported),
+            Err(Error::NotSupported)
+        );
+        assert_eq!(
+            Rotation::ROTATE_90.validate(supported),
+            Err(Error::NotSupported)
+        );
+        assert_eq!(Rotation(0).validate(supported), Err(Error::Invalid));
+    }
+
+    #[test]
+    fn simplify() {
+        let reflect_only = Rotation::ROTATE_0 | Rotation::REFLECT_MASK;
+        // 180 degrees with a reflection in x is the same as a reflection
+        // in y alone.
+        assert_eq!(
+            (Rotation::ROTATE_180 | Rotation::REFLECT_X).simplify(reflect_only),
+            Rotation::ROTATE_0 | Rotation::REFLECT_Y
+        );
+        assert_eq!(
+            Rotation::ROTATE_180.simplify(reflect_only),
+            Rotation::ROTATE_0 | Rotation::REFLECT_MASK
+        );
+
+        let rotate_only = Rotation::ROTATE_MASK;
+        assert_eq!(
+            (Rotation::ROTATE_0 | Rotation::REFLECT_MASK).simplify(rotate_only),
+            Rotation::ROTATE_180
+        );
+        assert_eq!(
+            (Rotation::ROTATE_90 | Rotation::REFLECT_Y).simplify(rotate_only),
+            Rotation::ROTATE_270 | Rotation::REFLECT_X
+        );
+
+        // Supported values are left alone.
+        assert_eq!(
+            Rotation::ROTATE_180.simplify(rotate_only),
+            Rotation::ROTATE_180
+        );
+        // As are values without a single rotation.
+        assert_eq!(Rotation(0).simplify(Rotation(0)), Rotation(0));
+    }
+}
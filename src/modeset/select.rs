@@ -0,0 +1,421 @@
+use core::cmp::Ordering;
+
+use super::{CardResources, ConnectorState, ModeInfo};
+
+/// A configurable policy for choosing one of a connector's modes.
+///
+/// The selector first rules out any modes that don't meet its hard
+/// constraints: interlacing, the maximum pixel clock, and the size limits.
+/// It then chooses from the remaining modes as follows:
+///
+/// 1. If a resolution was requested and any mode has that resolution, the
+///    mode at that resolution whose refresh rate is closest to the requested
+///    rate, treating all rates within the tolerance as equally close.
+/// 2. Otherwise, if a refresh rate was requested, only modes within the
+///    tolerance of that rate are considered, if there are any.
+/// 3. The preferred mode, if it's among the modes being considered.
+/// 4. The mode with the highest resolution, and then the highest refresh
+///    rate, as defined by [`ModeInfo::display_order`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModeSelector {
+    /// The requested resolution as `(width, height)`.
+    pub resolution: Option<(u16, u16)>,
+    /// The requested refresh rate in millihertz.
+    pub refresh_mhz: Option<u32>,
+    /// How far from [`Self::refresh_mhz`] a mode's refresh rate can be while
+    /// still counting as a match, in millihertz.
+    pub refresh_tolerance_mhz: u32,
+    pub allow_interlaced: bool,
+    pub max_pixel_clock_khz: Option<u32>,
+    /// The minimum size as `(width, height)`.
+    pub min_size: (u32, u32),
+    /// The maximum size as `(width, height)`.
+    pub max_size: Option<(u32, u32)>,
+}
+
+impl ModeSelector {
+    /// Returns a selector with no preferences or constraints, which selects
+    /// the preferred mode if there is one.
+    pub const fn new() -> Self {
+        Self {
+            resolution: None,
+            refresh_mhz: None,
+            refresh_tolerance_mhz: 0,
+            allow_interlaced: true,
+            max_pixel_clock_khz: None,
+            min_size: (0, 0),
+            max_size: None,
+        }
+    }
+
+    /// Returns the selector with a preference for the given resolution.
+    pub const fn with_resolution(mut self, width: u16, height: u16) -> Self {
+        self.resolution = Some((width, height));
+        self
+    }
+
+    /// Returns the selector with a preference for the given refresh rate,
+    /// treating any rate within `tolerance_mhz` of it as a match.
+    ///
+    /// For example, a target of `60_000` with a tolerance of `100` matches
+    /// both 60Hz and 59.94Hz modes.
+    pub const fn with_refresh_mhz(mut self, refresh_mhz: u32, tolerance_mhz: u32) -> Self {
+        self.refresh_mhz = Some(refresh_mhz);
+        self.refresh_tolerance_mhz = tolerance_mhz;
+        self
+    }
+
+    /// Returns the selector with interlaced modes excluded.
+    pub const fn without_interlaced(mut self) -> Self {
+        self.allow_interlaced = false;
+        self
+    }
+
+    /// Returns the selector with modes whose pixel clock exceeds the given
+    /// frequency excluded.
+    pub const fn with_max_pixel_clock_khz(mut self, khz: u32) -> Self {
+        self.max_pixel_clock_khz = Some(khz);
+        self
+    }
+
+    /// Returns the selector with modes that are outside of the card's
+    /// framebuffer size limits excluded.
+    pub const fn with_resources_limits(mut self, resources: &CardResources) -> Self {
+        self.min_size = (resources.min_width, resources.min_height);
+        self.max_size = Some((resources.max_width, resources.max_height));
+        self
+    }
+
+    /// Returns true if the mode meets all of the selector's constraints.
+    pub fn is_acceptable(&self, mode: &ModeInfo) -> bool {
+        let (width, height) = (mode.hdisplay as u32, mode.vdisplay as u32);
+        (self.allow_interlaced || !mode.is_interlaced())
+            && self.max_pixel_clock_khz.is_none_or(|max| mode.clock <= max)
+            && width >= self.min_size.0
+            && height >= self.min_size.1
+            && self
+                .max_size
+                .is_none_or(|(max_w, max_h)| width <= max_w && height <= max_h)
+    }
+
+    /// Choose a mode from the given list, or return `None` if none of the
+    /// modes are acceptable.
+    pub fn select<'a>(&self, modes: &'a [ModeInfo]) -> Option<&'a ModeInfo> {
+        let acceptable = || modes.iter().filter(|m| self.is_acceptable(m));
+
+        if let Some((width, height)) = self.resolution {
+            let best = acceptable()
+                .filter(|m| m.hdisplay == width && m.vdisplay == height)
+                .min_by(|a, b| {
+                    self.refresh_distance(a)
+                        .cmp(&self.refresh_distance(b))
+                        .then_with(|| Self::fallback_order(a, b))
+                });
+            if best.is_some() {
+                return best;
+            }
+        }
+
+        let refresh_matches = |m: &&ModeInfo| self.refresh_distance(m) == 0;
+        if self.refresh_mhz.is_some() && acceptable().any(|m| refresh_matches(&m)) {
+            acceptable()
+                .filter(refresh_matches)
+                .min_by(|a, b| Self::fallback_order(a, b))
+        } else {
+            acceptable().min_by(|a, b| Self::fallback_order(a, b))
+        }
+    }
+
+    /// Returns how far the mode's refresh rate is from the requested rate,
+    /// or zero if it's within the tolerance or no rate was requested.
+    fn refresh_distance(&self, mode: &ModeInfo) -> u32 {
+        let Some(target) = self.refresh_mhz else {
+            return 0;
+        };
+        let distance = mode.refresh_mhz().abs_diff(target);
+        if distance <= self.refresh_tolerance_mhz {
+            0
+        } else {
+            distance
+        }
+    }
+
+    fn fallback_order(a: &ModeInfo, b: &ModeInfo) -> Ordering {
+        b.is_preferred()
+            .cmp(&a.is_preferred())
+            .then_with(|| a.display_order(b))
+    }
+}
+
+impl Default for ModeSelector {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConnectorState {
+    /// Choose one of the connector's modes using the given selector.
+    #[inline]
+    pub fn select_mode(&self, selector: &ModeSelector) -> Option<&ModeInfo> {
+        selector.select(&self.modes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ioctl;
+    use alloc::vec::Vec;
+
+    fn mode(width: u16, height: u16, htotal: u16, vtotal: u16, clock: u32) -> ModeInfo {
+        let mut mode = ModeInfo {
+            name: Vec::new(),
+            clock,
+            hdisplay: width,
+            hsync_start: width + 8,
+            hsync_end: width + 40,
+            htotal,
+            hskew: 0,
+            vdisplay: height,
+            vsync_start: height + 3,
+            vsync_end: height + 8,
+            vtotal,
+            vscan: 0,
+            vrefresh: 0,
+            flags: 0,
+            typ: ioctl::DRM_MODE_TYPE_DRIVER,
+        };
+        mode.fill_vrefresh_and_name();
+        mode
+    }
+
+    fn preferred(mut mode: ModeInfo) -> ModeInfo {
+        mode.typ |= ioctl::DRM_MODE_TYPE_PREFERRED;
+        mode
+    }
+
+    fn interlaced(mut mode: ModeInfo) -> ModeInfo {
+        mode.flags |= ioctl::DRM_MODE_FLAG_INTERLACE;
+        mode.fill_vrefresh_and_name();
+        mode
+    }
+
+    const FHD_60: usize = 0;
+    const FHD_59_94: usize = 1;
+    const FHD_50: usize = 2;
+    const QHD_60: usize = 3;
+    const QHD_144: usize = 4;
+    const HD_60: usize = 5;
+    const FHD_60I: usize = 6;
+    const UHD_30: usize = 7;
+
+    /// A typical mode list, in no particular order and with no preferred
+    /// mode.
+    fn modes() -> Vec<ModeInfo> {
+        alloc::vec![
+            mode(1920, 1080, 2200, 1125, 148500),
+            mode(1920, 1080, 2200, 1125, 148352),
+            mode(1920, 1080, 2640, 1125, 148500),
+            mode(2560, 1440, 2720, 1481, 241500),
+            mode(2560, 1440, 2640, 1543, 586587),
+            mode(1280, 720, 1650, 750, 74250),
+            interlaced(mode(1920, 1080, 2200, 1125, 74250)),
+            mode(3840, 2160, 4400, 2250, 297000),
+        ]
+    }
+
+    fn with_preferred(idx: usize) -> Vec<ModeInfo> {
+        let mut modes = modes();
+        modes[idx] = preferred(modes[idx].clone());
+        modes
+    }
+
+    /// Returns the index of the selected mode in the list.
+    fn select(selector: &ModeSelector, modes: &[ModeInfo]) -> Option<usize> {
+        let selected = selector.select(modes)?;
+        modes.iter().position(|m| core::ptr::eq(m, selected))
+    }
+
+    #[test]
+    fn mode_list_refresh_rates() {
+        let modes = modes();
+        let refresh: Vec<u32> = modes.iter().map(ModeInfo::refresh_mhz).collect();
+        assert_eq!(
+            refresh,
+            [60000, 59940, 50000, 59951, 144000, 60000, 60000, 30000]
+        );
+    }
+
+    #[test]
+    fn exact_resolution() {
+        // Rule 1: the requested resolution wins over the preferred mode, and
+        // ties between refresh rates are broken by the fallback order.
+        let modes = with_preferred(QHD_144);
+        let selector = ModeSelector::new().with_resolution(1920, 1080);
+        assert_eq!(select(&selector, &modes), Some(FHD_60));
+
+        let selector = selector.with_refresh_mhz(50000, 0);
+        assert_eq!(select(&selector, &modes), Some(FHD_50));
+
+        // The closest refresh rate is chosen when none is within the
+        // tolerance.
+        let selector = ModeSelector::new()
+            .with_resolution(1920, 1080)
+            .with_refresh_mhz(55000, 0);
+        assert_eq!(select(&selector, &modes), Some(FHD_59_94));
+    }
+
+    #[test]
+    fn refresh_tolerance() {
+        // Rates within the tolerance are equally close, so the preferred
+        // mode wins among them.
+        let modes = with_preferred(FHD_59_94);
+        let selector = ModeSelector::new()
+            .with_resolution(1920, 1080)
+            .with_refresh_mhz(60000, 100);
+        assert_eq!(select(&selector, &modes), Some(FHD_59_94));
+
+        // Without a tolerance only the exact rate matches.
+        let selector = selector.with_refresh_mhz(60000, 0);
+        assert_eq!(select(&selector, &modes), Some(FHD_60));
+    }
+
+    #[test]
+    fn missing_resolution_falls_back() {
+        let modes = with_preferred(HD_60);
+        let selector = ModeSelector::new().with_resolution(1024, 768);
+        assert_eq!(select(&selector, &modes), Some(HD_60));
+
+        let selector = selector.with_refresh_mhz(144000, 500);
+        assert_eq!(select(&selector, &modes), Some(QHD_144));
+    }
+
+    #[test]
+    fn refresh_only() {
+        // Rule 2: only the modes within the tolerance are considered, and
+        // the preferred mode is chosen among them.
+        let modes = with_preferred(HD_60);
+        let selector = ModeSelector::new().with_refresh_mhz(144000, 500);
+        assert_eq!(select(&selector, &modes), Some(QHD_144));
+
+        let selector = ModeSelector::new().with_refresh_mhz(60000, 100);
+        assert_eq!(select(&selector, &modes), Some(HD_60));
+
+        // Without a preferred mode, the highest resolution within the
+        // tolerance wins.
+        assert_eq!(select(&selector, &self::modes()), Some(QHD_60));
+
+        // If no mode is within the tolerance, the rate is ignored.
+        let selector = ModeSelector::new().with_refresh_mhz(75000, 100);
+        assert_eq!(select(&selector, &modes), Some(HD_60));
+    }
+
+    #[test]
+    fn preferred_mode() {
+        // Rule 3.
+        let modes = with_preferred(HD_60);
+        assert_eq!(select(&ModeSelector::new(), &modes), Some(HD_60));
+
+        // Nothing is selected if no mode meets the constraints, not even the
+        // preferred mode.
+        let selector = ModeSelector::new().with_max_pixel_clock_khz(50000);
+        assert_eq!(select(&selector, &modes), None);
+
+        // A preferred mode that doesn't meet the constraints is ignored.
+        let selector = ModeSelector {
+            min_size: (1920, 1080),
+            ..ModeSelector::new()
+        };
+        assert_eq!(select(&selector, &modes), Some(UHD_30));
+    }
+
+    #[test]
+    fn highest_resolution_then_refresh() {
+        // Rule 4.
+        let modes = modes();
+        assert_eq!(select(&ModeSelector::new(), &modes), Some(UHD_30));
+
+        let selector = ModeSelector {
+            max_size: Some((2560, 1600)),
+            ..ModeSelector::new()
+        };
+        assert_eq!(select(&selector, &modes), Some(QHD_144));
+
+        let selector = ModeSelector {
+            max_size: Some((1920, 1200)),
+            ..ModeSelector::new()
+        };
+        assert_eq!(select(&selector, &modes), Some(FHD_60));
+    }
+
+    #[test]
+    fn interlace_exclusion() {
+        let pair = alloc::vec![
+            mode(1280, 720, 1650, 750, 74250),
+            preferred(interlaced(mode(1920, 1080, 2200, 1125, 74250))),
+        ];
+        let selector = ModeSelector::new().with_resolution(1920, 1080);
+        assert_eq!(select(&selector, &pair), Some(1));
+        assert_eq!(
+            select(&selector.clone().without_interlaced(), &pair),
+            Some(0)
+        );
+        assert_eq!(
+            select(&ModeSelector::new().without_interlaced(), &pair),
+            Some(0)
+        );
+
+        // Progressive modes are preferred over interlaced ones otherwise.
+        let modes = modes();
+        let selector = ModeSelector::new().with_resolution(1920, 1080);
+        assert_eq!(select(&selector, &modes), Some(FHD_60));
+        assert!(ModeSelector::new().is_acceptable(&modes[FHD_60I]));
+        assert!(!ModeSelector::new()
+            .without_interlaced()
+            .is_acceptable(&modes[FHD_60I]));
+    }
+
+    #[test]
+    fn pixel_clock_exclusion() {
+        let modes = modes();
+        let selector = ModeSelector::new().with_max_pixel_clock_khz(300000);
+        assert_eq!(select(&selector, &modes), Some(UHD_30));
+        let selector = ModeSelector::new()
+            .with_resolution(2560, 1440)
+            .with_max_pixel_clock_khz(300000);
+        assert_eq!(select(&selector, &modes), Some(QHD_60));
+        let selector = ModeSelector::new().with_max_pixel_clock_khz(200000);
+        assert_eq!(select(&selector, &modes), Some(FHD_60));
+    }
+
+    #[test]
+    fn size_exclusion() {
+        let resources = CardResources {
+            fb_ids: Vec::new(),
+            crtc_ids: Vec::new(),
+            connector_ids: Vec::new(),
+            encoder_ids: Vec::new(),
+            plane_ids: Vec::new(),
+            min_width: 1600,
+            max_width: 2560,
+            min_height: 900,
+            max_height: 1600,
+        };
+        let modes = modes();
+        let selector = ModeSelector::new().with_resources_limits(&resources);
+        assert_eq!(select(&selector, &modes), Some(QHD_144));
+        assert!(!selector.is_acceptable(&modes[HD_60]));
+        assert!(!selector.is_acceptable(&modes[UHD_30]));
+
+        let selector = selector.with_resolution(1280, 720);
+        assert_eq!(select(&selector, &modes), Some(QHD_144));
+
+        let selector = ModeSelector {
+            min_size: (4000, 0),
+            ..ModeSelector::new()
+        };
+        assert_eq!(select(&selector, &modes), None);
+        assert_eq!(select(&ModeSelector::new(), &[]), None);
+    }
+}
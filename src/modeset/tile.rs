@@ -0,0 +1,430 @@
+use alloc::vec::Vec;
+
+use super::{ConnectorId, ConnectorState, ModeInfo, ModeSelector};
+use crate::result::Error;
+
+/// The content of a connector's `TILE` property, which describes the
+/// connector's part of a display that is driven through multiple
+/// connectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileInfo {
+    /// Identifies the group of tiles that make up the same display. All of
+    /// the connectors of one display share the same id.
+    pub group_id: u32,
+    /// True if all of the tiles are in a single physical enclosure.
+    pub single_monitor: bool,
+    pub num_h_tiles: u32,
+    pub num_v_tiles: u32,
+    /// The horizontal position of this tile, counting from zero at the left.
+    pub h_location: u32,
+    /// The vertical position of this tile, counting from zero at the top.
+    pub v_location: u32,
+    pub tile_width: u32,
+    pub tile_height: u32,
+}
+
+impl TileInfo {
+    /// Parse the content of a `TILE` property blob, which the kernel
+    /// formats as eight colon-separated decimal integers.
+    ///
+    /// Returns [`Error::Invalid`] if the blob is malformed.
+    pub fn parse(raw: &[u8]) -> Result<Self, Error> {
+        let raw = raw.split(|b| *b == 0).next().unwrap_or(raw);
+        let s = core::str::from_utf8(raw).map_err(|_| Error::Invalid)?;
+        let mut fields = [0_u32; 8];
+        let mut parts = s.trim().split(':');
+        for field in fields.iter_mut() {
+            *field = parts
+                .next()
+                .and_then(|p| p.parse().ok())
+                .ok_or(Error::Invalid)?;
+        }
+        if parts.next().is_some() {
+            return Err(Error::Invalid);
+        }
+        Ok(Self {
+            group_id: fields[0],
+            single_monitor: fields[1] != 0,
+            num_h_tiles: fields[2],
+            num_v_tiles: fields[3],
+            h_location: fields[4],
+            v_location: fields[5],
+            tile_width: fields[6],
+            tile_height: fields[7],
+        })
+    }
+}
+
+/// A display that is driven through multiple connectors, each of which
+/// provides one tile of the full image.
+///
+/// To present one surface across the whole display, use a framebuffer of
+/// [`Self::width`] by [`Self::height`] and set up one CRTC per tile, using
+/// the tile's mode and scanning out from the tile's offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TiledMonitor {
+    pub group_id: u32,
+    pub single_monitor: bool,
+    pub num_h_tiles: u32,
+    pub num_v_tiles: u32,
+    /// The total width of all of the tiles, in pixels.
+    pub width: u32,
+    /// The total height of all of the tiles, in pixels.
+    pub height: u32,
+    /// The tiles that are currently present, ordered from left to right and
+    /// then from top to bottom.
+    pub tiles: Vec<MonitorTile>,
+}
+
+/// One connector's part of a [`TiledMonitor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonitorTile {
+    pub connector_id: ConnectorId,
+    pub info: TileInfo,
+    /// The horizontal offset of this tile within the whole display.
+    pub x: u32,
+    /// The vertical offset of this tile within the whole display.
+    pub y: u32,
+    /// The connector's mode that matches the tile size, if it has one.
+    ///
+    /// The modes of all tiles are chosen to have the same refresh rate
+    /// where possible.
+    pub mode: Option<ModeInfo>,
+}
+
+impl TiledMonitor {
+    /// Group connectors into tiled monitors, given each connector's state
+    /// and tile information.
+    ///
+    /// Connectors whose tile information isn't consistent with the other
+    /// tiles of the same group are ignored.
+    pub fn group(connectors: &[(ConnectorState, TileInfo)]) -> Vec<Self> {
+        let mut ret: Vec<Self> = Vec::new();
+        for (state, info) in connectors {
+            // DisplayID can't describe more than 64 tiles in either direction.
+            // The whole display must also fit in a u32 even if every tile
+            // were this tile's size, so that the layout can't overflow.
+            let valid = (1..=64).contains(&info.num_h_tiles)
+                && (1..=64).contains(&info.num_v_tiles)
+                && info.h_location < info.num_h_tiles
+                && info.v_location < info.num_v_tiles
+                && info.tile_width.checked_mul(info.num_h_tiles).is_some()
+                && info.tile_height.checked_mul(info.num_v_tiles).is_some();
+            if !valid {
+                continue;
+            }
+            let monitor = match ret.iter_mut().find(|m| m.group_id == info.group_id) {
+                Some(monitor) => monitor,
+                None => {
+                    ret.push(Self {
+                        group_id: info.group_id,
+                        single_monitor: info.single_monitor,
+                        num_h_tiles: info.num_h_tiles,
+                        num_v_tiles: info.num_v_tiles,
+                        width: 0,
+                        height: 0,
+                        tiles: Vec::new(),
+                    });
+                    ret.last_mut().unwrap()
+                }
+            };
+            let consistent = info.num_h_tiles == monitor.num_h_tiles
+                && info.num_v_tiles == monitor.num_v_tiles
+                && !monitor.tiles.iter().any(|t| {
+                    t.info.h_location == info.h_location && t.info.v_location == info.v_location
+                });
+            if !consistent {
+                continue;
+            }
+            monitor.tiles.push(MonitorTile {
+                connector_id: state.id,
+                info: *info,
+                x: 0,
+                y: 0,
+                mode: None,
+            });
+        }
+
+        for monitor in ret.iter_mut() {
+            monitor
+                .tiles
+                .sort_by_key(|t| (t.info.v_location, t.info.h_location));
+            monitor.layout();
+            monitor.choose_modes(connectors);
+        }
+        ret
+    }
+
+    /// Returns true if a connector is present for every tile.
+    pub fn is_complete(&self) -> bool {
+        self.tiles.len() as u64 == self.num_h_tiles as u64 * self.num_v_tiles as u64
+    }
+
+    /// Calculate the offset of each tile and the total size.
+    ///
+    /// [`Self::group`] only accepts tiles whose size multiplied by the
+    /// number of tiles fits in a u32, so none of these sums can overflow.
+    fn layout(&mut self) {
+        // Tiles are usually all the same size, but the sizes of each column
+        // and row are tracked separately in case they aren't. Columns and
+        // rows with no tiles present take the size of the first tile.
+        let default = self.tiles.first().map(|t| t.info);
+        let (default_w, default_h) = default.map_or((0, 0), |i| (i.tile_width, i.tile_height));
+        let col_width = |col: u32| {
+            self.tiles
+                .iter()
+                .find(|t| t.info.h_location == col)
+                .map_or(default_w, |t| t.info.tile_width)
+        };
+        let row_height = |row: u32| {
+            self.tiles
+                .iter()
+                .find(|t| t.info.v_location == row)
+                .map_or(default_h, |t| t.info.tile_height)
+        };
+        let offsets: Vec<(u32, u32)> = self
+            .tiles
+            .iter()
+            .map(|t| {
+                let x = (0..t.info.h_location).map(col_width).sum();
+                let y = (0..t.info.v_location).map(row_height).sum();
+                (x, y)
+            })
+            .collect();
+        self.width = (0..self.num_h_tiles).map(col_width).sum();
+        self.height = (0..self.num_v_tiles).map(row_height).sum();
+        for (tile, (x, y)) in self.tiles.iter_mut().zip(offsets) {
+            tile.x = x;
+            tile.y = y;
+        }
+    }
+
+    /// Choose a mode for each tile, matching the refresh rate of the mode
+    /// chosen for the first tile where possible.
+    fn choose_modes(&mut self, connectors: &[(ConnectorState, TileInfo)]) {
+        let mut refresh_mhz = None;
+        for tile in self.tiles.iter_mut() {
+            let Some((state, _)) = connectors.iter().find(|(s, _)| s.id == tile.connector_id)
+            else {
+                continue;
+            };
+            let (Ok(width), Ok(height)) = (
+                u16::try_from(tile.info.tile_width),
+                u16::try_from(tile.info.tile_height),
+            ) else {
+                continue;
+            };
+            let mut selector = ModeSelector::new().with_resolution(width, height);
+            if let Some(refresh_mhz) = refresh_mhz {
+                selector = selector.with_refresh_mhz(refresh_mhz, 100);
+            }
+            tile.mode = selector
+                .select(&state.modes)
+                .filter(|m| m.hdisplay == width && m.vdisplay == height)
+                .cloned();
+            if let Some(mode) = &tile.mode {
+                refresh_mhz.get_or_insert(mode.refresh_mhz());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ioctl;
+    use crate::modeset::{ConnectionState, ConnectorType, EncoderId, SubpixelType};
+
+    fn mode(width: u16, height: u16, clock: u32, preferred: bool) -> ModeInfo {
+        let mut mode = ModeInfo {
+            name: Vec::new(),
+            clock,
+            hdisplay: width,
+            hsync_start: width + 8,
+            hsync_end: width + 40,
+            htotal: width + 80,
+            hskew: 0,
+            vdisplay: height,
+            vsync_start: height + 3,
+            vsync_end: height + 8,
+            vtotal: height + 62,
+            vscan: 0,
+            vrefresh: 0,
+            flags: 0,
+            typ: ioctl::DRM_MODE_TYPE_DRIVER,
+        };
+        if preferred {
+            mode.typ |= ioctl::DRM_MODE_TYPE_PREFERRED;
+        }
+        mode.fill_vrefresh_and_name();
+        mode
+    }
+
+    fn connector(id: u32, modes: Vec<ModeInfo>) -> ConnectorState {
+        ConnectorState {
+            id: ConnectorId(id),
+            current_encoder_id: EncoderId(0),
+            connector_type: ConnectorType::DisplayPort,
+            connector_type_id: id,
+            connection_state: ConnectionState::Connected,
+            width_mm: 0,
+            height_mm: 0,
+            subpixel_type: SubpixelType::Unknown,
+            modes,
+            props: Vec::new(),
+            available_encoder_ids: Vec::new(),
+        }
+    }
+
+    fn tile(h_location: u32, v_location: u32) -> TileInfo {
+        TileInfo {
+            group_id: 1,
+            single_monitor: true,
+            num_h_tiles: 2,
+            num_v_tiles: 1,
+            h_location,
+            v_location,
+            tile_width: 2560,
+            tile_height: 2880,
+        }
+    }
+
+    #[test]
+    fn parse() {
+        // The kernel includes the string's NUL terminator in the blob.
+        let info = TileInfo::parse(b"1:1:2:1:1:0:2560:2880\0").unwrap();
+        assert_eq!(
+            info,
+            TileInfo {
+                group_id: 1,
+                single_monitor: true,
+                num_h_tiles: 2,
+                num_v_tiles: 1,
+                h_location: 1,
+                v_location: 0,
+                tile_width: 2560,
+                tile_height: 2880,
+            }
+        );
+        assert_eq!(TileInfo::parse(b"1:1:2:1:1:0:2560:2880"), Ok(info));
+        assert_eq!(TileInfo::parse(b"1:1:2:1:1:0:2560:2880\0junk"), Ok(info));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for raw in [
+            &b""[..],
+            b"1:1:2:1:1:0:2560",
+            b"1:1:2:1:1:0:2560:2880:1",
+            b"1:1:2:1:one:0:2560:2880",
+            b"1:1:2:1:-1:0:2560:2880",
+            b"1:1:2:1:1:0:2560:4294967296",
+            b"1:1:2:1:\xff:0:2560:2880",
+        ] {
+            assert_eq!(TileInfo::parse(raw), Err(Error::Invalid), "{raw:?}");
+        }
+    }
+
+    #[test]
+    fn group_2x1() {
+        // Both connectors can drive 60Hz and 30Hz, but the right half
+        // prefers 30Hz. It should still use 60Hz to match the left half.
+        let hz60 = mode(2560, 2880, 483_000, false);
+        let hz30 = mode(2560, 2880, 241_500, false);
+        let hz30_preferred = mode(2560, 2880, 241_500, true);
+        let connectors = [
+            (
+                connector(11, alloc::vec![hz30_preferred, hz60.clone()]),
+                tile(1, 0),
+            ),
+            (
+                connector(10, alloc::vec![hz60.clone(), hz30.clone()]),
+                tile(0, 0),
+            ),
+            // Connectors from another display aren't included.
+            (
+                connector(12, alloc::vec![hz60.clone()]),
+                TileInfo {
+                    group_id: 2,
+                    num_h_tiles: 1,
+                    h_location: 0,
+                    ..tile(0, 0)
+                },
+            ),
+        ];
+        let monitors = TiledMonitor::group(&connectors);
+        assert_eq!(monitors.len(), 2);
+
+        let monitor = &monitors[0];
+        assert_eq!(monitor.group_id, 1);
+        assert_eq!((monitor.width, monitor.height), (5120, 2880));
+        assert!(monitor.is_complete());
+        let tiles: Vec<_> = monitor
+            .tiles
+            .iter()
+            .map(|t| (t.connector_id, t.x, t.y))
+            .collect();
+        assert_eq!(tiles, [(ConnectorId(10), 0, 0), (ConnectorId(11), 2560, 0)]);
+        for tile in &monitor.tiles {
+            assert_eq!(tile.mode.as_ref(), Some(&hz60));
+        }
+
+        assert_eq!(monitors[1].group_id, 2);
+        assert_eq!(monitors[1].width, 2560);
+    }
+
+    #[test]
+    fn group_incomplete() {
+        // A missing tile still takes up space in the layout.
+        let connectors = [(connector(11, Vec::new()), tile(1, 0))];
+        let monitors = TiledMonitor::group(&connectors);
+        assert_eq!(monitors.len(), 1);
+        assert!(!monitors[0].is_complete());
+        assert_eq!(monitors[0].width, 5120);
+        assert_eq!(monitors[0].tiles[0].x, 2560);
+        // There's no mode of the right size.
+        assert_eq!(monitors[0].tiles[0].mode, None);
+    }
+
+    #[test]
+    fn group_rejects_inconsistent_tiles() {
+        let connectors = [
+            (connector(10, Vec::new()), tile(0, 0)),
+            // The same location as the first tile.
+            (connector(11, Vec::new()), tile(0, 0)),
+            // Locations outside of the grid.
+            (connector(12, Vec::new()), tile(2, 0)),
+            (connector(13, Vec::new()), tile(0, 1)),
+            // A different number of tiles than the rest of the group.
+            (
+                connector(14, Vec::new()),
+                TileInfo {
+                    num_h_tiles: 3,
+                    ..tile(1, 0)
+                },
+            ),
+            // Sizes that would overflow the total size.
+            (
+                connector(15, Vec::new()),
+                TileInfo {
+                    group_id: 2,
+                    tile_width: u32::MAX / 2 + 1,
+                    ..tile(0, 0)
+                },
+            ),
+            (
+                connector(16, Vec::new()),
+                TileInfo {
+                    group_id: 3,
+                    num_v_tiles: 64,
+                    tile_height: u32::MAX / 63,
+                    ..tile(0, 0)
+                },
+            ),
+        ];
+        let monitors = TiledMonitor::group(&connectors);
+        assert_eq!(monitors.len(), 1);
+        let ids: Vec<_> = monitors[0].tiles.iter().map(|t| t.connector_id).collect();
+        assert_eq!(ids, [ConnectorId(10)]);
+    }
+}
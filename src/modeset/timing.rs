@@ -0,0 +1,499 @@
+use alloc::vec::Vec;
+
+use super::ModeInfo;
+use crate::ioctl;
+use crate::math::floor;
+
+/// The blanking style used when generating a mode with
+/// [`ModeInfo::cvt`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CvtBlanking {
+    /// Standard CRT-style blanking.
+    Standard,
+    /// Reduced blanking from CVT 1.1, suitable for most digital displays.
+    ReducedV1,
+    /// Reduced blanking from CVT 1.2, which uses an even shorter horizontal
+    /// blanking interval and a finer pixel clock step.
+    ReducedV2,
+    /// Reduced blanking from CVT 2.0, using its default horizontal blanking
+    /// and no additional vertical blanking. The width is rounded down to a
+    /// multiple of eight pixels and, unlike [`CvtBlanking::ReducedV2`], the
+    /// pixel clock is rounded up so that the refresh rate is never lower
+    /// than requested.
+    ReducedV3,
+}
+
+/// The minimum time for the vertical sync and back porch in CVT and GTF,
+/// in microseconds.
+const MIN_VSYNC_BP_US: f64 = 550.0;
+/// The minimum vertical blanking time for CVT reduced blanking, in
+/// microseconds.
+const RB_MIN_V_BLANK_US: f64 = 460.0;
+/// The size of a character cell in pixels, which horizontal timings are
+/// aligned to.
+const CELL_GRAN: f64 = 8.0;
+
+// Parameters of the blanking formula shared by standard CVT and GTF, which
+// give C' = 30 and M' = 300.
+const C_PRIME: f64 = (40.0 - 20.0) * 128.0 / 256.0 + 20.0;
+const M_PRIME: f64 = 128.0 / 256.0 * 600.0;
+
+impl ModeInfo {
+    /// Generate a mode with the given resolution and refresh rate using the
+    /// VESA Coordinated Video Timings standard.
+    ///
+    /// For every blanking style except reduced blanking v2 the width is
+    /// rounded down to a multiple of eight pixels, as the standard requires. The
+    /// resulting mode has the type `DRM_MODE_TYPE_USERDEF`. Returns `None`
+    /// if any of the arguments is zero or the timings don't fit in the
+    /// mode's fields.
+    pub fn cvt(width: u16, height: u16, refresh_hz: u32, blanking: CvtBlanking) -> Option<Self> {
+        if width == 0 || height == 0 || refresh_hz == 0 {
+            return None;
+        }
+        let v_field_rate = refresh_hz as f64;
+        let v_lines = height as f64;
+        let h_pixels = match blanking {
+            CvtBlanking::Standard | CvtBlanking::ReducedV1 | CvtBlanking::ReducedV3 => {
+                floor(width as f64 / CELL_GRAN) * CELL_GRAN
+            }
+            CvtBlanking::ReducedV2 => width as f64,
+        };
+        const V_FRONT_PORCH: f64 = 3.0;
+        const MIN_V_BPORCH: f64 = 6.0;
+
+        let t = match blanking {
+            CvtBlanking::Standard => {
+                let v_sync = cvt_vsync_width(h_pixels as u32, height as u32) as f64;
+                let h_period_est =
+                    (1_000_000.0 / v_field_rate - MIN_VSYNC_BP_US) / (v_lines + V_FRONT_PORCH);
+                let v_sync_bp =
+                    (floor(MIN_VSYNC_BP_US / h_period_est) + 1.0).max(v_sync + MIN_V_BPORCH);
+                let v_total = v_lines + v_sync_bp + V_FRONT_PORCH;
+
+                let duty_cycle = (C_PRIME - M_PRIME * h_period_est / 1000.0).max(20.0);
+                let h_blank =
+                    floor(h_pixels * duty_cycle / (100.0 - duty_cycle) / (2.0 * CELL_GRAN))
+                        * (2.0 * CELL_GRAN);
+                let h_total = h_pixels + h_blank;
+                let clock_mhz = 0.25 * floor(h_total / h_period_est / 0.25);
+                let h_sync = floor(0.08 * h_total / CELL_GRAN) * CELL_GRAN;
+                let h_front_porch = h_blank - h_sync - h_blank / 2.0;
+                RawTimings {
+                    h_pixels,
+                    h_front_porch,
+                    h_sync,
+                    h_total,
+                    v_lines,
+                    v_front_porch: V_FRONT_PORCH,
+                    v_sync,
+                    v_total,
+                    clock_khz: clock_mhz * 1000.0,
+                    hsync_positive: false,
+                    vsync_positive: true,
+                }
+            }
+            CvtBlanking::ReducedV1 => {
+                let v_sync = cvt_vsync_width(h_pixels as u32, height as u32) as f64;
+                let h_period_est = (1_000_000.0 / v_field_rate - RB_MIN_V_BLANK_US) / v_lines;
+                let vbi_lines = (floor(RB_MIN_V_BLANK_US / h_period_est) + 1.0)
+                    .max(V_FRONT_PORCH + v_sync + MIN_V_BPORCH);
+                let v_total = v_lines + vbi_lines;
+                let h_total = h_pixels + 160.0;
+                let clock_mhz = 0.25 * floor(v_field_rate * v_total * h_total / 1_000_000.0 / 0.25);
+                RawTimings {
+                    h_pixels,
+                    h_front_porch: 48.0,
+                    h_sync: 32.0,
+                    h_total,
+                    v_lines,
+                    v_front_porch: V_FRONT_PORCH,
+                    v_sync,
+                    v_total,
+                    clock_khz: clock_mhz * 1000.0,
+                    hsync_positive: true,
+                    vsync_positive: false,
+                }
+            }
+            CvtBlanking::ReducedV2 | CvtBlanking::ReducedV3 => {
+                const V_SYNC: f64 = 8.0;
+                const MIN_V_FRONT_PORCH: f64 = 1.0;
+                let h_period_est = (1_000_000.0 / v_field_rate - RB_MIN_V_BLANK_US) / v_lines;
+                let vbi_lines = (floor(RB_MIN_V_BLANK_US / h_period_est) + 1.0)
+                    .max(MIN_V_FRONT_PORCH + V_SYNC + MIN_V_BPORCH);
+                let v_total = v_lines + vbi_lines;
+                let h_total = h_pixels + 80.0;
+                let clock_khz = v_field_rate * v_total * h_total / 1000.0;
+                let clock_khz = if blanking == CvtBlanking::ReducedV3 {
+                    -floor(-clock_khz)
+                } else {
+                    floor(clock_khz)
+                };
+                RawTimings {
+                    h_pixels,
+                    h_front_porch: 8.0,
+                    h_sync: 32.0,
+                    h_total,
+                    v_lines,
+                    v_front_porch: vbi_lines - V_SYNC - MIN_V_BPORCH,
+                    v_sync: V_SYNC,
+                    v_total,
+                    clock_khz,
+                    hsync_positive: true,
+                    vsync_positive: false,
+                }
+            }
+        };
+        t.to_mode_info()
+    }
+
+    /// Generate a mode with the given resolution and refresh rate using the
+    /// VESA Generalized Timing Formula with its default parameters.
+    ///
+    /// The width is rounded to the nearest multiple of eight pixels, as the
+    /// standard requires. The resulting mode has the type
+    /// `DRM_MODE_TYPE_USERDEF`. Returns `None` if any of the arguments is
+    /// zero or the timings don't fit in the mode's fields.
+    pub fn gtf(width: u16, height: u16, refresh_hz: u32) -> Option<Self> {
+        if width == 0 || height == 0 || refresh_hz == 0 {
+            return None;
+        }
+        const MIN_PORCH: f64 = 1.0;
+        const V_SYNC: f64 = 3.0;
+        let v_field_rate = refresh_hz as f64;
+        let v_lines = height as f64;
+        let h_pixels = round(width as f64 / CELL_GRAN) * CELL_GRAN;
+
+        let h_period_est = (1_000_000.0 / v_field_rate - MIN_VSYNC_BP_US) / (v_lines + MIN_PORCH);
+        let v_sync_bp = round(MIN_VSYNC_BP_US / h_period_est);
+        let v_total = v_lines + v_sync_bp + MIN_PORCH;
+        let v_field_rate_est = 1_000_000.0 / h_period_est / v_total;
+        let h_period = h_period_est / (v_field_rate / v_field_rate_est);
+
+        let duty_cycle = C_PRIME - M_PRIME * h_period / 1000.0;
+        let h_blank = round(h_pixels * duty_cycle / (100.0 - duty_cycle) / (2.0 * CELL_GRAN))
+            * (2.0 * CELL_GRAN);
+        let h_total = h_pixels + h_blank;
+        let h_sync = round(0.08 * h_total / CELL_GRAN) * CELL_GRAN;
+        RawTimings {
+            h_pixels,
+            h_front_porch: h_blank / 2.0 - h_sync,
+            h_sync,
+            h_total,
+            v_lines,
+            v_front_porch: MIN_PORCH,
+            v_sync: V_SYNC,
+            v_total,
+            clock_khz: floor(h_total / h_period * 1000.0),
+            hsync_positive: false,
+            vsync_positive: true,
+        }
+        .to_mode_info()
+    }
+}
+
+/// The result of a timing formula, before conversion to a [`ModeInfo`].
+struct RawTimings {
+    h_pixels: f64,
+    h_front_porch: f64,
+    h_sync: f64,
+    h_total: f64,
+    v_lines: f64,
+    v_front_porch: f64,
+    v_sync: f64,
+    v_total: f64,
+    clock_khz: f64,
+    hsync_positive: bool,
+    vsync_positive: bool,
+}
+
+impl RawTimings {
+    fn to_mode_info(&self) -> Option<ModeInfo> {
+        let field = |v: f64| -> Option<u16> {
+            if (0.0..=u16::MAX as f64).contains(&v) {
+                Some(v as u16)
+            } else {
+                None
+            }
+        };
+        let hsync_start = self.h_pixels + self.h_front_porch;
+        let vsync_start = self.v_lines + self.v_front_porch;
+        if !(1.0..=u32::MAX as f64).contains(&self.clock_khz) {
+            return None;
+        }
+        let mut mode = ModeInfo {
+            name: Vec::new(),
+            clock: self.clock_khz as u32,
+            hdisplay: field(self.h_pixels)?,
+            hsync_start: field(hsync_start)?,
+            hsync_end: field(hsync_start + self.h_sync)?,
+            htotal: field(self.h_total)?,
+            hskew: 0,
+            vdisplay: field(self.v_lines)?,
+            vsync_start: field(vsync_start)?,
+            vsync_end: field(vsync_start + self.v_sync)?,
+            vtotal: field(self.v_total)?,
+            vscan: 0,
+            vrefresh: 0,
+            flags: 0,
+            typ: ioctl::DRM_MODE_TYPE_USERDEF,
+        };
+        mode.flags |= if self.hsync_positive {
+            ioctl::DRM_MODE_FLAG_PHSYNC
+        } else {
+            ioctl::DRM_MODE_FLAG_NHSYNC
+        };
+        mode.flags |= if self.vsync_positive {
+            ioctl::DRM_MODE_FLAG_PVSYNC
+        } else {
+            ioctl::DRM_MODE_FLAG_NVSYNC
+        };
+        mode.fill_vrefresh_and_name();
+        Some(mode)
+    }
+}
+
+/// Returns the CVT vertical sync width, which encodes the aspect ratio.
+fn cvt_vsync_width(width: u32, height: u32) -> u32 {
+    if height.is_multiple_of(3) && height * 4 / 3 == width {
+        4
+    } else if height.is_multiple_of(9) && height * 16 / 9 == width {
+        5
+    } else if height.is_multiple_of(10) && height * 16 / 10 == width {
+        6
+    } else if (height.is_multiple_of(4) && height * 5 / 4 == width)
+        || (height.is_multiple_of(9) && height * 15 / 9 == width)
+    {
+        7
+    } else {
+        10
+    }
+}
+
+fn round(v: f64) -> f64 {
+    floor(v + 0.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modeset::SyncPolarity;
+
+    /// Returns the mode's pixel clock and timings as
+    /// `(clock, [hdisplay, hsync_start, hsync_end, htotal], [vdisplay, vsync_start, vsync_end, vtotal])`.
+    fn timings(mode: &ModeInfo) -> (u32, [u16; 4], [u16; 4]) {
+        (
+            mode.clock,
+            [mode.hdisplay, mode.hsync_start, mode.hsync_end, mode.htotal],
+            [mode.vdisplay, mode.vsync_start, mode.vsync_end, mode.vtotal],
+        )
+    }
+
+    #[test]
+    fn cvt_standard() {
+        // Rows from the VESA DMT standard that are defined using CVT, and
+        // the CVT 1.2 spreadsheet's result for 1920x1080.
+        let cases = [
+            (
+                1280,
+                800,
+                83500,
+                [1280, 1352, 1480, 1680],
+                [800, 803, 809, 831],
+            ),
+            (
+                1440,
+                900,
+                106500,
+                [1440, 1520, 1672, 1904],
+                [900, 903, 909, 934],
+            ),
+            (
+                1920,
+                1080,
+                173000,
+                [1920, 2048, 2248, 2576],
+                [1080, 1083, 1088, 1120],
+            ),
+        ];
+        for (w, h, clock, htimings, vtimings) in cases {
+            let mode = ModeInfo::cvt(w, h, 60, CvtBlanking::Standard).unwrap();
+            assert_eq!(timings(&mode), (clock, htimings, vtimings), "{w}x{h}");
+            assert_eq!(mode.hsync_polarity(), Some(SyncPolarity::Negative));
+            assert_eq!(mode.vsync_polarity(), Some(SyncPolarity::Positive));
+            assert_eq!(mode.vrefresh, 60);
+        }
+    }
+
+    #[test]
+    fn cvt_reduced_v1() {
+        // Rows from the VESA DMT standard that are defined using CVT
+        // reduced blanking.
+        let cases = [
+            (
+                1280,
+                800,
+                71000,
+                [1280, 1328, 1360, 1440],
+                [800, 803, 809, 823],
+            ),
+            (
+                1920,
+                1080,
+                138500,
+                [1920, 1968, 2000, 2080],
+                [1080, 1083, 1088, 1111],
+            ),
+            (
+                1920,
+                1200,
+                154000,
+                [1920, 1968, 2000, 2080],
+                [1200, 1203, 1209, 1235],
+            ),
+            (
+                2560,
+                1600,
+                268500,
+                [2560, 2608, 2640, 2720],
+                [1600, 1603, 1609, 1646],
+            ),
+        ];
+        for (w, h, clock, htimings, vtimings) in cases {
+            let mode = ModeInfo::cvt(w, h, 60, CvtBlanking::ReducedV1).unwrap();
+            assert_eq!(timings(&mode), (clock, htimings, vtimings), "{w}x{h}");
+            assert_eq!(mode.hsync_polarity(), Some(SyncPolarity::Positive));
+            assert_eq!(mode.vsync_polarity(), Some(SyncPolarity::Negative));
+        }
+    }
+
+    #[test]
+    fn cvt_reduced_v2() {
+        // The commonly published CVT-RBv2 timings for these modes, which are
+        // also what the CVT 1.2 spreadsheet gives.
+        let cases = [
+            (
+                1920,
+                1080,
+                133320,
+                [1920, 1928, 1960, 2000],
+                [1080, 1097, 1105, 1111],
+            ),
+            (
+                3840,
+                2160,
+                522614,
+                [3840, 3848, 3880, 3920],
+                [2160, 2208, 2216, 2222],
+            ),
+        ];
+        for (w, h, clock, htimings, vtimings) in cases {
+            let mode = ModeInfo::cvt(w, h, 60, CvtBlanking::ReducedV2).unwrap();
+            assert_eq!(timings(&mode), (clock, htimings, vtimings), "{w}x{h}");
+            assert_eq!(mode.hsync_polarity(), Some(SyncPolarity::Positive));
+            assert_eq!(mode.vsync_polarity(), Some(SyncPolarity::Negative));
+        }
+        // Reduced blanking v2 doesn't round the width.
+        let mode = ModeInfo::cvt(1366, 768, 60, CvtBlanking::ReducedV2).unwrap();
+        assert_eq!(mode.hdisplay, 1366);
+    }
+
+    #[test]
+    fn cvt_reduced_v3() {
+        // Worked by hand from the CVT 2.0 reduced blanking v3 equations,
+        // with the default 80 pixel horizontal blanking and no additional
+        // vertical blanking, rather than taken from this implementation:
+        //
+        //   h_period_est = (1e6 / refresh - 460) / height
+        //   vbi_lines = floor(460 / h_period_est) + 1
+        //   clock = ceil(refresh * (height + vbi_lines) * (width + 80) / 1000)
+        //
+        // which gives these intermediate values:
+        //
+        //   1920x1080@60:  7.5031us, 31 lines, 133320.0kHz
+        //   3840x2160@60:  7.5031us, 62 lines, 522614.4kHz
+        //   2560x1440@144: 4.5031us, 103 lines, 586586.9kHz
+        //   1360x768@60:   21.102us, 22 lines, 68256.0kHz
+        //
+        // The vertical front porch is the rest of the blanking after the
+        // 8 line sync and 6 line back porch.
+        let cases = [
+            (
+                1920,
+                1080,
+                60,
+                133320,
+                [1920, 1928, 1960, 2000],
+                [1080, 1097, 1105, 1111],
+            ),
+            (
+                3840,
+                2160,
+                60,
+                522615,
+                [3840, 3848, 3880, 3920],
+                [2160, 2208, 2216, 2222],
+            ),
+            (
+                2560,
+                1440,
+                144,
+                586587,
+                [2560, 2568, 2600, 2640],
+                [1440, 1529, 1537, 1543],
+            ),
+            // The width is rounded down to a multiple of eight pixels.
+            (
+                1366,
+                768,
+                60,
+                68256,
+                [1360, 1368, 1400, 1440],
+                [768, 776, 784, 790],
+            ),
+        ];
+        for (w, h, refresh, clock, htimings, vtimings) in cases {
+            let mode = ModeInfo::cvt(w, h, refresh, CvtBlanking::ReducedV3).unwrap();
+            assert_eq!(timings(&mode), (clock, htimings, vtimings), "{w}x{h}");
+            assert_eq!(mode.hsync_polarity(), Some(SyncPolarity::Positive));
+            assert_eq!(mode.vsync_polarity(), Some(SyncPolarity::Negative));
+        }
+        // The clock is rounded up where reduced blanking v2 rounds it down,
+        // so the refresh rate never falls below the requested one.
+        let v2 = ModeInfo::cvt(2560, 1440, 144, CvtBlanking::ReducedV2).unwrap();
+        assert_eq!(v2.clock, 586586);
+    }
+
+    #[test]
+    fn gtf() {
+        // Results of the reference GTF spreadsheet, as also produced by
+        // the X.Org `gtf` tool, which gives the clock to the nearest 10kHz.
+        let cases = [
+            (
+                1920,
+                1080,
+                17280,
+                [1920, 2040, 2248, 2576],
+                [1080, 1081, 1084, 1118],
+            ),
+            (640, 480, 2386, [640, 656, 720, 800], [480, 481, 484, 497]),
+        ];
+        for (w, h, clock_10khz, htimings, vtimings) in cases {
+            let mode = ModeInfo::gtf(w, h, 60).unwrap();
+            let (clock, actual_h, actual_v) = timings(&mode);
+            assert_eq!((clock + 5) / 10, clock_10khz, "{w}x{h}");
+            assert_eq!((actual_h, actual_v), (htimings, vtimings), "{w}x{h}");
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        assert_eq!(ModeInfo::cvt(0, 1080, 60, CvtBlanking::Standard), None);
+        assert_eq!(ModeInfo::cvt(1920, 1080, 0, CvtBlanking::ReducedV1), None);
+        assert_eq!(ModeInfo::gtf(1920, 0, 60), None);
+        assert_eq!(
+            ModeInfo::cvt(65535, 65535, 240, CvtBlanking::Standard),
+            None
+        );
+    }
+}